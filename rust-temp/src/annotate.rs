@@ -0,0 +1,625 @@
+//! Finding temperatures in prose, like "bake at 350°F" or "lows of -5 C",
+//! and writing their conversions next to them.
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    format::NumberFormat,
+    locale::Locale,
+    temperature::{Scale, Temperature},
+};
+
+/// Settings for annotating text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotateOptions {
+    /// What each mention is replaced with.
+    pub template: Template,
+    /// The scale to convert to, or `None` for each mention's
+    /// [counterpart](Scale::counterpart). Mentions already on this scale
+    /// are left alone.
+    pub to: Option<Scale>,
+    /// How converted values are written. Numbers in the text are also read
+    /// with the format's locale, so "36,6 °C" is found in German.
+    pub number_format: NumberFormat,
+}
+
+impl AnnotateOptions {
+    pub fn new(number_format: NumberFormat) -> Self {
+        AnnotateOptions {
+            template: Template::default(),
+            to: None,
+            number_format,
+        }
+    }
+}
+
+/// A temperature found in the text and what it is to be replaced with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    /// Byte offset of the mention's first byte.
+    pub start: usize,
+    /// Byte offset just past the mention's last byte.
+    pub end: usize,
+    /// The mention as written, e.g. "350°F".
+    pub original: String,
+    /// The template filled in for the mention, e.g. "350°F (177°C)".
+    pub replacement: String,
+}
+
+/// Reasons a template could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A `{name}` that isn't one of the placeholders.
+    UnknownPlaceholder(String),
+    /// A `{` without its `}`, or a `}` without its `{`. Literal braces are
+    /// written `{{` and `}}`.
+    UnmatchedBrace,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::UnknownPlaceholder(name) => write!(
+                f,
+                "Unknown placeholder {{{}}}. Expected {{original}}, {{converted}}, {{value}}, {{symbol}} or {{scale}}.",
+                name
+            ),
+            TemplateError::UnmatchedBrace => {
+                f.write_str("Unmatched brace in template. Write {{ and }} for literal braces.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    Text(String),
+    /// The mention as written.
+    Original,
+    /// The converted value with its symbol, e.g. "177°C".
+    Converted,
+    /// The converted value alone, e.g. "177".
+    Value,
+    /// The symbol of the scale converted to, e.g. "°C".
+    Symbol,
+    /// The name of the scale converted to, e.g. "celsius".
+    Scale,
+}
+
+/// What each mention is replaced with, e.g. the default
+/// `{original} ({converted})` turns "350°F" into "350°F (177°C)".
+///
+/// The placeholders are `{original}`, `{converted}`, `{value}`, `{symbol}`
+/// and `{scale}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    pieces: Vec<Piece>,
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        Template {
+            pieces: vec![
+                Piece::Original,
+                Piece::Text(" (".into()),
+                Piece::Converted,
+                Piece::Text(")".into()),
+            ],
+        }
+    }
+}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut chars = input.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '}' => return Err(TemplateError::UnmatchedBrace),
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(TemplateError::UnmatchedBrace),
+                        }
+                    }
+                    let piece = match name.as_str() {
+                        "original" => Piece::Original,
+                        "converted" => Piece::Converted,
+                        "value" => Piece::Value,
+                        "symbol" => Piece::Symbol,
+                        "scale" => Piece::Scale,
+                        _ => return Err(TemplateError::UnknownPlaceholder(name)),
+                    };
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(piece);
+                }
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        Ok(Template { pieces })
+    }
+}
+
+impl Template {
+    fn render(&self, original: &str, value: &str, scale: Scale) -> String {
+        let symbol = scale.symbol();
+        // SI puts a space before K but not before a degree sign.
+        let space = if symbol.starts_with('°') { "" } else { " " };
+
+        let mut text = String::new();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Text(literal) => text.push_str(literal),
+                Piece::Original => text.push_str(original),
+                Piece::Converted => text.push_str(&format!("{}{}{}", value, space, symbol)),
+                Piece::Value => text.push_str(value),
+                Piece::Symbol => text.push_str(symbol),
+                Piece::Scale => text.push_str(scale.name()),
+            }
+        }
+        text
+    }
+}
+
+/// A number as written in the text.
+struct Number {
+    /// The number rewritten with a decimal point, for parsing and for
+    /// matching its precision.
+    literal: String,
+    value: f64,
+}
+
+/// A temperature, or a range of them, found in the text.
+struct Mention {
+    start: usize,
+    end: usize,
+    low: Number,
+    /// The top of a range such as "350-375°F", with what was written
+    /// between the two numbers.
+    high: Option<(String, Number)>,
+    scale: Scale,
+}
+
+/// Finds the temperatures mentioned in `text` and works out what each is
+/// replaced with. A number counts as a temperature when it is followed by
+/// a degree sign and scale ("350°F", "20 ºC", "℃"), a scale's name
+/// ("98.6 Fahrenheit", "5 degrees C") or one of the capital letters C, F
+/// and K ("-5 C"). A capital on its own only counts at the end of a phrase,
+/// so "3 C batteries" and "5 K resistors" are left alone, and no unit counts
+/// before a hyphen and letters, as in "2 F-stops". Ranges such as
+/// "350-375°F" or "10 to 15 C" are converted as a whole.
+///
+/// A mention followed by another in parentheses, as in "350°F (177°C)",
+/// has already been annotated and is skipped, so text can be annotated
+/// twice without doubling up.
+pub fn find_mentions(text: &str, options: &AnnotateOptions) -> Vec<Annotation> {
+    let locale = options.number_format.locale;
+    let mentions = scan(text, locale);
+
+    let mut annotations = Vec::new();
+    let mut index = 0;
+    while index < mentions.len() {
+        let mention = &mentions[index];
+        let annotated = mentions.get(index + 1).is_some_and(|next| {
+            text[mention.end..next.start].trim() == "("
+                && text[next.end..].trim_start().starts_with(')')
+        });
+        if annotated {
+            index += 2;
+            continue;
+        }
+        index += 1;
+
+        let to = options.to.unwrap_or_else(|| mention.scale.counterpart());
+        if to == mention.scale {
+            continue;
+        }
+        let format = |number: &Number| {
+            let temperature = Temperature::new(number.value, mention.scale).ok()?;
+            let value = temperature.to(to).value();
+            Some(
+                options
+                    .number_format
+                    .for_input(&number.literal)
+                    .format(value),
+            )
+        };
+        let Some(low) = format(&mention.low) else {
+            continue;
+        };
+        let value = match &mention.high {
+            Some((separator, high)) => match format(high) {
+                Some(high) => format!("{}{}{}", low, separator, high),
+                None => continue,
+            },
+            None => low,
+        };
+
+        let original = &text[mention.start..mention.end];
+        annotations.push(Annotation {
+            start: mention.start,
+            end: mention.end,
+            original: original.to_string(),
+            replacement: options.template.render(original, &value, to),
+        });
+    }
+    annotations
+}
+
+/// Returns `text` with each temperature it mentions replaced by the
+/// options' template.
+pub fn annotate(text: &str, options: &AnnotateOptions) -> String {
+    let mut annotated = String::with_capacity(text.len());
+    let mut copied = 0;
+    for annotation in find_mentions(text, options) {
+        annotated.push_str(&text[copied..annotation.start]);
+        annotated.push_str(&annotation.replacement);
+        copied = annotation.end;
+    }
+    annotated.push_str(&text[copied..]);
+    annotated
+}
+
+fn scan(text: &str, locale: Locale) -> Vec<Mention> {
+    let mut mentions = Vec::new();
+    let mut at = 0;
+    while let Some(c) = text[at..].chars().next() {
+        if let Some((low, low_end)) = number_at(text, at, locale) {
+            if let Some(mention) = mention_at(text, at, low, low_end, locale) {
+                at = mention.end;
+                mentions.push(mention);
+            } else {
+                at = low_end;
+            }
+            continue;
+        }
+        at += c.len_utf8();
+    }
+    mentions
+}
+
+/// Reads the unit, or the rest of a range and its unit, after a number.
+fn mention_at(
+    text: &str,
+    start: usize,
+    low: Number,
+    low_end: usize,
+    locale: Locale,
+) -> Option<Mention> {
+    if let Some((separator, high, high_end)) = range_at(text, low_end, locale)
+        && let Some((scale, end)) = unit_at(text, high_end, locale)
+    {
+        return Some(Mention {
+            start,
+            end,
+            low,
+            high: Some((separator, high)),
+            scale,
+        });
+    }
+
+    let (scale, end) = unit_at(text, low_end, locale)?;
+    Some(Mention {
+        start,
+        end,
+        low,
+        high: None,
+        scale,
+    })
+}
+
+/// Reads a number starting at `at`, if one does and it isn't the tail of
+/// a word, another number or a price.
+fn number_at(text: &str, at: usize, locale: Locale) -> Option<(Number, usize)> {
+    if let Some(before) = text[..at].chars().next_back()
+        && (before.is_alphanumeric() || matches!(before, '.' | ',' | '_' | '$' | '€' | '£' | '¥'))
+    {
+        return None;
+    }
+
+    let rest = &text[at..];
+    let mut chars = rest.char_indices().peekable();
+    let mut literal = String::new();
+    if let Some(&(_, sign @ ('-' | '+' | '\u{2212}'))) = chars.peek() {
+        literal.push(if sign == '+' { '+' } else { '-' });
+        chars.next();
+    }
+    if !chars.peek().is_some_and(|(_, c)| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let is_separator = |c: char| {
+        c == '.'
+            || c == locale.decimal_separator()
+            || Some(c) == locale.grouping_separator()
+            || (locale == Locale::French && c == '\u{00A0}')
+    };
+    let mut end = rest.len();
+    while let Some((index, c)) = chars.next() {
+        let digit_follows = chars.peek().is_some_and(|(_, next)| next.is_ascii_digit());
+        if c.is_ascii_digit() || (is_separator(c) && digit_follows) {
+            literal.push(c);
+        } else {
+            end = index;
+            break;
+        }
+    }
+
+    let literal = locale.delocalize(&literal);
+    let value = literal.parse().ok()?;
+    Some((Number { literal, value }, at + end))
+}
+
+/// Reads "-375", " to 375" or " – 375" after the first number of a range.
+fn range_at(text: &str, at: usize, locale: Locale) -> Option<(String, Number, usize)> {
+    let rest = &text[at..];
+    let spaced = rest.trim_start();
+    let (after, signed) = if let Some(after) = spaced.strip_prefix(['-', '–', '—']) {
+        (after, false)
+    } else if let Some(after) = spaced.strip_prefix("to ") {
+        (after, true)
+    } else {
+        return None;
+    };
+
+    let high_at = text.len() - after.trim_start().len();
+    let starts_signed = text[high_at..].starts_with(['-', '+', '\u{2212}']);
+    if starts_signed && !signed {
+        return None;
+    }
+    let (high, end) = number_at(text, high_at, locale)?;
+    Some((text[at..high_at].to_string(), high, end))
+}
+
+/// Reads the scale after a number, returning it and where it ends.
+fn unit_at(text: &str, at: usize, locale: Locale) -> Option<(Scale, usize)> {
+    let rest = &text[at..];
+    let unspaced = rest
+        .strip_prefix([' ', '\u{00A0}', '\u{202F}'])
+        .unwrap_or(rest);
+    let offset = at + rest.len() - unspaced.len();
+
+    let (scale, length) = if let Some(after) = unspaced.strip_prefix(['℃', '℉']) {
+        let scale = if unspaced.starts_with('℃') {
+            Scale::Celsius
+        } else {
+            Scale::Fahrenheit
+        };
+        (scale, unspaced.len() - after.len())
+    } else if let Some(after) = unspaced.strip_prefix(['°', 'º']) {
+        let symbol = after.strip_prefix(' ').unwrap_or(after);
+        let (scale, letters) = degree_symbol(symbol)?;
+        (scale, unspaced.len() - symbol.len() + letters)
+    } else {
+        let word = leading_word(unspaced);
+        let lowered = word.to_lowercase();
+        if ["degrees", "degree", "deg", locale.degrees()]
+            .iter()
+            .any(|degrees| lowered == degrees.to_lowercase())
+        {
+            let after = &unspaced[word.len()..];
+            let named = after.strip_prefix([' ', '\u{00A0}'])?;
+            let name = leading_word(named);
+            let scale = scale_name(name).or_else(|| scale_letter(&name.to_uppercase()))?;
+            (scale, unspaced.len() - named.len() + name.len())
+        } else if let Some(scale) = scale_name(word) {
+            (scale, word.len())
+        } else {
+            let scale = scale_letter(word)?;
+            // A lone capital followed by a word is more likely a label, as
+            // in "3 C batteries", than a temperature.
+            let next = unspaced[word.len()..].trim_start_matches([' ', '\u{00A0}']);
+            if next.starts_with(char::is_alphabetic) {
+                return None;
+            }
+            (scale, word.len())
+        }
+    };
+
+    let end = offset + length;
+    let mut after = text[end..].chars();
+    match (after.next(), after.next()) {
+        (Some(c), _) if c.is_alphanumeric() => None,
+        (Some('-'), Some(c)) if c.is_alphabetic() => None,
+        _ => Some((scale, end)),
+    }
+}
+
+fn leading_word(text: &str) -> &str {
+    let end = text
+        .find(|c: char| !c.is_alphabetic())
+        .unwrap_or(text.len());
+    &text[..end]
+}
+
+/// Reads the letters of a scale after a degree sign, e.g. "C" or "Rø".
+fn degree_symbol(text: &str) -> Option<(Scale, usize)> {
+    let symbols = [
+        ("Rø", Scale::Romer),
+        ("Ré", Scale::Reaumur),
+        ("De", Scale::Delisle),
+        ("C", Scale::Celsius),
+        ("c", Scale::Celsius),
+        ("F", Scale::Fahrenheit),
+        ("f", Scale::Fahrenheit),
+        ("K", Scale::Kelvin),
+        ("R", Scale::Rankine),
+        ("N", Scale::Newton),
+    ];
+    symbols
+        .into_iter()
+        .find(|(symbol, _)| text.starts_with(symbol))
+        .map(|(symbol, scale)| (scale, symbol.len()))
+}
+
+/// The scale a word names. Newton is left out, since "5 newtons" is a
+/// force.
+fn scale_name(word: &str) -> Option<Scale> {
+    match word.to_lowercase().as_str() {
+        "centigrade" => Some(Scale::Celsius),
+        "kelvins" => Some(Scale::Kelvin),
+        "newton" => None,
+        name if name.chars().count() > 2 => name.parse().ok(),
+        _ => None,
+    }
+}
+
+/// The scale a capital letter on its own stands for.
+fn scale_letter(word: &str) -> Option<Scale> {
+    match word {
+        "C" => Some(Scale::Celsius),
+        "F" => Some(Scale::Fahrenheit),
+        "K" => Some(Scale::Kelvin),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Precision;
+
+    fn options() -> AnnotateOptions {
+        let mut options = AnnotateOptions::new(NumberFormat::default());
+        options.number_format.precision = Precision::Decimals(0);
+        options
+    }
+
+    #[test]
+    fn annotates_temperatures_in_prose() {
+        let text =
+            "Bake at 350°F for an hour. Lows tonight of -5 C, 98.6 degrees Fahrenheit inside.";
+
+        assert_eq!(
+            annotate(text, &options()),
+            "Bake at 350°F (177°C) for an hour. Lows tonight of -5 C (23°F), \
+             98.6 degrees Fahrenheit (37°C) inside."
+        );
+    }
+
+    #[test]
+    fn reports_byte_offsets() {
+        let text = "über 20 ℃ and 300 K";
+        let mentions = find_mentions(text, &options());
+
+        assert_eq!(
+            mentions,
+            vec![
+                Annotation {
+                    start: 6,
+                    end: 12,
+                    original: "20 ℃".into(),
+                    replacement: "20 ℃ (68°F)".into(),
+                },
+                Annotation {
+                    start: 17,
+                    end: 22,
+                    original: "300 K".into(),
+                    replacement: "300 K (27°C)".into(),
+                },
+            ]
+        );
+        assert_eq!(&text[mentions[0].start..mentions[0].end], "20 ℃");
+    }
+
+    #[test]
+    fn converts_ranges_as_a_whole() {
+        let text = "Roast at 350-375°F, or keep at -10 to -5 C.";
+
+        assert_eq!(
+            annotate(text, &options()),
+            "Roast at 350-375°F (177-191°C), or keep at -10 to -5 C (14 to 23°F)."
+        );
+    }
+
+    #[test]
+    fn leaves_other_numbers_alone() {
+        for text in [
+            "Use A4 paper and 5 Cups of flour",
+            "It costs $300K",
+            "Set it to 350 degrees",
+            "5 newton",
+            "1990s",
+            "Open up 2 F-stops",
+            "Order 5 K resistors",
+            "It takes 3 C batteries",
+            "A 20°C-rated bag",
+        ] {
+            assert_eq!(annotate(text, &options()), text);
+        }
+    }
+
+    #[test]
+    fn skips_mentions_already_annotated() {
+        let text = "Bake at 350°F (177°C).";
+
+        assert_eq!(annotate(text, &options()), text);
+    }
+
+    #[test]
+    fn converts_to_a_chosen_scale_with_a_template() {
+        let mut options = options();
+        options.to = Some(Scale::Kelvin);
+        options.template = "{original} [{value} {scale}]".parse().unwrap();
+        let text = "0 °C, 32 °F and 273 K";
+
+        assert_eq!(
+            annotate(text, &options),
+            "0 °C [273 kelvin], 32 °F [273 kelvin] and 273 K"
+        );
+    }
+
+    #[test]
+    fn reads_numbers_in_the_locale() {
+        let mut options = options();
+        options.number_format = NumberFormat::new(Precision::Decimals(1), Default::default())
+            .with_locale(Locale::German);
+
+        assert_eq!(
+            annotate("Fieber ab 38,5 Grad Celsius", &options),
+            "Fieber ab 38,5 Grad Celsius (101,3°F)"
+        );
+    }
+
+    #[test]
+    fn parses_templates() {
+        assert_eq!("{original} ({converted})".parse(), Ok(Template::default()));
+        assert_eq!(
+            "{{{value}}}"
+                .parse::<Template>()
+                .unwrap()
+                .render("", "5", Scale::Celsius),
+            "{5}"
+        );
+        assert_eq!(
+            "{kelvin}".parse::<Template>(),
+            Err(TemplateError::UnknownPlaceholder("kelvin".into()))
+        );
+        assert_eq!(
+            "{value".parse::<Template>(),
+            Err(TemplateError::UnmatchedBrace)
+        );
+        assert_eq!("}".parse::<Template>(), Err(TemplateError::UnmatchedBrace));
+    }
+}
@@ -0,0 +1,394 @@
+use std::{
+    fmt,
+    io::{self, BufRead, Write},
+};
+
+use crate::{
+    calibration::Calibration,
+    error::ConversionError,
+    format::NumberFormat,
+    units::{Quantity, Unit},
+};
+
+/// Settings for converting a stream of values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchOptions {
+    pub from: Unit,
+    pub to: Unit,
+    /// Read values from this named column of a delimited file with a header
+    /// row instead of one value per line.
+    pub column: Option<String>,
+    pub delimiter: char,
+    /// Stop at the first bad line instead of skipping it.
+    pub strict: bool,
+    /// How converted values are written. [`Precision::MatchInput`] is
+    /// resolved against each line's own value, and values are read with
+    /// the format's locale, so "36,6" is read as 36.6 in German.
+    ///
+    /// [`Precision::MatchInput`]: crate::Precision::MatchInput
+    pub number_format: NumberFormat,
+    /// Correct each value with this calibration before converting it.
+    pub calibration: Option<Calibration>,
+}
+
+impl BatchOptions {
+    pub fn new(from: Unit, to: Unit) -> Self {
+        BatchOptions {
+            from,
+            to,
+            column: None,
+            delimiter: ',',
+            strict: false,
+            number_format: NumberFormat::default(),
+            calibration: None,
+        }
+    }
+}
+
+/// Counts of what happened to the lines of a batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    pub converted: usize,
+    pub skipped: usize,
+}
+
+/// Reasons a batch conversion stopped early.
+#[derive(Debug)]
+pub enum BatchError {
+    Io(io::Error),
+    /// The header row has no column with the requested name.
+    MissingColumn(String),
+    /// A line could not be converted.
+    Line {
+        line: usize,
+        error: ConversionError,
+    },
+    /// A row has fewer fields than it takes to reach the named column.
+    MissingField {
+        line: usize,
+        column: String,
+    },
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Io(error) => write!(f, "{}", error),
+            BatchError::MissingColumn(name) => write!(f, "No column named {} in header.", name),
+            BatchError::Line { line, error } => write!(f, "line {}: {}", line, error),
+            BatchError::MissingField { line, column } => {
+                write!(f, "line {}: The row has no {} field.", line, column)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+impl From<io::Error> for BatchError {
+    fn from(error: io::Error) -> Self {
+        BatchError::Io(error)
+    }
+}
+
+/// Converts one value per line (or one column per row) from `reader`,
+/// writing each converted value to `writer` in input order.
+///
+/// Blank lines and empty fields are ignored. Lines that fail to convert, and
+/// rows too short to have the column, are passed to `on_bad_line` as a
+/// [`BatchError::Line`] or [`BatchError::MissingField`] with their 1-based
+/// line number and skipped, unless the options are strict, in which case the
+/// first one ends the batch.
+pub fn convert_batch<R, W, F>(
+    reader: R,
+    writer: &mut W,
+    options: &BatchOptions,
+    mut on_bad_line: F,
+) -> Result<BatchSummary, BatchError>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(&BatchError),
+{
+    let mut summary = BatchSummary::default();
+    let mut lines = reader.lines().enumerate();
+
+    let column_index = match &options.column {
+        Some(name) => {
+            let header = match lines.next() {
+                Some((_, header)) => header?,
+                None => return Ok(summary),
+            };
+            let index = split_fields(&header, options.delimiter)
+                .iter()
+                .position(|field| field.trim() == name)
+                .ok_or_else(|| BatchError::MissingColumn(name.clone()))?;
+            Some(index)
+        }
+        None => None,
+    };
+
+    for (index, line) in lines {
+        let line = line?;
+        let line_number = index + 1;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let raw = match (column_index, &options.column) {
+            (Some(index), Some(column)) => split_fields(&line, options.delimiter)
+                .into_iter()
+                .nth(index)
+                .ok_or_else(|| BatchError::MissingField {
+                    line: line_number,
+                    column: column.clone(),
+                }),
+            _ => Ok(line),
+        };
+        let converted = match raw {
+            Ok(raw) if raw.trim().is_empty() => continue,
+            Ok(raw) => convert_value(&raw, options).map_err(|error| BatchError::Line {
+                line: line_number,
+                error,
+            }),
+            Err(error) => Err(error),
+        };
+        match converted {
+            Ok(converted) => {
+                writeln!(writer, "{}", converted)?;
+                summary.converted += 1;
+            }
+            Err(error) if options.strict => return Err(error),
+            Err(error) => {
+                on_bad_line(&error);
+                summary.skipped += 1;
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Converts one raw value, written with the precision it calls for.
+fn convert_value(raw: &str, options: &BatchOptions) -> Result<String, ConversionError> {
+    let raw = options.number_format.locale.delocalize(raw);
+    let quantity = Quantity::parse(&raw, &options.from)?;
+    let quantity = match &options.calibration {
+        Some(calibration) => Quantity::new(calibration.apply(quantity.value), quantity.unit),
+        None => quantity,
+    };
+    let converted = quantity.to(&options.to)?;
+    Ok(options
+        .number_format
+        .for_input(&raw)
+        .format(converted.value))
+}
+
+/// Splits a delimited line into fields, honouring double-quoted fields.
+fn split_fields(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        calibration::{CalibrationPoint, Fit},
+        units::UnitRegistry,
+    };
+
+    fn options(from: &str, to: &str) -> BatchOptions {
+        let registry = UnitRegistry::builtin();
+        BatchOptions::new(
+            registry.get(from).unwrap().clone(),
+            registry.get(to).unwrap().clone(),
+        )
+    }
+
+    fn run(
+        input: &str,
+        options: &BatchOptions,
+    ) -> (Result<BatchSummary, BatchError>, String, Vec<usize>) {
+        let mut output = Vec::new();
+        let mut bad_lines = Vec::new();
+        let result = convert_batch(input.as_bytes(), &mut output, options, |error| {
+            bad_lines.push(match error {
+                BatchError::Line { line, .. } | BatchError::MissingField { line, .. } => *line,
+                _ => unreachable!("only line errors are reported"),
+            })
+        });
+        (result, String::from_utf8(output).unwrap(), bad_lines)
+    }
+
+    #[test]
+    fn converts_one_value_per_line_in_order() {
+        let options = options("C", "F");
+        let (result, output, bad_lines) = run("0\n100\n-40\n", &options);
+
+        assert_eq!(
+            result.unwrap(),
+            BatchSummary {
+                converted: 3,
+                skipped: 0
+            }
+        );
+        assert_eq!(output, "32.00\n212.00\n-40.00\n");
+        assert!(bad_lines.is_empty());
+    }
+
+    #[test]
+    fn skips_bad_lines_and_reports_their_numbers() {
+        let options = options("C", "K");
+        let (result, output, bad_lines) = run("0\nwarm\n\n-300\n10\n", &options);
+
+        assert_eq!(
+            result.unwrap(),
+            BatchSummary {
+                converted: 2,
+                skipped: 2
+            }
+        );
+        assert_eq!(output, "273.15\n283.15\n");
+        assert_eq!(bad_lines, vec![2, 4]);
+    }
+
+    #[test]
+    fn strict_mode_stops_at_the_first_bad_line() {
+        let mut options = options("C", "K");
+        options.strict = true;
+        let (result, output, _bad_lines) = run("0\nwarm\n10\n", &options);
+
+        assert!(matches!(
+            result,
+            Err(BatchError::Line {
+                line: 2,
+                error: ConversionError::InvalidNumber(_)
+            })
+        ));
+        assert_eq!(output, "273.15\n");
+    }
+
+    #[test]
+    fn reads_a_named_column() {
+        let mut options = options("F", "C");
+        options.column = Some("temp_f".into());
+        let input = "time,temp_f,note\n1,32,\"cold, dry\"\n2,212,boiling\n";
+        let (result, output, _bad_lines) = run(input, &options);
+
+        assert_eq!(result.unwrap().converted, 2);
+        assert_eq!(output, "0.00\n100.00\n");
+    }
+
+    #[test]
+    fn column_line_numbers_count_the_header() {
+        let mut options = options("F", "C");
+        options.column = Some("temp_f".into());
+        let (_result, _output, bad_lines) = run("temp_f\n32\noops\n", &options);
+
+        assert_eq!(bad_lines, vec![3]);
+    }
+
+    #[test]
+    fn reports_rows_without_the_column() {
+        let mut options = options("F", "C");
+        options.column = Some("temp_f".into());
+        let input = "time,temp_f\n1,32\n2\n\n3,\n4,212\n";
+        let (result, output, bad_lines) = run(input, &options);
+
+        assert_eq!(
+            result.unwrap(),
+            BatchSummary {
+                converted: 2,
+                skipped: 1
+            }
+        );
+        assert_eq!(output, "0.00\n100.00\n");
+        assert_eq!(bad_lines, vec![3]);
+
+        options.strict = true;
+        let (result, output, _bad_lines) = run(input, &options);
+        assert!(matches!(
+            result,
+            Err(BatchError::MissingField { line: 3, column }) if column == "temp_f"
+        ));
+        assert_eq!(output, "0.00\n");
+    }
+
+    #[test]
+    fn fails_when_the_column_is_missing() {
+        let mut options = options("F", "C");
+        options.column = Some("temp_c".into());
+        let (result, _output, _bad_lines) = run("temp_f\n32\n", &options);
+
+        assert!(matches!(result, Err(BatchError::MissingColumn(name)) if name == "temp_c"));
+    }
+
+    #[test]
+    fn matches_the_precision_of_each_line() {
+        let mut options = options("F", "C");
+        options.number_format.precision = crate::format::Precision::MatchInput;
+        let (_result, output, _bad_lines) = run("98.6\n212.00\n", &options);
+
+        assert_eq!(output, "37.0\n100.00\n");
+    }
+
+    #[test]
+    fn reads_and_writes_values_in_the_locale() {
+        let mut options = options("C", "F");
+        options.number_format = options.number_format.with_locale(crate::Locale::German);
+        let (_result, output, _bad_lines) = run("36,6\n10.000\n", &options);
+
+        assert_eq!(output, "97,88\n18.032,00\n");
+    }
+
+    #[test]
+    fn splits_quoted_fields() {
+        assert_eq!(
+            split_fields("a,\"b, c\",\"say \"\"hi\"\"\"", ','),
+            vec!["a", "b, c", "say \"hi\""]
+        );
+        assert_eq!(split_fields("1;2", ';'), vec!["1", "2"]);
+    }
+
+    #[test]
+    fn corrects_values_with_the_calibration() {
+        let points = vec![
+            CalibrationPoint {
+                raw: 0.8,
+                reference: 0.0,
+            },
+            CalibrationPoint {
+                raw: 100.8,
+                reference: 100.0,
+            },
+        ];
+        let options = BatchOptions {
+            calibration: Some(Calibration::fit(points, Fit::Linear).unwrap()),
+            ..options("C", "F")
+        };
+
+        let (result, output, _) = run("0.8\n37.8\n", &options);
+
+        assert_eq!(result.unwrap().converted, 2);
+        assert_eq!(output, "32.00\n98.60\n");
+    }
+}
@@ -0,0 +1,223 @@
+//! The boiling point of water at an altitude or pressure, from the Antoine
+//! equation, and the pressure it boils at for a given temperature.
+
+use crate::{
+    temperature::{Scale, Temperature},
+    units::{Quantity, Unit, UnitRegistry},
+    weather::{Estimate, RangeWarning, WeatherError},
+};
+
+/// Antoine constants `(A, B, C)` for water, with pressure in mmHg and
+/// temperature in celsius. The first set is fitted from 1 °C to 100 °C, the
+/// second from 99 °C to 374 °C.
+const ANTOINE_LOW: (f64, f64, f64) = (8.07131, 1730.63, 233.426);
+const ANTOINE_HIGH: (f64, f64, f64) = (8.14019, 1810.94, 244.485);
+/// Where the two sets agree, in mmHg and celsius. Switching here rather than
+/// at 100 °C, where they differ by 0.16 °C, keeps the results continuous.
+const CROSSOVER_PRESSURE: f64 = 1014.92;
+const CROSSOVER_TEMPERATURE: f64 = 108.27;
+
+/// Below its triple point water sublimates, and above its critical point
+/// there is no liquid left to boil, in celsius.
+const TRIPLE_POINT: f64 = 0.01;
+const CRITICAL_POINT: f64 = 373.946;
+
+/// The International Standard Atmosphere at sea level, in pascals and
+/// kelvin, and how fast it cools with height in the troposphere, in kelvin
+/// per metre.
+const SEA_LEVEL_PRESSURE: f64 = 101_325.0;
+const SEA_LEVEL_TEMPERATURE: f64 = 288.15;
+const LAPSE_RATE: f64 = 0.0065;
+/// g·M / (R·L), the power in the barometric formula.
+const BAROMETRIC_EXPONENT: f64 = 5.25588;
+/// Where the troposphere ends and the lapse rate stops holding, in metres.
+const TROPOPAUSE: f64 = 11_000.0;
+
+/// The air pressure at an altitude above sea level, in pascals, from the
+/// barometric formula for the standard atmosphere. The altitude may be in
+/// any unit of length.
+pub fn pressure_at_altitude(altitude: &Quantity) -> Result<Estimate<Quantity>, WeatherError> {
+    let registry = UnitRegistry::builtin();
+    let metres = altitude.to(registry.get("m")?)?.value;
+    let base = 1.0 - LAPSE_RATE * metres / SEA_LEVEL_TEMPERATURE;
+    if !metres.is_finite() || base <= 0.0 {
+        return Err(WeatherError::InvalidAltitude(metres));
+    }
+    let pascals = SEA_LEVEL_PRESSURE * base.powf(BAROMETRIC_EXPONENT);
+
+    let mut warnings = Vec::new();
+    if metres > TROPOPAUSE {
+        warnings.push(RangeWarning::AboveTroposphere);
+    }
+    Ok(Estimate {
+        value: Quantity::new(pascals, registry.get("Pa")?.clone()),
+        warnings,
+    })
+}
+
+/// The temperature water boils at under a pressure, on `scale`. The
+/// pressure may be in any unit of pressure.
+pub fn boiling_point(pressure: &Quantity, scale: Scale) -> Result<Estimate, WeatherError> {
+    let mmhg = pressure.to(UnitRegistry::builtin().get("mmHg")?)?.value;
+    if !mmhg.is_finite() || mmhg <= 0.0 {
+        return Err(WeatherError::InvalidPressure(pressure.value));
+    }
+
+    let (a, b, c) = if mmhg <= CROSSOVER_PRESSURE {
+        ANTOINE_LOW
+    } else {
+        ANTOINE_HIGH
+    };
+    let celsius = b / (a - mmhg.log10()) - c;
+    if !(TRIPLE_POINT..=CRITICAL_POINT).contains(&celsius) {
+        return Err(WeatherError::NoBoilingPoint(celsius));
+    }
+    Ok(Estimate {
+        value: Temperature::new(celsius, Scale::Celsius)?.to(scale),
+        warnings: Vec::new(),
+    })
+}
+
+/// The temperature water boils at an altitude above sea level, on
+/// `scale`, in the standard atmosphere.
+pub fn boiling_point_at_altitude(
+    altitude: &Quantity,
+    scale: Scale,
+) -> Result<Estimate, WeatherError> {
+    let Estimate {
+        value: pressure,
+        mut warnings,
+    } = pressure_at_altitude(altitude)?;
+    let boiling = boiling_point(&pressure, scale)?;
+    warnings.extend(boiling.warnings);
+    Ok(Estimate {
+        value: boiling.value,
+        warnings,
+    })
+}
+
+/// The pressure, in `unit`, under which water boils at `temperature`: its
+/// vapour pressure.
+pub fn boiling_pressure(
+    temperature: Temperature,
+    unit: &Unit,
+) -> Result<Estimate<Quantity>, WeatherError> {
+    let celsius = temperature.to(Scale::Celsius).value();
+    if !(TRIPLE_POINT..=CRITICAL_POINT).contains(&celsius) {
+        return Err(WeatherError::NoBoilingPoint(celsius));
+    }
+
+    let (a, b, c) = if celsius <= CROSSOVER_TEMPERATURE {
+        ANTOINE_LOW
+    } else {
+        ANTOINE_HIGH
+    };
+    let mmhg = 10f64.powf(a - b / (c + celsius));
+    let pressure = Quantity::new(mmhg, UnitRegistry::builtin().get("mmHg")?.clone());
+    Ok(Estimate {
+        value: pressure.to(unit)?,
+        warnings: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ConversionError;
+
+    fn assert_close(expected: f64, actual: f64, tolerance: f64) {
+        let diff = (expected - actual).abs();
+        assert!(
+            diff <= tolerance,
+            "expected={} actual={} diff={}",
+            expected,
+            actual,
+            diff
+        );
+    }
+
+    fn quantity(value: f64, unit: &str) -> Quantity {
+        Quantity::new(value, UnitRegistry::builtin().get(unit).unwrap().clone())
+    }
+
+    #[test]
+    fn water_boils_at_100_c_at_sea_level() {
+        let boiling = boiling_point(&quantity(1.0, "atm"), Scale::Celsius).unwrap();
+        assert_close(100.0, boiling.value.value(), 0.01);
+
+        let fahrenheit = boiling_point(&quantity(14.696, "psi"), Scale::Fahrenheit).unwrap();
+        assert_eq!(fahrenheit.value.scale(), Scale::Fahrenheit);
+        assert_close(212.0, fahrenheit.value.value(), 0.01);
+
+        let pressure_cooker = boiling_point(&quantity(2.0, "atm"), Scale::Celsius).unwrap();
+        assert_close(120.7, pressure_cooker.value.value(), 0.05);
+    }
+
+    #[test]
+    fn pressure_falls_with_altitude() {
+        let pressure = pressure_at_altitude(&quantity(1500.0, "m")).unwrap();
+        assert_eq!(pressure.value.unit.symbol, "Pa");
+        assert_close(84_556.0, pressure.value.value, 1.0);
+
+        let feet = pressure_at_altitude(&quantity(1500.0 / 0.3048, "ft")).unwrap();
+        assert_close(pressure.value.value, feet.value.value, 1e-6);
+    }
+
+    #[test]
+    fn water_boils_cooler_up_mountains() {
+        let denver = boiling_point_at_altitude(&quantity(1609.0, "m"), Scale::Celsius).unwrap();
+        assert_close(94.66, denver.value.value(), 0.01);
+        assert!(denver.warnings.is_empty());
+
+        let everest = boiling_point_at_altitude(&quantity(8849.0, "m"), Scale::Celsius).unwrap();
+        assert_close(70.26, everest.value.value(), 0.01);
+
+        let stratosphere =
+            boiling_point_at_altitude(&quantity(12.0, "km"), Scale::Celsius).unwrap();
+        assert_eq!(stratosphere.warnings, vec![RangeWarning::AboveTroposphere]);
+    }
+
+    #[test]
+    fn finds_the_pressure_for_a_boiling_point() {
+        let kpa = UnitRegistry::builtin().get("kPa").unwrap().clone();
+        let celsius = |value| Temperature::new(value, Scale::Celsius).unwrap();
+
+        let pressure = boiling_pressure(celsius(90.0), &kpa).unwrap();
+        assert_close(70.03, pressure.value.value, 0.01);
+        let pressure = boiling_pressure(celsius(120.0), &kpa).unwrap();
+        assert_close(197.97, pressure.value.value, 0.01);
+
+        // The inverse of the forward calculation.
+        let boiling = boiling_point(&pressure.value, Scale::Celsius).unwrap();
+        assert_close(120.0, boiling.value.value(), 1e-9);
+    }
+
+    #[test]
+    fn rejects_impossible_inputs() {
+        assert_eq!(
+            boiling_point(&quantity(-5.0, "kPa"), Scale::Celsius),
+            Err(WeatherError::InvalidPressure(-5.0))
+        );
+        assert!(matches!(
+            boiling_point(&quantity(1.0, "Pa"), Scale::Celsius),
+            Err(WeatherError::NoBoilingPoint(_))
+        ));
+        assert_eq!(
+            pressure_at_altitude(&quantity(50.0, "km")),
+            Err(WeatherError::InvalidAltitude(50_000.0))
+        );
+        assert!(matches!(
+            pressure_at_altitude(&quantity(5.0, "kg")),
+            Err(WeatherError::Conversion(
+                ConversionError::IncompatibleUnits { .. }
+            ))
+        ));
+
+        let kpa = UnitRegistry::builtin().get("kPa").unwrap().clone();
+        let supercritical = Temperature::new(400.0, Scale::Celsius).unwrap();
+        assert_eq!(
+            boiling_pressure(supercritical, &kpa),
+            Err(WeatherError::NoBoilingPoint(400.0))
+        );
+    }
+}
@@ -0,0 +1,478 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// A probe's raw reading next to what a reference thermometer read at the
+/// same time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalibrationPoint {
+    pub raw: f64,
+    pub reference: f64,
+}
+
+/// How corrections are worked out from calibration points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Fit {
+    /// A least-squares straight line.
+    #[default]
+    Linear,
+    /// A least-squares polynomial of this degree.
+    Polynomial(usize),
+    /// Straight lines between neighbouring points, extended past the ends
+    /// by the first and last segments.
+    Piecewise,
+}
+
+impl FromStr for Fit {
+    type Err = CalibrationError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let name = input.trim().to_lowercase();
+        let degree = name
+            .strip_prefix("polynomial")
+            .or_else(|| name.strip_prefix("poly"))
+            .and_then(|degree| degree.trim_start_matches([':', '-']).parse().ok());
+        match (name.as_str(), degree) {
+            ("linear", _) => Ok(Fit::Linear),
+            ("quadratic", _) => Ok(Fit::Polynomial(2)),
+            ("cubic", _) => Ok(Fit::Polynomial(3)),
+            ("piecewise", _) => Ok(Fit::Piecewise),
+            (_, Some(degree)) if degree >= 1 => Ok(Fit::Polynomial(degree)),
+            _ => Err(CalibrationError::UnknownFit(input.to_string())),
+        }
+    }
+}
+
+/// Reasons a calibration could not be loaded or fitted.
+#[derive(Debug)]
+pub enum CalibrationError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// A line is not a pair of numbers.
+    Parse {
+        line: usize,
+        text: String,
+    },
+    /// The requested fit method is not supported.
+    UnknownFit(String),
+    /// The fit needs more points with distinct raw values than there are.
+    TooFewPoints {
+        needed: usize,
+        found: usize,
+    },
+    /// Piecewise interpolation can't use two points with the same raw
+    /// value.
+    DuplicateRaw(f64),
+    /// A calibration corrects temperatures, but the values are in this
+    /// other unit.
+    NotTemperature(String),
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationError::Io { path, error } => {
+                write!(f, "Could not read {}: {}.", path.display(), error)
+            }
+            CalibrationError::Parse { line, text } => write!(
+                f,
+                "Calibration line {}: expected a raw and a reference value, got {:?}.",
+                line, text
+            ),
+            CalibrationError::UnknownFit(input) => write!(
+                f,
+                "Unknown fit: {}. Expected linear, poly<N> or piecewise.",
+                input
+            ),
+            CalibrationError::TooFewPoints { needed, found } => write!(
+                f,
+                "The fit needs {} calibration points with different raw values, got {}.",
+                needed, found
+            ),
+            CalibrationError::DuplicateRaw(raw) => write!(
+                f,
+                "Raw value {} appears more than once, which piecewise interpolation can't use.",
+                raw
+            ),
+            CalibrationError::NotTemperature(unit) => write!(
+                f,
+                "A calibration corrects temperatures, so it can't be used for values in {}.",
+                unit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+/// A calibration point as the fitted correction sees it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Residual {
+    pub raw: f64,
+    pub reference: f64,
+    /// The raw value after correction.
+    pub corrected: f64,
+}
+
+impl Residual {
+    /// How far the corrected value is from the reference, positive when it
+    /// reads high.
+    pub fn error(&self) -> f64 {
+        self.corrected - self.reference
+    }
+}
+
+/// A correction from a probe's raw readings to reference values, fitted to
+/// calibration points. Readings are corrected in their own unit, before any
+/// conversion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    points: Vec<CalibrationPoint>,
+    correction: Correction,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Correction {
+    /// Coefficients from the constant term up.
+    Polynomial(Vec<f64>),
+    /// The points, sorted by raw value.
+    Piecewise(Vec<CalibrationPoint>),
+}
+
+impl Calibration {
+    pub fn fit(points: Vec<CalibrationPoint>, fit: Fit) -> Result<Self, CalibrationError> {
+        let correction = match fit {
+            Fit::Linear => Correction::Polynomial(least_squares(&points, 1)?),
+            Fit::Polynomial(degree) => Correction::Polynomial(least_squares(&points, degree)?),
+            Fit::Piecewise => {
+                let mut sorted = points.clone();
+                sorted.sort_by(|a, b| a.raw.total_cmp(&b.raw));
+                if let Some(pair) = sorted.windows(2).find(|pair| pair[0].raw == pair[1].raw) {
+                    return Err(CalibrationError::DuplicateRaw(pair[0].raw));
+                }
+                if sorted.len() < 2 {
+                    return Err(CalibrationError::TooFewPoints {
+                        needed: 2,
+                        found: sorted.len(),
+                    });
+                }
+                Correction::Piecewise(sorted)
+            }
+        };
+
+        Ok(Calibration { points, correction })
+    }
+
+    /// Corrects a raw reading.
+    pub fn apply(&self, raw: f64) -> f64 {
+        match &self.correction {
+            Correction::Polynomial(coefficients) => coefficients
+                .iter()
+                .rev()
+                .fold(0.0, |sum, coefficient| sum * raw + coefficient),
+            Correction::Piecewise(points) => {
+                let segment = points
+                    .windows(2)
+                    .position(|pair| raw < pair[1].raw)
+                    .unwrap_or(points.len() - 2);
+                let (low, high) = (points[segment], points[segment + 1]);
+                low.reference
+                    + (raw - low.raw) * (high.reference - low.reference) / (high.raw - low.raw)
+            }
+        }
+    }
+
+    pub fn points(&self) -> &[CalibrationPoint] {
+        &self.points
+    }
+
+    /// The fitted polynomial's coefficients, from the constant term up, or
+    /// `None` for piecewise interpolation.
+    pub fn coefficients(&self) -> Option<&[f64]> {
+        match &self.correction {
+            Correction::Polynomial(coefficients) => Some(coefficients),
+            Correction::Piecewise(_) => None,
+        }
+    }
+
+    /// How each calibration point comes out after correction, in the order
+    /// the points were given.
+    pub fn residuals(&self) -> Vec<Residual> {
+        self.points
+            .iter()
+            .map(|point| Residual {
+                raw: point.raw,
+                reference: point.reference,
+                corrected: self.apply(point.raw),
+            })
+            .collect()
+    }
+
+    /// The root mean square of the residual errors.
+    pub fn rms_error(&self) -> f64 {
+        let residuals = self.residuals();
+        let sum: f64 = residuals.iter().map(|r| r.error() * r.error()).sum();
+        (sum / residuals.len() as f64).sqrt()
+    }
+
+    /// The largest residual error, ignoring sign.
+    pub fn max_error(&self) -> f64 {
+        self.residuals()
+            .iter()
+            .map(|residual| residual.error().abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Fits a polynomial of `degree` through the points by least squares,
+/// returning its coefficients from the constant term up.
+///
+/// Raw values are centred on their mean before solving the normal
+/// equations, which keeps them well conditioned, and the result is shifted
+/// back afterwards.
+fn least_squares(points: &[CalibrationPoint], degree: usize) -> Result<Vec<f64>, CalibrationError> {
+    let mut distinct: Vec<f64> = points.iter().map(|point| point.raw).collect();
+    distinct.sort_by(f64::total_cmp);
+    distinct.dedup();
+    if distinct.len() <= degree {
+        return Err(CalibrationError::TooFewPoints {
+            needed: degree + 1,
+            found: distinct.len(),
+        });
+    }
+
+    let size = degree + 1;
+    let mean = points.iter().map(|point| point.raw).sum::<f64>() / points.len() as f64;
+    let mut matrix = vec![vec![0.0; size + 1]; size];
+    for point in points {
+        let u = point.raw - mean;
+        let powers: Vec<f64> = (0..2 * size).map(|k| u.powi(k as i32)).collect();
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().take(size).enumerate() {
+                *cell += powers[i + j];
+            }
+            row[size] += point.reference * powers[i];
+        }
+    }
+
+    // Gaussian elimination with partial pivoting.
+    for column in 0..size {
+        let pivot = (column..size)
+            .max_by(|&a, &b| matrix[a][column].abs().total_cmp(&matrix[b][column].abs()))
+            .expect("the range is not empty");
+        matrix.swap(column, pivot);
+        let (upper, lower) = matrix.split_at_mut(column + 1);
+        let pivot_row = &upper[column];
+        for row in lower {
+            let factor = row[column] / pivot_row[column];
+            for (cell, pivot_cell) in row.iter_mut().zip(pivot_row).skip(column) {
+                *cell -= factor * pivot_cell;
+            }
+        }
+    }
+    let mut centred = vec![0.0; size];
+    for row in (0..size).rev() {
+        let known: f64 = (row + 1..size).map(|k| matrix[row][k] * centred[k]).sum();
+        centred[row] = (matrix[row][size] - known) / matrix[row][row];
+    }
+
+    // Expand sum c_k (x - mean)^k into powers of x.
+    let mut coefficients = vec![0.0; size];
+    for (k, c) in centred.iter().enumerate() {
+        let mut binomial = 1.0;
+        for (j, coefficient) in coefficients.iter_mut().enumerate().take(k + 1) {
+            *coefficient += c * binomial * (-mean).powi((k - j) as i32);
+            binomial = binomial * (k - j) as f64 / (j + 1) as f64;
+        }
+    }
+    Ok(coefficients)
+}
+
+/// Reads calibration points from a CSV file and fits them.
+pub fn load_calibration_file(path: &Path, fit: Fit) -> Result<Calibration, CalibrationError> {
+    let contents = fs::read_to_string(path).map_err(|error| CalibrationError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    Calibration::fit(parse_calibration(&contents)?, fit)
+}
+
+/// Parses calibration points from CSV with one `raw,reference` pair per
+/// line. Blank lines and lines starting with `#` are skipped, and so is a
+/// header row at the top.
+pub fn parse_calibration(csv: &str) -> Result<Vec<CalibrationPoint>, CalibrationError> {
+    let mut points = Vec::new();
+    let mut first = true;
+
+    for (index, line) in csv.lines().enumerate() {
+        let text = line.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = text.split(',').map(str::trim).collect();
+        let values = match fields.as_slice() {
+            [raw, reference] => raw.parse().ok().zip(reference.parse().ok()),
+            _ => None,
+        };
+        match values {
+            Some((raw, reference)) if f64::is_finite(raw) && f64::is_finite(reference) => {
+                points.push(CalibrationPoint { raw, reference })
+            }
+            None if first && fields.len() == 2 => {}
+            _ => {
+                return Err(CalibrationError::Parse {
+                    line: index + 1,
+                    text: text.to_string(),
+                });
+            }
+        }
+        first = false;
+    }
+
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(pairs: &[(f64, f64)]) -> Vec<CalibrationPoint> {
+        pairs
+            .iter()
+            .map(|&(raw, reference)| CalibrationPoint { raw, reference })
+            .collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn linear_fit_recovers_an_exact_line() {
+        // A probe reading 0.8 °C high at 0 °C and 2% high in slope.
+        let calibration = Calibration::fit(
+            points(&[(0.8, 0.0), (51.8, 50.0), (102.8, 100.0)]),
+            Fit::Linear,
+        )
+        .unwrap();
+
+        let coefficients = calibration.coefficients().unwrap();
+        assert_close(coefficients[1], 1.0 / 1.02);
+        assert_close(calibration.apply(0.8), 0.0);
+        assert_close(calibration.apply(26.3), 25.0);
+        assert!(calibration.max_error() < 1e-9);
+    }
+
+    #[test]
+    fn linear_fit_reports_residuals() {
+        let calibration = Calibration::fit(
+            points(&[(0.0, 0.0), (1.0, 1.0), (2.0, 1.0), (3.0, 3.0)]),
+            Fit::Linear,
+        )
+        .unwrap();
+
+        // The least-squares line is y = 0.9x - 0.1, leaving errors of
+        // -0.1, -0.2, 0.7 and -0.4.
+        let errors: Vec<f64> = calibration
+            .residuals()
+            .iter()
+            .map(Residual::error)
+            .collect();
+        for (error, expected) in errors.iter().zip([-0.1, -0.2, 0.7, -0.4]) {
+            assert_close(*error, expected);
+        }
+        assert_close(calibration.max_error(), 0.7);
+        assert_close(calibration.rms_error(), (0.7_f64 / 4.0).sqrt());
+    }
+
+    #[test]
+    fn polynomial_fit_follows_a_curve() {
+        let curve = |x: f64| 0.5 - 0.2 * x + 0.003 * x * x;
+        let pairs: Vec<(f64, f64)> = [0.0, 20.0, 40.0, 60.0, 80.0, 100.0]
+            .iter()
+            .map(|&x| (x, curve(x)))
+            .collect();
+
+        let calibration = Calibration::fit(points(&pairs), Fit::Polynomial(2)).unwrap();
+
+        for (expected, actual) in [0.5, -0.2, 0.003]
+            .iter()
+            .zip(calibration.coefficients().unwrap())
+        {
+            assert!((expected - actual).abs() < 1e-9);
+        }
+        assert!((calibration.apply(55.0) - curve(55.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn piecewise_interpolates_and_extends_the_ends() {
+        let calibration = Calibration::fit(
+            points(&[(100.0, 98.0), (0.0, 0.5), (50.0, 49.0)]),
+            Fit::Piecewise,
+        )
+        .unwrap();
+
+        assert_close(calibration.apply(25.0), 24.75);
+        assert_close(calibration.apply(75.0), 73.5);
+        assert_close(calibration.apply(-10.0), -9.2);
+        assert_close(calibration.apply(110.0), 107.8);
+        assert!(calibration.coefficients().is_none());
+        assert_eq!(calibration.max_error(), 0.0);
+    }
+
+    #[test]
+    fn rejects_fits_without_enough_points() {
+        assert!(matches!(
+            Calibration::fit(points(&[(1.0, 1.0), (1.0, 2.0)]), Fit::Linear),
+            Err(CalibrationError::TooFewPoints {
+                needed: 2,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            Calibration::fit(points(&[(0.0, 0.0), (1.0, 1.0)]), Fit::Polynomial(2)),
+            Err(CalibrationError::TooFewPoints { needed: 3, .. })
+        ));
+        assert!(matches!(
+            Calibration::fit(
+                points(&[(0.0, 0.0), (0.0, 1.0), (1.0, 1.0)]),
+                Fit::Piecewise
+            ),
+            Err(CalibrationError::DuplicateRaw(_))
+        ));
+    }
+
+    #[test]
+    fn parses_csv_with_a_header_and_comments() {
+        let csv = "raw,reference\n# ice bath\n0.8, 0\n\n100.9,100\n";
+        assert_eq!(
+            parse_calibration(csv).unwrap(),
+            points(&[(0.8, 0.0), (100.9, 100.0)])
+        );
+
+        assert!(matches!(
+            parse_calibration("0.8,0\n1,2,3\n"),
+            Err(CalibrationError::Parse { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn parses_fit_names() {
+        assert_eq!("linear".parse::<Fit>().unwrap(), Fit::Linear);
+        assert_eq!("poly3".parse::<Fit>().unwrap(), Fit::Polynomial(3));
+        assert_eq!("polynomial:2".parse::<Fit>().unwrap(), Fit::Polynomial(2));
+        assert_eq!("Piecewise".parse::<Fit>().unwrap(), Fit::Piecewise);
+        assert!("poly0".parse::<Fit>().is_err());
+        assert!("spline".parse::<Fit>().is_err());
+    }
+}
@@ -0,0 +1,643 @@
+use std::{path::PathBuf, str::FromStr, time::Duration};
+
+use clap::{ArgAction, Args, Parser, Subcommand};
+
+use crate::output::OutputFormat;
+use rust_convert::{
+    DEFAULT_SYSFS_ROOT, ExactNotation, Fit, JsonPath, Locale, MAX_DIGITS, NumberFormat, Precision,
+    Rounding, Scale, TableFormat, Template, ThermocoupleType,
+};
+
+/// rust-convert converts temperatures between celsius, fahrenheit, kelvin,
+/// rankine and the historical réaumur, delisle, newton and rømer scales.
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about,
+    disable_help_flag = true,
+    subcommand_precedence_over_arg = true,
+    allow_negative_numbers = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub convert: ConvertArgs,
+
+    /// Conversion expression, e.g. "72°F to K" or 98.6 F in C
+    #[arg(conflicts_with = "ConvertArgs")]
+    pub expression: Vec<String>,
+
+    /// Treat the value as a temperature difference, so no offset is applied
+    #[arg(long)]
+    pub delta: bool,
+
+    /// Output format: a sentence (text) or a JSON object (json)
+    #[arg(long = "format", value_name = "FORMAT", default_value = "text")]
+    pub output: OutputFormat,
+
+    /// Start the interactive mode even when stdin is not a terminal
+    #[arg(short, long, conflicts_with_all = ["ConvertArgs", "expression"])]
+    pub interactive: bool,
+
+    /// Convert with exact fractions rather than floating point, e.g. 37.(7)
+    #[arg(long, conflicts_with_all = ["precision", "sig_figs", "calibration"])]
+    pub exact: bool,
+
+    /// How --exact writes repeating decimals: parentheses, overline, ellipsis
+    /// or fraction
+    #[arg(
+        long,
+        value_name = "STYLE",
+        default_value = "parentheses",
+        requires = "exact"
+    )]
+    pub notation: ExactNotation,
+
+    /// Digits to show after the decimal point, up to 100 [default: 2]
+    #[arg(
+        long,
+        value_name = "N",
+        global = true,
+        conflicts_with = "sig_figs",
+        value_parser = parse_precision
+    )]
+    pub precision: Option<usize>,
+
+    /// Significant figures to show, up to 100, or "auto" to match the input
+    /// value
+    #[arg(long, value_name = "N|auto", global = true)]
+    pub sig_figs: Option<SigFigs>,
+
+    /// How to round: half-even, half-up or truncate
+    #[arg(long, value_name = "MODE", global = true, default_value = "half-even")]
+    pub rounding: Rounding,
+
+    /// Language for numbers and messages: en, de, fr or C [default: from
+    /// LC_ALL, LC_NUMERIC or LANG]
+    #[arg(long, value_name = "LOCALE", global = true)]
+    pub locale: Option<Locale>,
+
+    /// Load extra unit definitions from this TOML file
+    #[arg(long, value_name = "PATH", global = true)]
+    pub units_file: Option<PathBuf>,
+
+    /// Correct raw readings with the raw,reference pairs in this CSV file
+    #[arg(long, value_name = "PATH", global = true)]
+    pub calibration: Option<PathBuf>,
+
+    /// How to fit the calibration points: linear, poly<N> or piecewise
+    #[arg(
+        long,
+        value_name = "METHOD",
+        global = true,
+        default_value = "linear",
+        requires = "calibration"
+    )]
+    pub fit: Fit,
+
+    /// Print help
+    #[arg(long, action = ArgAction::Help)]
+    help: Option<bool>,
+}
+
+impl Cli {
+    /// The locale picked by --locale, or the environment's.
+    pub fn locale(&self) -> Locale {
+        self.locale.unwrap_or_else(Locale::from_env)
+    }
+
+    /// The number format picked by --precision, --sig-figs, --rounding and
+    /// --locale.
+    pub fn number_format(&self) -> NumberFormat {
+        let precision = match (self.precision, self.sig_figs) {
+            (Some(decimals), _) => Precision::Decimals(decimals),
+            (None, Some(SigFigs::Count(count))) => Precision::SignificantFigures(count),
+            (None, Some(SigFigs::Auto)) => Precision::MatchInput,
+            (None, None) => NumberFormat::default().precision,
+        };
+        NumberFormat::new(precision, self.rounding).with_locale(self.locale())
+    }
+
+    /// Whether a subcommand was given along with options that only apply to
+    /// single conversions, or with a --format it doesn't write. The global
+    /// options may come before or after a subcommand, but these can't be
+    /// mixed with one.
+    pub fn mixes_subcommand_with_conversion(&self) -> bool {
+        let Some(command) = &self.command else {
+            return false;
+        };
+        self.convert.conversion().is_some()
+            || !self.expression.is_empty()
+            || self.delta
+            || self.exact
+            || self.interactive
+            || (self.output != OutputFormat::Text && !command.writes_reports())
+    }
+
+    /// The notation for exact results, if --exact was given.
+    pub fn exact_notation(&self) -> Option<ExactNotation> {
+        self.exact.then_some(self.notation)
+    }
+}
+
+/// The value of an option in raw arguments, for reporting errors when the
+/// arguments as a whole could not be parsed.
+pub fn raw_option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix(name) {
+            Some("") => return args.next().map(String::as_str),
+            Some(rest) if rest.starts_with('=') => return Some(&rest[1..]),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The value of --precision: a number of decimals up to [`MAX_DIGITS`].
+pub fn parse_precision(input: &str) -> Result<usize, String> {
+    match input.parse() {
+        Ok(decimals) if decimals <= MAX_DIGITS => Ok(decimals),
+        _ => Err(format!(
+            "expected a number from 0 to {}, got {}",
+            MAX_DIGITS, input
+        )),
+    }
+}
+
+/// The value of --r0: a resistance in ohms above zero.
+pub fn parse_r0(input: &str) -> Result<f64, String> {
+    match input.parse::<f64>() {
+        Ok(ohms) if ohms.is_finite() && ohms > 0.0 => Ok(ohms),
+        _ => Err(format!("expected a resistance above zero, got {}", input)),
+    }
+}
+
+/// The value of --sig-figs: a count, or "auto" to count the input's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigFigs {
+    Count(usize),
+    Auto,
+}
+
+impl FromStr for SigFigs {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if input.eq_ignore_ascii_case("auto") {
+            return Ok(SigFigs::Auto);
+        }
+        match input.parse() {
+            Ok(count) if (1..=MAX_DIGITS).contains(&count) => Ok(SigFigs::Count(count)),
+            _ => Err(format!(
+                "expected a number from 1 to {} or auto, got {}",
+                MAX_DIGITS, input
+            )),
+        }
+    }
+}
+
+/// The single-value conversion flags. Only one may be given at a time.
+#[derive(Debug, Args)]
+#[group(multiple = false)]
+pub struct ConvertArgs {
+    /// Convert celsius to fahrenheit
+    #[arg(short, long, value_name = "VAL", allow_negative_numbers = true)]
+    pub celsius: Option<String>,
+
+    /// Convert fahrenheit to celsius
+    #[arg(short, long, value_name = "VAL", allow_negative_numbers = true)]
+    pub fahrenheit: Option<String>,
+
+    /// Convert kelvin to celsius
+    #[arg(short, long, value_name = "VAL", allow_negative_numbers = true)]
+    pub kelvin: Option<String>,
+
+    /// Convert rankine to fahrenheit
+    #[arg(short, long, value_name = "VAL", allow_negative_numbers = true)]
+    pub rankine: Option<String>,
+
+    /// Convert réaumur to celsius
+    #[arg(long, value_name = "VAL", allow_negative_numbers = true)]
+    pub reaumur: Option<String>,
+
+    /// Convert delisle to celsius
+    #[arg(long, value_name = "VAL", allow_negative_numbers = true)]
+    pub delisle: Option<String>,
+
+    /// Convert newton to celsius
+    #[arg(long, value_name = "VAL", allow_negative_numbers = true)]
+    pub newton: Option<String>,
+
+    /// Convert rømer to celsius
+    #[arg(long, value_name = "VAL", allow_negative_numbers = true)]
+    pub romer: Option<String>,
+}
+
+impl ConvertArgs {
+    /// Returns the raw value along with the scale it is read in and the scale
+    /// it converts to, if a conversion flag was given.
+    pub fn conversion(&self) -> Option<(&str, Scale, Scale)> {
+        let flags = [
+            (&self.celsius, Scale::Celsius),
+            (&self.fahrenheit, Scale::Fahrenheit),
+            (&self.kelvin, Scale::Kelvin),
+            (&self.rankine, Scale::Rankine),
+            (&self.reaumur, Scale::Reaumur),
+            (&self.delisle, Scale::Delisle),
+            (&self.newton, Scale::Newton),
+            (&self.romer, Scale::Romer),
+        ];
+
+        flags.into_iter().find_map(|(value, from)| {
+            value
+                .as_deref()
+                .map(|value| (value, from, from.counterpart()))
+        })
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print a conversion table for a range of temperatures
+    Table(TableArgs),
+    /// Convert one value per line from a file or stdin
+    Batch(BatchArgs),
+    /// Convert fields inside a JSON or CSV document, leaving the rest as it
+    /// was
+    Doc(DocArgs),
+    /// Find temperatures in text and write their conversions next to them
+    Annotate(AnnotateArgs),
+    /// How hot it feels, from the NWS heat index
+    HeatIndex(HumidityArgs),
+    /// How cold it feels in the wind, from the NWS wind chill
+    WindChill(WindChillArgs),
+    /// The temperature at which dew forms, from the Magnus formula
+    DewPoint(HumidityArgs),
+    /// The Canadian humidex
+    Humidex(HumidityArgs),
+    /// The temperature water boils at an altitude or pressure, from the
+    /// Antoine equation, or the pressure it boils at for a temperature
+    BoilingPoint(BoilingPointArgs),
+    /// Convert a thermocouple voltage to a temperature, or back
+    Thermocouple(ThermocoupleArgs),
+    /// Convert a platinum RTD's resistance to a temperature, or back
+    Rtd(RtdArgs),
+    /// List the temperatures reported by the kernel's thermal and hwmon
+    /// sensors
+    Sensors(SensorsArgs),
+    /// Keep converting readings from a sensor, a file or stdin, with alerts
+    /// when they cross thresholds
+    Watch(WatchArgs),
+    /// Read temperatures and dew points from METAR or SYNOP reports
+    Metar(MetarArgs),
+    /// The color of light from a blackbody at a color temperature, as hex,
+    /// RGB and CIE xy
+    Color(ColorArgs),
+}
+
+impl Command {
+    /// Whether the subcommand writes its result in the top-level --format.
+    /// The others print their own layouts, some with a --format of their own.
+    pub fn writes_reports(&self) -> bool {
+        matches!(
+            self,
+            Command::HeatIndex(_)
+                | Command::WindChill(_)
+                | Command::DewPoint(_)
+                | Command::Humidex(_)
+                | Command::BoilingPoint(_)
+        )
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct TableArgs {
+    /// First value in the table
+    #[arg(long, allow_negative_numbers = true)]
+    pub from: f64,
+
+    /// Last value in the table
+    #[arg(long, allow_negative_numbers = true)]
+    pub to: f64,
+
+    /// Distance between rows
+    #[arg(long, default_value_t = 10.0)]
+    pub step: f64,
+
+    /// Unit of the range, e.g. c, f, km or kWh
+    #[arg(short, long, visible_alias = "unit", default_value = "c")]
+    pub scale: String,
+
+    /// Output format (text, markdown, csv or json)
+    #[arg(long, default_value = "text")]
+    pub format: TableFormat,
+}
+
+#[derive(Debug, Args)]
+pub struct BatchArgs {
+    /// File to read values from (defaults to stdin)
+    pub input: Option<PathBuf>,
+
+    /// Unit the values are in
+    #[arg(long)]
+    pub from: String,
+
+    /// Unit to convert the values to
+    #[arg(long)]
+    pub to: String,
+
+    /// Read values from this column of a CSV file with a header row
+    #[arg(long)]
+    pub column: Option<String>,
+
+    /// Field delimiter used with --column
+    #[arg(long, default_value_t = ',')]
+    pub delimiter: char,
+
+    /// Stop at the first bad line instead of skipping it
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct DocArgs {
+    /// File to read the document from (defaults to stdin)
+    pub input: Option<PathBuf>,
+
+    /// Convert the JSON values at this path, e.g. '$.readings[*].temp_f'
+    #[arg(
+        long,
+        value_name = "PATH",
+        required_unless_present = "column",
+        conflicts_with = "column"
+    )]
+    pub path: Option<JsonPath>,
+
+    /// Convert the values in this column of a CSV file with a header row
+    #[arg(long, value_name = "NAME")]
+    pub column: Option<String>,
+
+    /// Unit the values are in
+    #[arg(long)]
+    pub from: String,
+
+    /// Unit to convert the values to
+    #[arg(long)]
+    pub to: String,
+
+    /// Rename the converted field or column
+    #[arg(long, value_name = "NAME", conflicts_with = "add")]
+    pub rename: Option<String>,
+
+    /// Keep the field or column and add the converted values after it under
+    /// this name
+    #[arg(long, value_name = "NAME")]
+    pub add: Option<String>,
+
+    /// Field delimiter used with --column
+    #[arg(long, default_value_t = ',')]
+    pub delimiter: char,
+
+    /// Stop at the first bad value instead of leaving it as it was
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct AnnotateArgs {
+    /// Files to annotate (defaults to stdin)
+    pub input: Vec<PathBuf>,
+
+    /// Scale to convert to [default: fahrenheit for celsius, celsius for
+    /// the others]
+    #[arg(long, value_name = "SCALE")]
+    pub to: Option<String>,
+
+    /// What each mention is replaced with, using {original}, {converted},
+    /// {value}, {symbol} and {scale}
+    #[arg(long, default_value = "{original} ({converted})")]
+    pub template: Template,
+
+    /// List each mention with its byte offsets instead of annotating the
+    /// text
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct AirArgs {
+    /// Air temperature
+    #[arg(allow_negative_numbers = true)]
+    pub temperature: String,
+
+    /// Scale of the temperature and the result, e.g. c or f
+    #[arg(short, long, default_value = "c")]
+    pub scale: String,
+}
+
+#[derive(Debug, Args)]
+pub struct HumidityArgs {
+    #[command(flatten)]
+    pub air: AirArgs,
+
+    /// Relative humidity in percent
+    #[arg(long, visible_alias = "rh", value_name = "PERCENT")]
+    pub humidity: String,
+}
+
+#[derive(Debug, Args)]
+pub struct WindChillArgs {
+    #[command(flatten)]
+    pub air: AirArgs,
+
+    /// Wind speed
+    #[arg(long, value_name = "VAL", allow_negative_numbers = true)]
+    pub wind: String,
+
+    /// Unit of the wind speed, e.g. km/h, mph or m/s
+    #[arg(long, value_name = "UNIT", default_value = "km/h")]
+    pub wind_unit: String,
+}
+
+#[derive(Debug, Args)]
+pub struct BoilingPointArgs {
+    #[command(flatten)]
+    pub input: BoilingPointInput,
+
+    /// Unit of --altitude, e.g. m, ft or km
+    #[arg(long, value_name = "UNIT", default_value = "m")]
+    pub altitude_unit: String,
+
+    /// Unit of --pressure and of the pressure --boils-at finds, e.g. kPa,
+    /// atm or psi
+    #[arg(long, value_name = "UNIT", default_value = "kPa")]
+    pub pressure_unit: String,
+
+    /// Scale of the result and --boils-at values
+    #[arg(short, long, default_value = "c")]
+    pub scale: String,
+}
+
+/// What the boiling point is worked out from. Exactly one must be given.
+#[derive(Debug, Args)]
+#[group(required = true, multiple = false)]
+pub struct BoilingPointInput {
+    /// Altitude above sea level, in the standard atmosphere
+    #[arg(long, value_name = "VAL", allow_negative_numbers = true)]
+    pub altitude: Option<String>,
+
+    /// Ambient pressure
+    #[arg(long, value_name = "VAL", allow_negative_numbers = true)]
+    pub pressure: Option<String>,
+
+    /// Find the pressure water boils at for this temperature instead
+    #[arg(long, value_name = "VAL", allow_negative_numbers = true)]
+    pub boils_at: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ThermocoupleArgs {
+    /// Voltage in millivolts, or a temperature with --reverse
+    #[arg(allow_negative_numbers = true)]
+    pub value: String,
+
+    /// Thermocouple type: K, J, T or E
+    #[arg(short = 't', long = "type", value_name = "TYPE")]
+    pub kind: ThermocoupleType,
+
+    /// Temperature of the reference junction [default: 0 °C]
+    #[arg(long, value_name = "VAL", allow_negative_numbers = true)]
+    pub cold_junction: Option<String>,
+
+    /// Scale of the result, the reference junction and --reverse values
+    #[arg(short, long, default_value = "c")]
+    pub scale: String,
+
+    /// Convert a temperature to the voltage the thermocouple produces
+    #[arg(long)]
+    pub reverse: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct RtdArgs {
+    /// Resistance in ohms, or a temperature with --reverse
+    #[arg(allow_negative_numbers = true)]
+    pub value: String,
+
+    /// Resistance at 0 °C in ohms, e.g. 100 for a Pt100
+    #[arg(long, value_name = "OHMS", default_value_t = 100.0, value_parser = parse_r0)]
+    pub r0: f64,
+
+    /// Scale of the result and --reverse values
+    #[arg(short, long, default_value = "c")]
+    pub scale: String,
+
+    /// Convert a temperature to the RTD's resistance
+    #[arg(long)]
+    pub reverse: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SensorsArgs {
+    /// Scale to show the temperatures in
+    #[arg(short, long, default_value = "c")]
+    pub scale: String,
+
+    /// Where sysfs is mounted
+    #[arg(long, value_name = "PATH", default_value = DEFAULT_SYSFS_ROOT)]
+    pub root: PathBuf,
+
+    /// Output format (text, markdown, csv or json)
+    #[arg(long, default_value = "text")]
+    pub format: TableFormat,
+}
+
+#[derive(Debug, Args)]
+pub struct MetarArgs {
+    /// File to read reports from, one per line (defaults to stdin)
+    pub input: Option<PathBuf>,
+
+    /// Scale to show the temperatures in
+    #[arg(short, long, default_value = "c")]
+    pub scale: String,
+
+    /// Output format (text, markdown, csv or json)
+    #[arg(long, default_value = "text")]
+    pub format: TableFormat,
+}
+
+#[derive(Debug, Args)]
+pub struct ColorArgs {
+    /// Color temperatures, from 1000 K to 40000 K
+    #[arg(required = true, allow_negative_numbers = true)]
+    pub temperatures: Vec<String>,
+
+    /// Scale of the color temperatures
+    #[arg(short, long, default_value = "k")]
+    pub scale: String,
+
+    /// Output format (text, markdown, csv or json)
+    #[arg(long, default_value = "text")]
+    pub format: TableFormat,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    /// Read a sysfs temperature file, in millidegrees celsius
+    #[arg(long, value_name = "PATH", conflicts_with = "file")]
+    pub sensor: Option<PathBuf>,
+
+    /// Read a file holding one value, which is rewritten as it changes
+    /// [default: read one value per line from stdin]
+    #[arg(long, value_name = "PATH")]
+    pub file: Option<PathBuf>,
+
+    /// Scale the file or stdin values are in
+    #[arg(long, default_value = "c", conflicts_with = "sensor")]
+    pub from: String,
+
+    /// Scale to print readings and give thresholds in
+    #[arg(long, default_value = "c")]
+    pub to: String,
+
+    /// Seconds between readings of a sensor or file
+    #[arg(long, value_name = "SECONDS", default_value = "1", value_parser = parse_interval)]
+    pub interval: Duration,
+
+    /// Stop after this many readings
+    #[arg(long, value_name = "N")]
+    pub count: Option<usize>,
+
+    /// Alert when a reading is above this value
+    #[arg(long, value_name = "VAL", allow_negative_numbers = true)]
+    pub above: Vec<String>,
+
+    /// Alert when a reading is below this value
+    #[arg(long, value_name = "VAL", allow_negative_numbers = true)]
+    pub below: Vec<String>,
+
+    /// How far back past a threshold a reading must go to clear its alert
+    #[arg(long, value_name = "VAL", default_value = "1")]
+    pub hysteresis: String,
+
+    /// Append alerts to this file instead of printing them to stderr
+    #[arg(long, value_name = "PATH")]
+    pub alert_log: Option<PathBuf>,
+
+    /// Run this shell command for each alert instead of printing it to
+    /// stderr
+    #[arg(long, value_name = "COMMAND")]
+    pub alert_command: Option<String>,
+}
+
+fn parse_interval(input: &str) -> Result<Duration, String> {
+    input
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("expected a number of seconds, got {}", input))
+}
@@ -0,0 +1,394 @@
+//! The color of light from a blackbody at a color temperature: its
+//! chromaticity on the Planckian locus and the sRGB color that matches it.
+
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+use crate::{
+    format::NumberFormat,
+    locale::Locale,
+    table::{TableFormat, json_string},
+    temperature::{Scale, Temperature},
+};
+
+/// The color temperatures, in kelvin, the locus approximations are fitted
+/// for.
+const RANGE: (f64, f64) = (1000.0, 40000.0);
+
+/// Below this, Krystek's rational approximation is used; from it up, Kim et
+/// al.'s cubic spline. They agree to within 0.0002 in x and y here.
+const SPLINE_FROM: f64 = 4000.0;
+
+/// CIE XYZ to linear sRGB, for the D65 white point.
+const XYZ_TO_SRGB: [[f64; 3]; 3] = [
+    [3.2404542, -1.5371385, -0.4985314],
+    [-0.9692660, 1.8760108, 0.0415560],
+    [0.0556434, -0.2040259, 1.0572252],
+];
+
+/// A point on the CIE 1931 chromaticity diagram.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chromaticity {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Chromaticity {
+    /// The chromaticity of a blackbody at `temperature`, on the Planckian
+    /// locus.
+    ///
+    /// Up to 4000 K this is Krystek's (1985) rational approximation in the
+    /// CIE 1960 uv space; above it, the cubic spline of Kim et al. (2002),
+    /// which also holds up past its fitted 25000 K. Both are within 0.0003
+    /// of the locus integrated from Planck's law.
+    pub fn planckian(temperature: Temperature) -> Result<Self, ColorError> {
+        let t = temperature.to(Scale::Kelvin).value();
+        if !(RANGE.0..=RANGE.1).contains(&t) {
+            return Err(ColorError::OutOfRange(t));
+        }
+
+        if t < SPLINE_FROM {
+            let u = (0.860117757 + 1.54118254e-4 * t + 1.28641212e-7 * t * t)
+                / (1.0 + 8.42420235e-4 * t + 7.08145163e-7 * t * t);
+            let v = (0.317398726 + 4.22806245e-5 * t + 4.20481691e-8 * t * t)
+                / (1.0 - 2.89741816e-5 * t + 1.61456053e-7 * t * t);
+            let d = 2.0 * u - 8.0 * v + 4.0;
+            return Ok(Chromaticity {
+                x: 3.0 * u / d,
+                y: 2.0 * v / d,
+            });
+        }
+
+        let x = -3.0258469e9 / t.powi(3) + 2.1070379e6 / t.powi(2) + 0.2226347e3 / t + 0.240390;
+        let y = 3.0817580 * x.powi(3) - 5.87338670 * x.powi(2) + 3.75112997 * x - 0.37001483;
+        Ok(Chromaticity { x, y })
+    }
+
+    /// The sRGB color with this chromaticity, as bright as it can be.
+    ///
+    /// Colors outside the sRGB gamut, such as the deep reds of the coolest
+    /// blackbodies, have their negative channels clipped to zero.
+    pub fn to_srgb(self) -> Rgb {
+        let xyz = [self.x / self.y, 1.0, (1.0 - self.x - self.y) / self.y];
+        let linear = XYZ_TO_SRGB.map(|row| {
+            let value: f64 = row.iter().zip(xyz).map(|(m, c)| m * c).sum();
+            value.max(0.0)
+        });
+        let brightest = linear.iter().copied().fold(0.0, f64::max);
+        let [red, green, blue] = linear.map(|value| encode(value / brightest));
+        Rgb { red, green, blue }
+    }
+}
+
+/// Applies the sRGB transfer function to a linear channel from 0 to 1.
+fn encode(linear: f64) -> u8 {
+    let encoded = if linear <= 0.0031308 {
+        12.92 * linear
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded * 255.0).round() as u8
+}
+
+/// A gamma-encoded sRGB color with 8 bits per channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Rgb {
+    /// The color as a hex triplet, e.g. "#ffad58".
+    pub fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+
+    /// A block of this color for a terminal with 24-bit color.
+    pub fn swatch(self) -> String {
+        format!(
+            "\x1b[48;2;{};{};{}m      \x1b[0m",
+            self.red, self.green, self.blue
+        )
+    }
+}
+
+/// The color of a blackbody at a color temperature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlackbodyColor {
+    pub temperature: Temperature,
+    pub chromaticity: Chromaticity,
+    pub rgb: Rgb,
+}
+
+/// The color of light from a blackbody at `temperature`, which may be on any
+/// scale.
+pub fn blackbody_color(temperature: Temperature) -> Result<BlackbodyColor, ColorError> {
+    let chromaticity = Chromaticity::planckian(temperature)?;
+    Ok(BlackbodyColor {
+        temperature,
+        chromaticity,
+        rgb: chromaticity.to_srgb(),
+    })
+}
+
+/// Writes the colors as a table of temperatures in `scale`, hex triplets,
+/// RGB channels and chromaticities. As with conversion tables, CSV and JSON
+/// always use a decimal point and no grouping. With `swatches`, text tables
+/// end each row with a block of the color.
+pub fn write_colors<W: Write>(
+    colors: &[BlackbodyColor],
+    scale: Scale,
+    format: TableFormat,
+    number_format: &NumberFormat,
+    swatches: bool,
+    writer: &mut W,
+) -> io::Result<()> {
+    let number_format = match format {
+        TableFormat::Csv | TableFormat::Json => number_format.with_locale(Locale::Posix),
+        TableFormat::Text | TableFormat::Markdown => *number_format,
+    };
+    let coordinate = |value: f64| number_format.locale.localize(&format!("{:.4}", value));
+    let rows: Vec<(String, String, String)> = colors
+        .iter()
+        .map(|color| {
+            (
+                number_format.format(color.temperature.to(scale).value()),
+                coordinate(color.chromaticity.x),
+                coordinate(color.chromaticity.y),
+            )
+        })
+        .collect();
+
+    match format {
+        TableFormat::Text => {
+            let width = rows
+                .iter()
+                .map(|(temperature, _, _)| temperature.chars().count())
+                .chain([scale.symbol().chars().count()])
+                .max()
+                .unwrap_or(0);
+            writeln!(
+                writer,
+                "{:>width$}  hex      red  green  blue       x       y",
+                scale.symbol()
+            )?;
+            for (color, (temperature, x, y)) in colors.iter().zip(&rows) {
+                let Rgb { red, green, blue } = color.rgb;
+                write!(
+                    writer,
+                    "{:>width$}  {}  {:>3}  {:>5}  {:>4}  {:>6}  {:>6}",
+                    temperature,
+                    color.rgb.hex(),
+                    red,
+                    green,
+                    blue,
+                    x,
+                    y
+                )?;
+                if swatches {
+                    write!(writer, "  {}", color.rgb.swatch())?;
+                }
+                writeln!(writer)?;
+            }
+        }
+        TableFormat::Markdown => {
+            writeln!(
+                writer,
+                "| {} | hex | red | green | blue | x | y |",
+                scale.symbol()
+            )?;
+            writeln!(writer, "| ---: | --- | ---: | ---: | ---: | ---: | ---: |")?;
+            for (color, (temperature, x, y)) in colors.iter().zip(&rows) {
+                let Rgb { red, green, blue } = color.rgb;
+                writeln!(
+                    writer,
+                    "| {} | {} | {} | {} | {} | {} | {} |",
+                    temperature,
+                    color.rgb.hex(),
+                    red,
+                    green,
+                    blue,
+                    x,
+                    y
+                )?;
+            }
+        }
+        TableFormat::Csv => {
+            writeln!(writer, "scale,temperature,hex,red,green,blue,x,y")?;
+            for (color, (temperature, x, y)) in colors.iter().zip(&rows) {
+                let Rgb { red, green, blue } = color.rgb;
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{},{},{}",
+                    scale.name(),
+                    temperature,
+                    color.rgb.hex(),
+                    red,
+                    green,
+                    blue,
+                    x,
+                    y
+                )?;
+            }
+        }
+        TableFormat::Json => {
+            writeln!(writer, "[")?;
+            for (i, (color, (temperature, x, y))) in colors.iter().zip(&rows).enumerate() {
+                let Rgb { red, green, blue } = color.rgb;
+                let separator = if i + 1 < rows.len() { "," } else { "" };
+                writeln!(
+                    writer,
+                    "  {{ \"scale\": {}, \"temperature\": {}, \"hex\": {}, \"rgb\": [{}, {}, {}], \"x\": {}, \"y\": {} }}{}",
+                    json_string(scale.name()),
+                    temperature,
+                    json_string(&color.rgb.hex()),
+                    red,
+                    green,
+                    blue,
+                    x,
+                    y,
+                    separator
+                )?;
+            }
+            writeln!(writer, "]")?;
+        }
+    }
+    Ok(())
+}
+
+/// Reasons a color temperature has no color.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorError {
+    /// The temperature, in kelvin, is outside 1000 K to 40000 K.
+    OutOfRange(f64),
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorError::OutOfRange(kelvin) => write!(
+                f,
+                "{} K is outside the color temperature range of {} K to {} K.",
+                kelvin, RANGE.0, RANGE.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ColorError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kelvin(value: f64) -> Temperature {
+        Temperature::new(value, Scale::Kelvin).expect("temperature should be valid")
+    }
+
+    fn assert_close(expected: f64, actual: f64, tolerance: f64) {
+        let diff = (expected - actual).abs();
+        assert!(
+            diff <= tolerance,
+            "expected={} actual={} diff={}",
+            expected,
+            actual,
+            diff
+        );
+    }
+
+    // Points on the Planckian locus, integrated from Planck's law with the
+    // CIE 1931 observer. 2856 K is illuminant A.
+    const LOCUS: [(f64, f64, f64); 4] = [
+        (1000.0, 0.6528, 0.3444),
+        (2856.0, 0.4476, 0.4075),
+        (6500.0, 0.3135, 0.3236),
+        (10000.0, 0.2807, 0.2884),
+    ];
+
+    #[test]
+    fn chromaticities_lie_on_the_planckian_locus() {
+        for (t, x, y) in LOCUS {
+            let chromaticity = Chromaticity::planckian(kelvin(t)).unwrap();
+            assert_close(x, chromaticity.x, 0.0005);
+            assert_close(y, chromaticity.y, 0.0005);
+        }
+
+        // The two approximations meet where they hand over.
+        let below = Chromaticity::planckian(kelvin(SPLINE_FROM - 1e-6)).unwrap();
+        let above = Chromaticity::planckian(kelvin(SPLINE_FROM)).unwrap();
+        assert_close(below.x, above.x, 0.0002);
+        assert_close(below.y, above.y, 0.0002);
+    }
+
+    #[test]
+    fn colors_run_from_red_to_blue() {
+        let hex = |t| blackbody_color(kelvin(t)).unwrap().rgb.hex();
+
+        assert_eq!(hex(1000.0), "#ff1700");
+        assert_eq!(hex(2700.0), "#ffad58");
+        assert_eq!(hex(6500.0), "#fff9fe");
+        assert_eq!(hex(10000.0), "#cdd9ff");
+        assert_eq!(hex(40000.0), "#9eb8ff");
+    }
+
+    #[test]
+    fn temperatures_on_any_scale_are_accepted() {
+        let celsius = Temperature::new(2426.85, Scale::Celsius).unwrap();
+        let color = blackbody_color(celsius).unwrap();
+
+        assert_eq!(color.temperature, celsius);
+        assert_eq!(color.rgb, blackbody_color(kelvin(2700.0)).unwrap().rgb);
+    }
+
+    #[test]
+    fn rejects_temperatures_outside_the_range() {
+        assert_eq!(
+            blackbody_color(kelvin(500.0)),
+            Err(ColorError::OutOfRange(500.0))
+        );
+        assert!(matches!(
+            blackbody_color(kelvin(50000.0)),
+            Err(ColorError::OutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn writes_tables_with_optional_swatches() {
+        let colors = [blackbody_color(kelvin(2700.0)).unwrap()];
+        let write = |format, swatches| {
+            let mut out = Vec::new();
+            write_colors(
+                &colors,
+                Scale::Kelvin,
+                format,
+                &NumberFormat::default(),
+                swatches,
+                &mut out,
+            )
+            .unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(
+            write(TableFormat::Text, false),
+            "      K  hex      red  green  blue       x       y\n\
+             2700.00  #ffad58  255    173    88  0.4600  0.4110\n"
+        );
+        assert!(
+            write(TableFormat::Text, true)
+                .ends_with("0.4110  \x1b[48;2;255;173;88m      \x1b[0m\n")
+        );
+        assert_eq!(
+            write(TableFormat::Csv, true),
+            "scale,temperature,hex,red,green,blue,x,y\n\
+             kelvin,2700.00,#ffad58,255,173,88,0.4600,0.4110\n"
+        );
+        assert_eq!(
+            write(TableFormat::Json, false),
+            "[\n  { \"scale\": \"kelvin\", \"temperature\": 2700.00, \"hex\": \"#ffad58\", \"rgb\": [255, 173, 88], \"x\": 0.4600, \"y\": 0.4110 }\n]\n"
+        );
+    }
+}
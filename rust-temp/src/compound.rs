@@ -0,0 +1,292 @@
+use crate::{
+    dimension::DimensionVector,
+    error::ConversionError,
+    units::{Conversion, Dimension, Unit, UnitRegistry},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    Name(&'a str),
+    Integer(i8),
+    /// An exponent written with superscript digits, e.g. the `²` in `m²`.
+    Superscript(i8),
+    Multiply,
+    Divide,
+    Power,
+    Open,
+    Close,
+}
+
+/// Parses a compound unit expression such as `kg*m/s^2`, `m·s⁻¹` or
+/// `BTU/(h*ft*°F)` into a linear unit named after the expression.
+///
+/// `*`, `·` and `×` multiply, `/` divides, and `^` or superscript digits
+/// raise to an integer power. `1` may stand in for a dimensionless unit, as
+/// in `1/s`. Offsets are dropped from temperature units, so `°F` inside a
+/// compound unit means a difference of one degree fahrenheit.
+pub(crate) fn parse(expression: &str, registry: &UnitRegistry) -> Result<Unit, ConversionError> {
+    let malformed = |reason| ConversionError::MalformedUnit {
+        unit: expression.to_string(),
+        reason,
+    };
+
+    let tokens = tokenize(expression).ok_or_else(|| malformed("exponent out of range"))?;
+    if tokens.is_empty() {
+        return Err(ConversionError::UnknownUnit(expression.to_string()));
+    }
+
+    let mut parser = Parser {
+        tokens: &tokens,
+        position: 0,
+        registry,
+        malformed: &malformed,
+    };
+    let (factor, vector) = parser.product()?;
+    if parser.position < tokens.len() {
+        return Err(malformed(match tokens[parser.position] {
+            Token::Close => "unmatched closing parenthesis",
+            _ => "expected `*` or `/` between units",
+        }));
+    }
+
+    let symbol = expression.trim();
+    Ok(Unit::new(
+        symbol,
+        symbol,
+        Dimension::from_vector(vector),
+        Conversion::Linear(factor),
+    ))
+}
+
+/// A recursive descent parser over the grammar
+///
+/// ```text
+/// product := power (("*" | "/") power)*
+/// power   := primary ("^" integer | superscript)?
+/// primary := name | "1" | "(" product ")"
+/// ```
+///
+/// where each rule yields the unit's size in base units and its dimension.
+struct Parser<'t, 'a, F> {
+    tokens: &'t [Token<'a>],
+    position: usize,
+    registry: &'t UnitRegistry,
+    malformed: &'t F,
+}
+
+impl<'a, F> Parser<'_, 'a, F>
+where
+    F: Fn(&'static str) -> ConversionError,
+{
+    fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.tokens.get(self.position).copied();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn product(&mut self) -> Result<(f64, DimensionVector), ConversionError> {
+        let (mut factor, mut vector) = self.power()?;
+
+        loop {
+            match self.peek() {
+                Some(Token::Multiply) => {
+                    self.position += 1;
+                    let (other_factor, other_vector) = self.power()?;
+                    factor *= other_factor;
+                    vector = vector * other_vector;
+                }
+                Some(Token::Divide) => {
+                    self.position += 1;
+                    let (other_factor, other_vector) = self.power()?;
+                    factor /= other_factor;
+                    vector = vector / other_vector;
+                }
+                _ => return Ok((factor, vector)),
+            }
+        }
+    }
+
+    fn power(&mut self) -> Result<(f64, DimensionVector), ConversionError> {
+        let (factor, vector) = self.primary()?;
+
+        let exponent = match self.peek() {
+            Some(Token::Superscript(exponent)) => {
+                self.position += 1;
+                exponent
+            }
+            Some(Token::Power) => {
+                self.position += 1;
+                match self.next() {
+                    Some(Token::Integer(exponent)) => exponent,
+                    _ => return Err((self.malformed)("expected an integer after `^`")),
+                }
+            }
+            _ => return Ok((factor, vector)),
+        };
+
+        Ok((factor.powi(exponent.into()), vector.powi(exponent)))
+    }
+
+    fn primary(&mut self) -> Result<(f64, DimensionVector), ConversionError> {
+        match self.next() {
+            Some(Token::Name(name)) => {
+                let unit = self.registry.get(name)?;
+                Ok((unit.conversion.factor(), unit.dimension.vector()))
+            }
+            Some(Token::Integer(1)) => Ok((1.0, DimensionVector::DIMENSIONLESS)),
+            Some(Token::Open) => {
+                let inner = self.product()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(inner),
+                    _ => Err((self.malformed)("unclosed parenthesis")),
+                }
+            }
+            _ => Err((self.malformed)("expected a unit")),
+        }
+    }
+}
+
+/// Splits a unit expression into tokens. Returns `None` if an exponent does
+/// not fit in an `i8`.
+fn tokenize(expression: &str) -> Option<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '*' | '·' | '×' => Token::Multiply,
+            '/' => Token::Divide,
+            '^' => Token::Power,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            c if superscript_digit(c).is_some() || c == '⁻' => {
+                let mut text = String::new();
+                text.push(if c == '⁻' { '-' } else { digit_char(c) });
+                while let Some(&(_, next)) = chars.peek() {
+                    match superscript_digit(next) {
+                        Some(_) => text.push(digit_char(next)),
+                        None => break,
+                    }
+                    chars.next();
+                }
+                Token::Superscript(text.parse().ok()?)
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '+' => {
+                let mut end = start + c.len_utf8();
+                while let Some(&(i, next)) = chars.peek() {
+                    if !next.is_ascii_digit() {
+                        break;
+                    }
+                    end = i + 1;
+                    chars.next();
+                }
+                Token::Integer(expression[start..end].parse().ok()?)
+            }
+            _ => {
+                // Names run until the next operator, so symbols with digits
+                // in them such as `m2` or `in3` stay whole.
+                let mut end = start + c.len_utf8();
+                while let Some(&(i, next)) = chars.peek() {
+                    if next.is_whitespace()
+                        || "*·×/^()⁻".contains(next)
+                        || superscript_digit(next).is_some()
+                    {
+                        break;
+                    }
+                    end = i + next.len_utf8();
+                    chars.next();
+                }
+                Token::Name(&expression[start..end])
+            }
+        };
+        tokens.push(token);
+    }
+
+    Some(tokens)
+}
+
+fn superscript_digit(c: char) -> Option<u32> {
+    "⁰¹²³⁴⁵⁶⁷⁸⁹"
+        .chars()
+        .position(|digit| digit == c)
+        .map(|position| position as u32)
+}
+
+/// The ASCII digit for a superscript digit.
+fn digit_char(c: char) -> char {
+    superscript_digit(c)
+        .and_then(|digit| char::from_digit(digit, 10))
+        .unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn factor_of(expression: &str) -> f64 {
+        parse(expression, UnitRegistry::builtin())
+            .expect("unit should parse")
+            .conversion
+            .factor()
+    }
+
+    #[test]
+    fn tokenizes_operators_exponents_and_names() {
+        assert_eq!(
+            tokenize("kg*m2/s^-2").unwrap(),
+            vec![
+                Token::Name("kg"),
+                Token::Multiply,
+                Token::Name("m2"),
+                Token::Divide,
+                Token::Name("s"),
+                Token::Power,
+                Token::Integer(-2),
+            ]
+        );
+        assert_eq!(
+            tokenize("m·s⁻¹").unwrap(),
+            vec![
+                Token::Name("m"),
+                Token::Multiply,
+                Token::Name("s"),
+                Token::Superscript(-1),
+            ]
+        );
+        assert_eq!(tokenize("m^300"), None);
+    }
+
+    #[test]
+    fn multiplies_divides_and_raises_factors() {
+        assert_eq!(factor_of("km/h"), 1e3 / 3600.0);
+        assert!((factor_of("cm^3") - 1e-6).abs() < 1e-18);
+        assert_eq!(factor_of("m/s/s"), 1.0);
+        assert_eq!(factor_of("(m/s)^2"), 1.0);
+        assert_eq!(factor_of("1/ms"), 1e3);
+    }
+
+    #[test]
+    fn temperature_units_contribute_their_degree_size() {
+        assert_eq!(factor_of("J/°C"), 1.0);
+        assert!((factor_of("J/°F") - 1.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn reports_what_is_malformed() {
+        let reason = |expression| match parse(expression, UnitRegistry::builtin()) {
+            Err(ConversionError::MalformedUnit { reason, .. }) => reason,
+            other => panic!("{}: expected a malformed unit, got {:?}", expression, other),
+        };
+
+        assert_eq!(reason("m/(s"), "unclosed parenthesis");
+        assert_eq!(reason("m/s)"), "unmatched closing parenthesis");
+        assert_eq!(reason("m^"), "expected an integer after `^`");
+        assert_eq!(reason("m*"), "expected a unit");
+        assert_eq!(reason("(m)(s)"), "expected `*` or `/` between units");
+    }
+}
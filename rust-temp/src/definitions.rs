@@ -0,0 +1,434 @@
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::units::{Conversion, Dimension, Unit, UnitRegistry};
+
+/// The layout of a unit definitions file.
+///
+/// ```toml
+/// [units."°Bw"]
+/// name = "brewing degree"
+/// aliases = ["brew"]
+/// unit = "°C"      # defined in terms of another unit...
+/// factor = 0.8
+/// offset = -10.0
+///
+/// [units.span]
+/// dimension = "length"   # ...or directly against the dimension's base unit
+/// factor = 0.2286
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DefinitionsFile {
+    #[serde(default)]
+    units: BTreeMap<String, Definition>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Definition {
+    name: Option<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    dimension: Option<String>,
+    /// Another unit this one is defined in terms of.
+    unit: Option<String>,
+    #[serde(default = "default_factor")]
+    factor: f64,
+    #[serde(default)]
+    offset: f64,
+}
+
+fn default_factor() -> f64 {
+    1.0
+}
+
+/// Reasons a unit definitions file could not be loaded.
+#[derive(Debug)]
+pub enum DefinitionError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    Parse(String),
+    UnknownDimension {
+        unit: String,
+        dimension: String,
+    },
+    UnknownReference {
+        unit: String,
+        reference: String,
+    },
+    /// A definition refers back to itself, directly or through other units.
+    /// Holds the chain of symbols that forms the loop.
+    Cycle(Vec<String>),
+    /// The definition is missing information or has an unusable factor.
+    Invalid {
+        unit: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DefinitionError::Io { path, error } => {
+                write!(f, "Could not read {}: {}.", path.display(), error)
+            }
+            DefinitionError::Parse(message) => {
+                write!(f, "Invalid unit definitions: {}", message.trim_end())
+            }
+            DefinitionError::UnknownDimension { unit, dimension } => {
+                write!(f, "Unit {} has unknown dimension {}.", unit, dimension)
+            }
+            DefinitionError::UnknownReference { unit, reference } => {
+                write!(
+                    f,
+                    "Unit {} is defined in terms of unknown unit {}.",
+                    unit, reference
+                )
+            }
+            DefinitionError::Cycle(chain) => {
+                write!(f, "Unit definitions form a cycle: {}.", chain.join(" -> "))
+            }
+            DefinitionError::Invalid { unit, reason } => write!(f, "Unit {} {}.", unit, reason),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {}
+
+/// Where the user's unit definitions live by default:
+/// `$XDG_CONFIG_HOME/rust-convert/units.toml`, falling back to
+/// `~/.config/rust-convert/units.toml`.
+pub fn default_units_path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_home.join("rust-convert").join("units.toml"))
+}
+
+/// Reads unit definitions from a TOML file into `registry`, returning how
+/// many units were added.
+pub fn load_units_file(path: &Path, registry: &mut UnitRegistry) -> Result<usize, DefinitionError> {
+    let contents = fs::read_to_string(path).map_err(|error| DefinitionError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    load_units(&contents, registry)
+}
+
+/// Parses unit definitions from TOML into `registry`, returning how many
+/// units were added.
+///
+/// Definitions may refer to built-in units, units already in the registry,
+/// or other units in the same file, in any order. Nothing is added unless
+/// every definition resolves.
+pub fn load_units(toml: &str, registry: &mut UnitRegistry) -> Result<usize, DefinitionError> {
+    let file: DefinitionsFile =
+        toml::from_str(toml).map_err(|error| DefinitionError::Parse(error.to_string()))?;
+
+    let mut resolver = Resolver {
+        definitions: &file.units,
+        registry,
+        resolved: BTreeMap::new(),
+        stack: Vec::new(),
+    };
+    for symbol in file.units.keys() {
+        resolver.resolve(symbol)?;
+    }
+
+    let units: Vec<Unit> = resolver.resolved.into_values().collect();
+    let count = units.len();
+    for unit in units {
+        registry.register(unit);
+    }
+
+    Ok(count)
+}
+
+struct Resolver<'a> {
+    definitions: &'a BTreeMap<String, Definition>,
+    registry: &'a UnitRegistry,
+    resolved: BTreeMap<String, Unit>,
+    /// Symbols currently being resolved, used to spot cycles.
+    stack: Vec<String>,
+}
+
+impl Resolver<'_> {
+    fn resolve(&mut self, symbol: &str) -> Result<Unit, DefinitionError> {
+        if let Some(unit) = self.resolved.get(symbol) {
+            return Ok(unit.clone());
+        }
+        if let Some(start) = self.stack.iter().position(|pending| pending == symbol) {
+            let mut chain = self.stack[start..].to_vec();
+            chain.push(symbol.to_string());
+            return Err(DefinitionError::Cycle(chain));
+        }
+
+        let definition = &self.definitions[symbol];
+        let invalid = |reason: &str| DefinitionError::Invalid {
+            unit: symbol.to_string(),
+            reason: reason.to_string(),
+        };
+        if !definition.factor.is_finite() || definition.factor == 0.0 {
+            return Err(invalid("needs a finite, non-zero factor"));
+        }
+        if !definition.offset.is_finite() {
+            return Err(invalid("needs a finite offset"));
+        }
+
+        let declared_dimension = definition
+            .dimension
+            .as_deref()
+            .map(|dimension| {
+                dimension
+                    .parse::<Dimension>()
+                    .map_err(|_| DefinitionError::UnknownDimension {
+                        unit: symbol.to_string(),
+                        dimension: dimension.to_string(),
+                    })
+            })
+            .transpose()?;
+
+        let (dimension, conversion) = match &definition.unit {
+            Some(reference) => {
+                self.stack.push(symbol.to_string());
+                let base = self.resolve_reference(symbol, reference);
+                self.stack.pop();
+                let base = base?;
+
+                if let Some(declared) = declared_dimension
+                    && declared != base.dimension
+                {
+                    return Err(invalid(&format!(
+                        "is declared as {} but defined in terms of {} ({})",
+                        declared, base.symbol, base.dimension
+                    )));
+                }
+                let conversion = base.conversion.scaled(definition.factor, definition.offset);
+                (base.dimension, conversion)
+            }
+            None => {
+                let dimension = declared_dimension
+                    .ok_or_else(|| invalid("needs either a dimension or a unit to build on"))?;
+                let conversion =
+                    Conversion::Linear(1.0).scaled(definition.factor, definition.offset);
+                (dimension, conversion)
+            }
+        };
+
+        let name = definition
+            .name
+            .clone()
+            .unwrap_or_else(|| symbol.to_string());
+        let aliases: Vec<&str> = definition.aliases.iter().map(String::as_str).collect();
+        let unit = Unit::new(symbol, &name, dimension, conversion).with_aliases(&aliases);
+
+        self.resolved.insert(symbol.to_string(), unit.clone());
+        Ok(unit)
+    }
+
+    /// Finds the unit a definition builds on, preferring other definitions
+    /// in the same file over units already in the registry.
+    fn resolve_reference(
+        &mut self,
+        symbol: &str,
+        reference: &str,
+    ) -> Result<Unit, DefinitionError> {
+        let defined = self.definitions.iter().find(|(other, definition)| {
+            other.as_str() == reference || definition.aliases.iter().any(|alias| alias == reference)
+        });
+
+        match defined {
+            Some((other, _)) => self.resolve(other),
+            None => self.registry.find(reference).cloned().ok_or_else(|| {
+                DefinitionError::UnknownReference {
+                    unit: symbol.to_string(),
+                    reference: reference.to_string(),
+                }
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(toml: &str) -> Result<UnitRegistry, DefinitionError> {
+        let mut registry = UnitRegistry::default();
+        load_units(toml, &mut registry)?;
+        Ok(registry)
+    }
+
+    fn assert_close(expected: f64, actual: f64) {
+        let diff = (expected - actual).abs();
+        assert!(
+            diff <= 1e-9,
+            "expected={} actual={} diff={}",
+            expected,
+            actual,
+            diff
+        );
+    }
+
+    #[test]
+    fn loads_linear_units_against_the_base_unit() {
+        let registry = load(
+            r#"
+            [units.span]
+            name = "span"
+            aliases = ["spans"]
+            dimension = "length"
+            factor = 0.2286
+            "#,
+        )
+        .unwrap();
+
+        assert_close(22.86, registry.convert(1.0, "spans", "cm").unwrap());
+    }
+
+    #[test]
+    fn loads_affine_temperature_units() {
+        // A scale where water freezes at 0 and boils at 50.
+        let registry = load(
+            r#"
+            [units."°H"]
+            dimension = "temperature"
+            factor = 2.0
+            offset = 136.575
+            "#,
+        )
+        .unwrap();
+
+        assert_close(0.0, registry.convert(0.0, "°C", "°H").unwrap());
+        assert_close(50.0, registry.convert(100.0, "°C", "°H").unwrap());
+        assert_close(212.0, registry.convert(50.0, "°H", "°F").unwrap());
+    }
+
+    #[test]
+    fn loads_units_defined_in_terms_of_other_units() {
+        // "brew" reads 10 at 0 °C and rises 1.25 per degree celsius.
+        let registry = load(
+            r#"
+            [units.brew]
+            unit = "°C"
+            factor = 0.8
+            offset = -10.0
+
+            [units.halfbrew]
+            unit = "brew"
+            factor = 0.5
+            "#,
+        )
+        .unwrap();
+
+        assert_close(0.0, registry.convert(10.0, "brew", "°C").unwrap());
+        assert_close(100.0, registry.convert(135.0, "brew", "°C").unwrap());
+        assert_close(135.0, registry.convert(270.0, "halfbrew", "brew").unwrap());
+        assert_eq!(
+            registry.get("halfbrew").unwrap().dimension,
+            Dimension::Temperature
+        );
+    }
+
+    #[test]
+    fn custom_temperature_units_check_absolute_zero() {
+        let registry = load(
+            r#"
+            [units.brew]
+            unit = "°C"
+            factor = 0.8
+            offset = -10.0
+            "#,
+        )
+        .unwrap();
+
+        assert!(registry.convert(-500.0, "brew", "K").is_err());
+    }
+
+    #[test]
+    fn reports_unknown_references() {
+        let result = load(
+            r#"
+            [units.x]
+            unit = "furlong"
+            "#,
+        );
+
+        assert!(matches!(
+            result,
+            Err(DefinitionError::UnknownReference { unit, reference })
+                if unit == "x" && reference == "furlong"
+        ));
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let result = load(
+            r#"
+            [units.a]
+            unit = "b"
+
+            [units.b]
+            unit = "c"
+
+            [units.c]
+            unit = "a"
+            "#,
+        );
+
+        match result {
+            Err(DefinitionError::Cycle(chain)) => assert_eq!(chain, vec!["a", "b", "c", "a"]),
+            other => panic!("expected a cycle, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_unknown_dimensions_and_missing_information() {
+        let result = load("[units.x]\ndimension = \"happiness\"\n");
+        assert!(matches!(
+            result,
+            Err(DefinitionError::UnknownDimension { .. })
+        ));
+
+        let result = load("[units.x]\nfactor = 2.0\n");
+        assert!(matches!(result, Err(DefinitionError::Invalid { .. })));
+
+        let result = load("[units.x]\ndimension = \"mass\"\nfactor = 0.0\n");
+        assert!(matches!(result, Err(DefinitionError::Invalid { .. })));
+    }
+
+    #[test]
+    fn reports_mismatched_declared_dimensions() {
+        let result = load("[units.x]\ndimension = \"mass\"\nunit = \"km\"\n");
+        assert!(matches!(result, Err(DefinitionError::Invalid { .. })));
+    }
+
+    #[test]
+    fn reports_toml_syntax_errors() {
+        let result = load("[units.x\n");
+        assert!(matches!(result, Err(DefinitionError::Parse(_))));
+    }
+
+    #[test]
+    fn failed_loads_leave_the_registry_untouched() {
+        let mut registry = UnitRegistry::default();
+        let before = registry.units().len();
+        let result = load_units(
+            "[units.a]\ndimension = \"mass\"\n[units.b]\nunit = \"nope\"\n",
+            &mut registry,
+        );
+
+        assert!(result.is_err());
+        assert_eq!(registry.units().len(), before);
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    fmt,
+    ops::{Div, Mul},
+};
+
+/// The base quantities every unit is built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BaseDimension {
+    Length,
+    Mass,
+    Time,
+    Temperature,
+    /// Amounts of information, counted in bytes.
+    Data,
+}
+
+impl BaseDimension {
+    pub const ALL: [BaseDimension; 5] = [
+        BaseDimension::Length,
+        BaseDimension::Mass,
+        BaseDimension::Time,
+        BaseDimension::Temperature,
+        BaseDimension::Data,
+    ];
+
+    /// The conventional dimension symbol, e.g. "L" for length.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BaseDimension::Length => "L",
+            BaseDimension::Mass => "M",
+            BaseDimension::Time => "T",
+            BaseDimension::Temperature => "Θ",
+            BaseDimension::Data => "D",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The exponent of each base dimension in a unit, e.g. force is
+/// `L·M·T⁻²`. Units can be converted into each other when their vectors
+/// are equal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DimensionVector {
+    exponents: [i8; BaseDimension::ALL.len()],
+}
+
+impl DimensionVector {
+    /// The vector of a pure number, where every exponent is zero.
+    pub const DIMENSIONLESS: DimensionVector = DimensionVector {
+        exponents: [0; BaseDimension::ALL.len()],
+    };
+
+    /// The vector of a single base dimension.
+    pub const fn of(base: BaseDimension) -> Self {
+        let mut exponents = [0; BaseDimension::ALL.len()];
+        exponents[base as usize] = 1;
+        DimensionVector { exponents }
+    }
+
+    /// Builds a vector from `(base, exponent)` pairs.
+    pub fn from_exponents(exponents: &[(BaseDimension, i8)]) -> Self {
+        let mut vector = DimensionVector::DIMENSIONLESS;
+        for &(base, exponent) in exponents {
+            let slot = &mut vector.exponents[base.index()];
+            *slot = slot.saturating_add(exponent);
+        }
+        vector
+    }
+
+    pub fn exponent(&self, base: BaseDimension) -> i8 {
+        self.exponents[base.index()]
+    }
+
+    pub fn is_dimensionless(&self) -> bool {
+        *self == DimensionVector::DIMENSIONLESS
+    }
+
+    /// Raises the vector to an integer power, e.g. `L` squared is `L²`.
+    pub fn powi(self, power: i8) -> Self {
+        DimensionVector {
+            exponents: self
+                .exponents
+                .map(|exponent| exponent.saturating_mul(power)),
+        }
+    }
+}
+
+impl Mul for DimensionVector {
+    type Output = DimensionVector;
+
+    fn mul(self, other: DimensionVector) -> DimensionVector {
+        let mut exponents = self.exponents;
+        for (exponent, other) in exponents.iter_mut().zip(other.exponents) {
+            *exponent = exponent.saturating_add(other);
+        }
+        DimensionVector { exponents }
+    }
+}
+
+impl Div for DimensionVector {
+    type Output = DimensionVector;
+
+    fn div(self, other: DimensionVector) -> DimensionVector {
+        self * other.powi(-1)
+    }
+}
+
+impl fmt::Display for DimensionVector {
+    /// Writes the vector in the usual notation, e.g. `L²·M·T⁻²`, or
+    /// "dimensionless" when every exponent is zero.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_dimensionless() {
+            return f.write_str("dimensionless");
+        }
+
+        let terms: Vec<String> = BaseDimension::ALL
+            .iter()
+            .filter(|base| self.exponent(**base) != 0)
+            .map(|base| match self.exponent(*base) {
+                1 => base.symbol().to_string(),
+                exponent => format!("{}{}", base.symbol(), superscript(exponent)),
+            })
+            .collect();
+        f.write_str(&terms.join("·"))
+    }
+}
+
+/// Writes an integer with Unicode superscript digits, e.g. `-2` as `⁻²`.
+fn superscript(number: i8) -> String {
+    number
+        .to_string()
+        .chars()
+        .map(|c| match c {
+            '-' => '⁻',
+            '0' => '⁰',
+            '1' => '¹',
+            '2' => '²',
+            '3' => '³',
+            '4' => '⁴',
+            '5' => '⁵',
+            '6' => '⁶',
+            '7' => '⁷',
+            '8' => '⁸',
+            '9' => '⁹',
+            other => other,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use BaseDimension::*;
+
+    #[test]
+    fn multiplies_and_divides_by_adding_exponents() {
+        let length = DimensionVector::of(Length);
+        let time = DimensionVector::of(Time);
+        let force = DimensionVector::of(Mass) * length / time.powi(2);
+
+        assert_eq!(force.exponent(Length), 1);
+        assert_eq!(force.exponent(Mass), 1);
+        assert_eq!(force.exponent(Time), -2);
+        assert_eq!(
+            force,
+            DimensionVector::from_exponents(&[(Length, 1), (Mass, 1), (Time, -2)])
+        );
+        assert!((length / length).is_dimensionless());
+    }
+
+    #[test]
+    fn displays_with_superscript_exponents() {
+        let conductivity = DimensionVector::from_exponents(&[
+            (Length, 1),
+            (Mass, 1),
+            (Time, -3),
+            (Temperature, -1),
+        ]);
+
+        assert_eq!(conductivity.to_string(), "L·M·T⁻³·Θ⁻¹");
+        assert_eq!(DimensionVector::of(Data).to_string(), "D");
+        assert_eq!(DimensionVector::DIMENSIONLESS.to_string(), "dimensionless");
+    }
+}
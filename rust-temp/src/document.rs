@@ -0,0 +1,1233 @@
+//! Converting fields inside JSON and CSV documents, copying everything else
+//! through byte for byte.
+
+use std::{
+    fmt,
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+use crate::{
+    batch::BatchSummary,
+    calibration::Calibration,
+    error::ConversionError,
+    format::NumberFormat,
+    locale::Locale,
+    table::{csv_field, json_string},
+    units::{Quantity, Unit},
+};
+
+/// Where a converted value is written.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FieldTarget {
+    /// Over the original value.
+    #[default]
+    Replace,
+    /// Over the original value, with the field or column renamed.
+    Rename(String),
+    /// Into a new field or column after the original, which is kept.
+    Add(String),
+}
+
+/// Settings for converting the fields of a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentOptions {
+    pub from: Unit,
+    pub to: Unit,
+    pub target: FieldTarget,
+    /// The field delimiter of CSV documents.
+    pub delimiter: char,
+    /// Stop at the first bad value instead of leaving it as it was.
+    pub strict: bool,
+    /// How converted values are written. Documents are machine-readable,
+    /// so values are always read and written with a decimal point whatever
+    /// the format's locale.
+    pub number_format: NumberFormat,
+    /// Correct each value with this calibration before converting it.
+    pub calibration: Option<Calibration>,
+}
+
+impl DocumentOptions {
+    pub fn new(from: Unit, to: Unit) -> Self {
+        DocumentOptions {
+            from,
+            to,
+            target: FieldTarget::Replace,
+            delimiter: ',',
+            strict: false,
+            number_format: NumberFormat::default(),
+            calibration: None,
+        }
+    }
+}
+
+/// Reasons a document could not be converted.
+#[derive(Debug)]
+pub enum DocumentError {
+    Io(io::Error),
+    /// The JSON path could not be parsed.
+    InvalidPath(String),
+    /// Renaming or adding a field needs a path that ends in a field name,
+    /// not an array index or wildcard.
+    UnnamedField(String),
+    /// The CSV header row has no column with the requested name.
+    MissingColumn(String),
+    /// The document is not valid JSON.
+    Json {
+        line: usize,
+        column: usize,
+    },
+    /// A JSON value could not be converted and the conversion is strict.
+    Field {
+        path: String,
+        error: ConversionError,
+    },
+    /// A CSV value could not be converted and the conversion is strict.
+    Line {
+        line: usize,
+        error: ConversionError,
+    },
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocumentError::Io(error) => write!(f, "{}", error),
+            DocumentError::InvalidPath(path) => write!(f, "Invalid JSON path: {}.", path),
+            DocumentError::UnnamedField(path) => write!(
+                f,
+                "The path {} must end in a field name to rename or add a field.",
+                path
+            ),
+            DocumentError::MissingColumn(name) => write!(f, "No column named {} in header.", name),
+            DocumentError::Json { line, column } => {
+                write!(f, "Invalid JSON at line {}, column {}.", line, column)
+            }
+            DocumentError::Field { path, error } => write!(f, "{}: {}", path, error),
+            DocumentError::Line { line, error } => write!(f, "line {}: {}", line, error),
+        }
+    }
+}
+
+impl std::error::Error for DocumentError {}
+
+impl From<io::Error> for DocumentError {
+    fn from(error: io::Error) -> Self {
+        DocumentError::Io(error)
+    }
+}
+
+/// One step of a [`JsonPath`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    /// `.name` or `['name']`.
+    Field(String),
+    /// `[3]`.
+    Index(usize),
+    /// `.*` or `[*]`: any one field or element.
+    Any,
+    /// `..`: any number of levels, including none.
+    Descendants,
+}
+
+/// Where a value sits in a JSON document, one object key or array index
+/// per level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// The subset of JSONPath used to pick values out of a document: `$`,
+/// `.name`, `['name']`, `[3]`, the wildcards `.*` and `[*]`, and `..` for
+/// any depth, e.g. `$.readings[*].temp_f` or `$..temp_f`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    text: String,
+    steps: Vec<Step>,
+}
+
+impl JsonPath {
+    /// The name of the field the path ends in, if it ends in one.
+    pub fn field_name(&self) -> Option<&str> {
+        match self.steps.last() {
+            Some(Step::Field(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, path: &[Segment]) -> bool {
+        matches_steps(&self.steps, path)
+    }
+}
+
+fn matches_steps(steps: &[Step], path: &[Segment]) -> bool {
+    match (steps.split_first(), path.split_first()) {
+        (None, _) => path.is_empty(),
+        (Some((Step::Descendants, rest)), _) => {
+            matches_steps(rest, path) || (!path.is_empty() && matches_steps(steps, &path[1..]))
+        }
+        (Some(_), None) => false,
+        (Some((step, rest)), Some((segment, remaining))) => {
+            let hit = match (step, segment) {
+                (Step::Any, _) => true,
+                (Step::Field(name), Segment::Key(key)) => name == key,
+                (Step::Index(index), Segment::Index(at)) => index == at,
+                _ => false,
+            };
+            hit && matches_steps(rest, remaining)
+        }
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = DocumentError;
+
+    /// Parses a path. The leading `$` may be left out, so `readings[*].temp_f`
+    /// is read as `$.readings[*].temp_f`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || DocumentError::InvalidPath(input.to_string());
+        let text = input.trim();
+        let rest = match text.strip_prefix('$') {
+            Some(rest) => rest.to_string(),
+            None if text.starts_with(['.', '[']) => text.to_string(),
+            None => format!(".{}", text),
+        };
+
+        let mut steps = Vec::new();
+        let mut chars = rest.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => {
+                    if chars.peek() == Some(&'.') {
+                        chars.next();
+                        steps.push(Step::Descendants);
+                        if chars.peek() == Some(&'[') {
+                            continue;
+                        }
+                    }
+                    let mut name = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c == '.' || c == '[' {
+                            break;
+                        }
+                        name.push(c);
+                        chars.next();
+                    }
+                    match name.as_str() {
+                        "" => return Err(invalid()),
+                        "*" => steps.push(Step::Any),
+                        _ => steps.push(Step::Field(name)),
+                    }
+                }
+                '[' => {
+                    let step = match chars.next() {
+                        Some(quote @ ('\'' | '"')) => {
+                            let mut name = String::new();
+                            loop {
+                                match chars.next() {
+                                    Some('\\') => name.push(chars.next().ok_or_else(invalid)?),
+                                    Some(c) if c == quote => break,
+                                    Some(c) => name.push(c),
+                                    None => return Err(invalid()),
+                                }
+                            }
+                            Step::Field(name)
+                        }
+                        Some('*') => Step::Any,
+                        Some(c) if c.is_ascii_digit() => {
+                            let mut digits = c.to_string();
+                            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                                digits.push(c);
+                                chars.next();
+                            }
+                            Step::Index(digits.parse().map_err(|_| invalid())?)
+                        }
+                        _ => return Err(invalid()),
+                    };
+                    if chars.next() != Some(']') {
+                        return Err(invalid());
+                    }
+                    steps.push(step);
+                }
+                _ => return Err(invalid()),
+            }
+        }
+
+        if steps.last() == Some(&Step::Descendants) {
+            return Err(invalid());
+        }
+        Ok(JsonPath {
+            text: text.to_string(),
+            steps,
+        })
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Writes a concrete location such as `$.readings[3].temp_f`.
+fn display_path(path: &[Segment]) -> String {
+    let mut text = String::from("$");
+    for segment in path {
+        match segment {
+            Segment::Key(key)
+                if !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+            {
+                text.push('.');
+                text.push_str(key);
+            }
+            Segment::Key(key) => {
+                text.push_str(&format!("['{}']", key.replace('\'', "\\'")));
+            }
+            Segment::Index(index) => text.push_str(&format!("[{}]", index)),
+        }
+    }
+    text
+}
+
+/// Converts the value in `text`, returning it written with the options'
+/// number format in the Posix locale.
+fn convert_value(text: &str, options: &DocumentOptions) -> Result<String, ConversionError> {
+    let quantity = Quantity::parse(text, &options.from)?;
+    let quantity = match &options.calibration {
+        Some(calibration) => Quantity::new(calibration.apply(quantity.value), quantity.unit),
+        None => quantity,
+    };
+    let converted = quantity.to(&options.to)?;
+    let number_format = options
+        .number_format
+        .with_locale(Locale::Posix)
+        .for_input(text.trim());
+    Ok(number_format.format(converted.value))
+}
+
+/// Converts the values at `path` in a JSON document read from `reader`,
+/// writing the document to `writer` with everything else, whitespace
+/// included, as it was.
+///
+/// The document is streamed, so only one scalar value is held at a time.
+/// Several documents in a row, as in JSON Lines, are each matched from
+/// `$`. Numbers are written back as numbers and numeric strings as
+/// strings; `null` and empty strings are left alone. Values that fail to
+/// convert are passed to `on_bad_value` with their location and left as
+/// they were, unless the options are strict, in which case the first one
+/// ends the conversion.
+///
+/// With [`FieldTarget::Rename`] the converted fields get the new name, and
+/// with [`FieldTarget::Add`] a field with the new name is added after each
+/// one, holding `null` if it couldn't be converted. Both need a path that
+/// ends in a field name.
+pub fn convert_json<R, W, F>(
+    reader: R,
+    writer: &mut W,
+    path: &JsonPath,
+    options: &DocumentOptions,
+    on_bad_value: F,
+) -> Result<BatchSummary, DocumentError>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(&str, &ConversionError),
+{
+    if options.target != FieldTarget::Replace && path.field_name().is_none() {
+        return Err(DocumentError::UnnamedField(path.to_string()));
+    }
+
+    let mut rewriter = JsonRewriter {
+        input: JsonInput {
+            reader,
+            line: 1,
+            column: 1,
+        },
+        writer,
+        pattern: path,
+        options,
+        on_bad_value,
+        path: Vec::new(),
+        summary: BatchSummary::default(),
+    };
+    loop {
+        rewriter.copy_whitespace()?;
+        if rewriter.input.peek()?.is_none() {
+            break;
+        }
+        rewriter.value()?;
+    }
+    Ok(rewriter.summary)
+}
+
+/// How deeply arrays and objects may nest. Each level is read recursively,
+/// so deeper documents are rejected as invalid rather than exhausting the
+/// stack.
+const MAX_JSON_DEPTH: usize = 256;
+
+/// A byte-at-a-time view of a JSON document that knows where it is, for
+/// error messages.
+struct JsonInput<R> {
+    reader: R,
+    line: usize,
+    column: usize,
+}
+
+impl<R: BufRead> JsonInput<R> {
+    fn peek(&mut self) -> io::Result<Option<u8>> {
+        Ok(self.reader.fill_buf()?.first().copied())
+    }
+
+    fn next(&mut self) -> io::Result<Option<u8>> {
+        let byte = self.peek()?;
+        if let Some(byte) = byte {
+            self.reader.consume(1);
+            if byte == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else if byte & 0xC0 != 0x80 {
+                self.column += 1;
+            }
+        }
+        Ok(byte)
+    }
+
+    fn error(&self) -> DocumentError {
+        DocumentError::Json {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    /// Consumes `expected`, or fails pointing at whatever is there instead.
+    fn expect(&mut self, expected: u8) -> Result<(), DocumentError> {
+        match self.peek()? {
+            Some(byte) if byte == expected => {
+                self.next()?;
+                Ok(())
+            }
+            _ => Err(self.error()),
+        }
+    }
+}
+
+/// A string, number or literal, as written and as read.
+struct Scalar {
+    raw: Vec<u8>,
+    kind: ScalarKind,
+}
+
+enum ScalarKind {
+    /// A string, unescaped.
+    String(String),
+    Number,
+    /// `true`, `false` or `null`.
+    Literal,
+}
+
+/// What became of a value at the path.
+enum Converted {
+    /// The value as it should be written.
+    Value(String),
+    /// The value is `null` or empty, so there's nothing to convert.
+    Missing,
+    /// The value could not be converted and has been reported.
+    Bad,
+}
+
+struct JsonRewriter<'a, R, W, F> {
+    input: JsonInput<R>,
+    writer: &'a mut W,
+    pattern: &'a JsonPath,
+    options: &'a DocumentOptions,
+    on_bad_value: F,
+    /// Where the value being read sits.
+    path: Vec<Segment>,
+    summary: BatchSummary,
+}
+
+impl<R, W, F> JsonRewriter<'_, R, W, F>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(&str, &ConversionError),
+{
+    fn copy_whitespace(&mut self) -> Result<(), DocumentError> {
+        let whitespace = self.read_whitespace()?;
+        self.writer.write_all(&whitespace)?;
+        Ok(())
+    }
+
+    fn read_whitespace(&mut self) -> Result<Vec<u8>, DocumentError> {
+        let mut whitespace = Vec::new();
+        while let Some(byte) = self.input.peek()? {
+            if !matches!(byte, b' ' | b'\t' | b'\n' | b'\r') {
+                break;
+            }
+            whitespace.push(byte);
+            self.input.next()?;
+        }
+        Ok(whitespace)
+    }
+
+    /// Copies the value at `self.path`, converting it if the path matches.
+    fn value(&mut self) -> Result<(), DocumentError> {
+        match self.input.peek()? {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            _ => {
+                let scalar = self.scalar()?;
+                match self.convert_if_matched(&scalar)? {
+                    Some(Converted::Value(text)) => self.writer.write_all(text.as_bytes())?,
+                    _ => self.writer.write_all(&scalar.raw)?,
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<(), DocumentError> {
+        self.check_depth()?;
+        self.input.expect(b'{')?;
+        self.writer.write_all(b"{")?;
+
+        let mut first = true;
+        loop {
+            // Kept back so an added field can be indented like the others.
+            let indent = self.read_whitespace()?;
+            if first && self.input.peek()? == Some(b'}') {
+                self.writer.write_all(&indent)?;
+                break;
+            }
+            if self.input.peek()? != Some(b'"') {
+                return Err(self.input.error());
+            }
+            let key = self.scalar()?;
+            let mut separator = self.read_whitespace()?;
+            self.input.expect(b':')?;
+            separator.push(b':');
+            separator.extend(self.read_whitespace()?);
+
+            let ScalarKind::String(name) = key.kind else {
+                unreachable!("object keys are strings");
+            };
+            self.path.push(Segment::Key(name));
+            self.member(&indent, &key.raw, &separator)?;
+            self.path.pop();
+
+            self.copy_whitespace()?;
+            match self.input.next()? {
+                Some(b',') => self.writer.write_all(b",")?,
+                Some(b'}') => break,
+                _ => return Err(self.input.error()),
+            }
+            first = false;
+        }
+
+        self.writer.write_all(b"}")?;
+        Ok(())
+    }
+
+    /// Copies one member of an object, whose key is already on the path,
+    /// renaming it or adding a field after it if its value is converted.
+    fn member(&mut self, indent: &[u8], key: &[u8], separator: &[u8]) -> Result<(), DocumentError> {
+        let is_scalar = !matches!(self.input.peek()?, Some(b'{' | b'['));
+        self.writer.write_all(indent)?;
+        if !is_scalar || !self.pattern.matches(&self.path) {
+            self.writer.write_all(key)?;
+            self.writer.write_all(separator)?;
+            return self.value();
+        }
+
+        let scalar = self.scalar()?;
+        let Some(converted) = self.convert_if_matched(&scalar)? else {
+            unreachable!("the path was just matched");
+        };
+        match (&self.options.target, converted) {
+            (FieldTarget::Replace, Converted::Value(text)) => {
+                self.writer.write_all(key)?;
+                self.writer.write_all(separator)?;
+                self.writer.write_all(text.as_bytes())?;
+            }
+            (FieldTarget::Replace, _) | (FieldTarget::Rename(_), Converted::Bad) => {
+                self.writer.write_all(key)?;
+                self.writer.write_all(separator)?;
+                self.writer.write_all(&scalar.raw)?;
+            }
+            (FieldTarget::Rename(name), converted) => {
+                self.writer.write_all(json_string(name).as_bytes())?;
+                self.writer.write_all(separator)?;
+                match converted {
+                    Converted::Value(text) => self.writer.write_all(text.as_bytes())?,
+                    _ => self.writer.write_all(&scalar.raw)?,
+                }
+            }
+            (FieldTarget::Add(name), converted) => {
+                self.writer.write_all(key)?;
+                self.writer.write_all(separator)?;
+                self.writer.write_all(&scalar.raw)?;
+                self.writer.write_all(b",")?;
+                self.writer.write_all(indent)?;
+                self.writer.write_all(json_string(name).as_bytes())?;
+                self.writer.write_all(separator)?;
+                match converted {
+                    Converted::Value(text) => self.writer.write_all(text.as_bytes())?,
+                    _ => self.writer.write_all(b"null")?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn array(&mut self) -> Result<(), DocumentError> {
+        self.check_depth()?;
+        self.input.expect(b'[')?;
+        self.writer.write_all(b"[")?;
+
+        let mut index = 0;
+        loop {
+            self.copy_whitespace()?;
+            if index == 0 && self.input.peek()? == Some(b']') {
+                break;
+            }
+            self.path.push(Segment::Index(index));
+            self.value()?;
+            self.path.pop();
+
+            self.copy_whitespace()?;
+            match self.input.next()? {
+                Some(b',') => self.writer.write_all(b",")?,
+                Some(b']') => {
+                    self.writer.write_all(b"]")?;
+                    return Ok(());
+                }
+                _ => return Err(self.input.error()),
+            }
+            index += 1;
+        }
+
+        self.input.expect(b']')?;
+        self.writer.write_all(b"]")?;
+        Ok(())
+    }
+
+    /// Fails at an array or object that would nest deeper than
+    /// [`MAX_JSON_DEPTH`].
+    fn check_depth(&self) -> Result<(), DocumentError> {
+        if self.path.len() >= MAX_JSON_DEPTH {
+            return Err(self.input.error());
+        }
+        Ok(())
+    }
+
+    fn scalar(&mut self) -> Result<Scalar, DocumentError> {
+        match self.input.peek()? {
+            Some(b'"') => self.string(),
+            Some(b'-' | b'0'..=b'9') => {
+                let mut raw = Vec::new();
+                while let Some(byte) = self.input.peek()? {
+                    if !matches!(byte, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') {
+                        break;
+                    }
+                    raw.push(byte);
+                    self.input.next()?;
+                }
+                if !is_json_number(&raw) {
+                    return Err(self.input.error());
+                }
+                Ok(Scalar {
+                    raw,
+                    kind: ScalarKind::Number,
+                })
+            }
+            Some(b'a'..=b'z') => {
+                let mut raw = Vec::new();
+                while let Some(byte @ b'a'..=b'z') = self.input.peek()? {
+                    raw.push(byte);
+                    self.input.next()?;
+                }
+                if !matches!(raw.as_slice(), b"true" | b"false" | b"null") {
+                    return Err(self.input.error());
+                }
+                Ok(Scalar {
+                    raw,
+                    kind: ScalarKind::Literal,
+                })
+            }
+            _ => Err(self.input.error()),
+        }
+    }
+
+    fn string(&mut self) -> Result<Scalar, DocumentError> {
+        self.input.expect(b'"')?;
+        let mut raw = vec![b'"'];
+        let mut bytes = Vec::new();
+        loop {
+            let byte = self.input.next()?.ok_or_else(|| self.input.error())?;
+            raw.push(byte);
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escape = self.input.next()?.ok_or_else(|| self.input.error())?;
+                    raw.push(escape);
+                    let unescaped = match escape {
+                        b'"' | b'\\' | b'/' => escape,
+                        b'b' => 0x08,
+                        b'f' => 0x0C,
+                        b'n' => b'\n',
+                        b'r' => b'\r',
+                        b't' => b'\t',
+                        b'u' => {
+                            let c = self.unicode_escape(&mut raw)?;
+                            bytes.extend(c.encode_utf8(&mut [0; 4]).as_bytes());
+                            continue;
+                        }
+                        _ => return Err(self.input.error()),
+                    };
+                    bytes.push(unescaped);
+                }
+                byte => bytes.push(byte),
+            }
+        }
+
+        let text = String::from_utf8(bytes).map_err(|_| self.input.error())?;
+        Ok(Scalar {
+            raw,
+            kind: ScalarKind::String(text),
+        })
+    }
+
+    /// Reads the digits of a `\u` escape, and of the low surrogate after it
+    /// if it is a high one.
+    fn unicode_escape(&mut self, raw: &mut Vec<u8>) -> Result<char, DocumentError> {
+        let high = self.hex4(raw)?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            for expected in [b'\\', b'u'] {
+                self.input.expect(expected)?;
+                raw.push(expected);
+            }
+            let low = self.hex4(raw)?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.input.error());
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.input.error())
+    }
+
+    fn hex4(&mut self, raw: &mut Vec<u8>) -> Result<u32, DocumentError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let byte = self.input.next()?.ok_or_else(|| self.input.error())?;
+            raw.push(byte);
+            let digit = (byte as char)
+                .to_digit(16)
+                .ok_or_else(|| self.input.error())?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    /// Converts a scalar if it sits at the path, or returns `None` if it
+    /// doesn't.
+    fn convert_if_matched(&mut self, scalar: &Scalar) -> Result<Option<Converted>, DocumentError> {
+        if !self.pattern.matches(&self.path) {
+            return Ok(None);
+        }
+
+        let (text, quoted) = match &scalar.kind {
+            ScalarKind::String(text) if text.trim().is_empty() => {
+                return Ok(Some(Converted::Missing));
+            }
+            ScalarKind::String(text) => (text.clone(), true),
+            ScalarKind::Literal if scalar.raw == b"null" => return Ok(Some(Converted::Missing)),
+            ScalarKind::Number | ScalarKind::Literal => {
+                (String::from_utf8_lossy(&scalar.raw).into_owned(), false)
+            }
+        };
+
+        match convert_value(&text, self.options) {
+            Ok(value) => {
+                self.summary.converted += 1;
+                let value = if quoted { json_string(&value) } else { value };
+                Ok(Some(Converted::Value(value)))
+            }
+            Err(error) if self.options.strict => Err(DocumentError::Field {
+                path: display_path(&self.path),
+                error,
+            }),
+            Err(error) => {
+                (self.on_bad_value)(&display_path(&self.path), &error);
+                self.summary.skipped += 1;
+                Ok(Some(Converted::Bad))
+            }
+        }
+    }
+}
+
+/// Whether `raw` follows JSON's number grammar: an optional minus, an
+/// integer part without leading zeros, then an optional fraction and
+/// exponent, each with at least one digit.
+fn is_json_number(raw: &[u8]) -> bool {
+    fn digits(raw: &[u8]) -> usize {
+        raw.iter().take_while(|byte| byte.is_ascii_digit()).count()
+    }
+
+    let mut rest = raw.strip_prefix(b"-").unwrap_or(raw);
+    match digits(rest) {
+        0 => return false,
+        n if n > 1 && rest[0] == b'0' => return false,
+        n => rest = &rest[n..],
+    }
+    if let Some(fraction) = rest.strip_prefix(b".") {
+        match digits(fraction) {
+            0 => return false,
+            n => rest = &fraction[n..],
+        }
+    }
+    if let Some(exponent) = rest.strip_prefix(b"e").or_else(|| rest.strip_prefix(b"E")) {
+        let exponent = exponent
+            .strip_prefix(b"+")
+            .or_else(|| exponent.strip_prefix(b"-"))
+            .unwrap_or(exponent);
+        match digits(exponent) {
+            0 => return false,
+            n => rest = &exponent[n..],
+        }
+    }
+    rest.is_empty()
+}
+
+/// Converts the values in the named column of a CSV document with a header
+/// row read from `reader`, writing the document to `writer` with every
+/// other field, quotes and line endings included, as it was.
+///
+/// Rows are streamed one at a time. Empty cells are left alone. Values that
+/// fail to convert are passed to `on_bad_line` with their 1-based line
+/// number and left as they were, unless the options are strict, in which
+/// case the first one ends the conversion.
+///
+/// With [`FieldTarget::Rename`] the column's header is renamed, and with
+/// [`FieldTarget::Add`] a column is added after it, empty where a value
+/// couldn't be converted.
+pub fn convert_csv<R, W, F>(
+    mut reader: R,
+    writer: &mut W,
+    column: &str,
+    options: &DocumentOptions,
+    mut on_bad_line: F,
+) -> Result<BatchSummary, DocumentError>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(usize, &ConversionError),
+{
+    let mut summary = BatchSummary::default();
+    let delimiter = options.delimiter;
+    let mut line_number = 1;
+
+    let Some((header, ending, lines)) = read_record(&mut reader)? else {
+        return Ok(summary);
+    };
+    line_number += lines;
+    let mut fields = split_raw_fields(&header, delimiter);
+    let index = fields
+        .iter()
+        .position(|field| unquote(field).trim() == column)
+        .ok_or_else(|| DocumentError::MissingColumn(column.to_string()))?;
+    let name = match &options.target {
+        FieldTarget::Replace => None,
+        FieldTarget::Rename(name) | FieldTarget::Add(name) => Some(csv_field(name)),
+    };
+    match (&options.target, &name) {
+        (FieldTarget::Rename(_), Some(name)) => fields[index] = name,
+        (FieldTarget::Add(_), Some(name)) => fields.insert(index + 1, name),
+        _ => {}
+    }
+    write_record(writer, &fields, delimiter, &ending)?;
+
+    while let Some((record, ending, lines)) = read_record(&mut reader)? {
+        let line = line_number;
+        line_number += lines;
+        if record.trim().is_empty() {
+            write!(writer, "{}{}", record, ending)?;
+            continue;
+        }
+
+        let mut fields = split_raw_fields(&record, delimiter);
+        let value = fields.get(index).map(|field| unquote(field));
+        let converted = match value {
+            Some(value) if !value.trim().is_empty() => match convert_value(&value, options) {
+                Ok(converted) => {
+                    summary.converted += 1;
+                    Some(csv_field(&converted))
+                }
+                Err(error) if options.strict => return Err(DocumentError::Line { line, error }),
+                Err(error) => {
+                    on_bad_line(line, &error);
+                    summary.skipped += 1;
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        match (&options.target, converted) {
+            (FieldTarget::Replace | FieldTarget::Rename(_), Some(converted)) => {
+                fields[index] = &converted;
+                write_record(writer, &fields, delimiter, &ending)?;
+            }
+            (FieldTarget::Add(_), converted) if index < fields.len() => {
+                let converted = converted.unwrap_or_default();
+                fields.insert(index + 1, &converted);
+                write_record(writer, &fields, delimiter, &ending)?;
+            }
+            _ => write!(writer, "{}{}", record, ending)?,
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Reads one CSV record, which runs over several lines if a quoted field
+/// holds a line break. Returns the record, its line ending and how many
+/// lines it took, or `None` at the end of the input.
+fn read_record<R: BufRead>(reader: &mut R) -> io::Result<Option<(String, String, usize)>> {
+    let mut record = String::new();
+    let mut lines = 0;
+    loop {
+        if reader.read_line(&mut record)? == 0 {
+            break;
+        }
+        lines += 1;
+        if record.matches('"').count().is_multiple_of(2) {
+            break;
+        }
+    }
+    if lines == 0 {
+        return Ok(None);
+    }
+
+    let content = record.trim_end_matches(['\r', '\n']).len();
+    let ending = record.split_off(content);
+    Ok(Some((record, ending, lines)))
+}
+
+fn write_record<W: Write>(
+    writer: &mut W,
+    fields: &[&str],
+    delimiter: char,
+    ending: &str,
+) -> io::Result<()> {
+    write!(
+        writer,
+        "{}{}",
+        fields.join(delimiter.encode_utf8(&mut [0; 4])),
+        ending
+    )
+}
+
+/// Splits a record into fields as they were written, quotes included.
+fn split_raw_fields(record: &str, delimiter: char) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    for (at, c) in record.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => {
+                fields.push(&record[start..at]);
+                start = at + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    fields.push(&record[start..]);
+    fields
+}
+
+/// The text of a field as written by [`split_raw_fields`], without its
+/// quotes.
+fn unquote(field: &str) -> String {
+    let trimmed = field.trim();
+    match trimmed
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+    {
+        Some(inner) => inner.replace("\"\"", "\""),
+        None => field.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format::Precision, units::UnitRegistry};
+
+    fn options(from: &str, to: &str) -> DocumentOptions {
+        let registry = UnitRegistry::builtin();
+        DocumentOptions::new(
+            registry.get(from).unwrap().clone(),
+            registry.get(to).unwrap().clone(),
+        )
+    }
+
+    fn json(
+        input: &str,
+        path: &str,
+        options: &DocumentOptions,
+    ) -> (Result<BatchSummary, DocumentError>, String, Vec<String>) {
+        let mut output = Vec::new();
+        let mut bad_values = Vec::new();
+        let result = convert_json(
+            input.as_bytes(),
+            &mut output,
+            &path.parse().unwrap(),
+            options,
+            |path, _| bad_values.push(path.to_string()),
+        );
+        (result, String::from_utf8(output).unwrap(), bad_values)
+    }
+
+    fn csv(
+        input: &str,
+        column: &str,
+        options: &DocumentOptions,
+    ) -> (Result<BatchSummary, DocumentError>, String, Vec<usize>) {
+        let mut output = Vec::new();
+        let mut bad_lines = Vec::new();
+        let result = convert_csv(input.as_bytes(), &mut output, column, options, |line, _| {
+            bad_lines.push(line)
+        });
+        (result, String::from_utf8(output).unwrap(), bad_lines)
+    }
+
+    #[test]
+    fn parses_json_paths() {
+        let path: JsonPath = "$.readings[*].temp_f".parse().unwrap();
+        assert_eq!(
+            path.steps,
+            vec![
+                Step::Field("readings".into()),
+                Step::Any,
+                Step::Field("temp_f".into())
+            ]
+        );
+        assert_eq!(path.field_name(), Some("temp_f"));
+
+        let path: JsonPath = "$..['temp f']".parse().unwrap();
+        assert_eq!(
+            path.steps,
+            vec![Step::Descendants, Step::Field("temp f".into())]
+        );
+        let path: JsonPath = "readings[2]".parse().unwrap();
+        assert_eq!(
+            path.steps,
+            vec![Step::Field("readings".into()), Step::Index(2)]
+        );
+        assert_eq!(path.field_name(), None);
+
+        for invalid in ["$.", "$[", "$[x]", "$['open]", "$..", "$readings"] {
+            assert!(
+                matches!(
+                    invalid.parse::<JsonPath>(),
+                    Err(DocumentError::InvalidPath(_))
+                ),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn matches_wildcards_and_any_depth() {
+        let path: JsonPath = "$..temp_f".parse().unwrap();
+        let key = |key: &str| Segment::Key(key.into());
+        assert!(path.matches(&[key("temp_f")]));
+        assert!(path.matches(&[key("a"), Segment::Index(3), key("temp_f")]));
+        assert!(!path.matches(&[key("temp_f"), key("x")]));
+
+        let path: JsonPath = "$.*[1]".parse().unwrap();
+        assert!(path.matches(&[key("a"), Segment::Index(1)]));
+        assert!(!path.matches(&[key("a"), Segment::Index(0)]));
+    }
+
+    #[test]
+    fn converts_matching_json_values_and_keeps_the_rest() {
+        let input = r#"{"site": "north", "readings": [
+  {"t": 1, "temp_f": 32, "note": "temp_f"},
+  {"t": 2, "temp_f": "212", "extra": {"temp_f": 50}}
+]}
+"#;
+        let (result, output, bad_values) = json(input, "$.readings[*].temp_f", &options("F", "C"));
+
+        assert_eq!(
+            result.unwrap(),
+            BatchSummary {
+                converted: 2,
+                skipped: 0
+            }
+        );
+        assert_eq!(
+            output,
+            r#"{"site": "north", "readings": [
+  {"t": 1, "temp_f": 0.00, "note": "temp_f"},
+  {"t": 2, "temp_f": "100.00", "extra": {"temp_f": 50}}
+]}
+"#
+        );
+        assert!(bad_values.is_empty());
+    }
+
+    #[test]
+    fn renames_or_adds_json_fields() {
+        let input = "{\n  \"temp_f\": 98.6,\n  \"id\": 7\n}";
+        let mut options = options("F", "C");
+        options.number_format.precision = Precision::MatchInput;
+
+        options.target = FieldTarget::Rename("temp_c".into());
+        let (_, output, _) = json(input, "$.temp_f", &options);
+        assert_eq!(output, "{\n  \"temp_c\": 37.0,\n  \"id\": 7\n}");
+
+        options.target = FieldTarget::Add("temp_c".into());
+        let (_, output, _) = json(input, "$.temp_f", &options);
+        assert_eq!(
+            output,
+            "{\n  \"temp_f\": 98.6,\n  \"temp_c\": 37.0,\n  \"id\": 7\n}"
+        );
+
+        let (result, _, _) = json(input, "$.list[*]", &options);
+        assert!(matches!(result, Err(DocumentError::UnnamedField(_))));
+    }
+
+    #[test]
+    fn reports_bad_json_values_by_path() {
+        let input = r#"[{"temp_f": "warm"}, {"temp_f": null}, {"temp_f": -500}]"#;
+        let mut options = options("F", "C");
+        options.target = FieldTarget::Add("temp_c".into());
+        let (result, output, bad_values) = json(input, "$[*].temp_f", &options);
+
+        assert_eq!(result.unwrap().skipped, 2);
+        assert_eq!(
+            output,
+            r#"[{"temp_f": "warm","temp_c": null}, {"temp_f": null,"temp_c": null}, {"temp_f": -500,"temp_c": null}]"#
+        );
+        assert_eq!(bad_values, vec!["$[0].temp_f", "$[2].temp_f"]);
+
+        options.strict = true;
+        let (result, _, _) = json(input, "$[*].temp_f", &options);
+        assert!(matches!(
+            result,
+            Err(DocumentError::Field { path, error: ConversionError::InvalidNumber(_) })
+                if path == "$[0].temp_f"
+        ));
+    }
+
+    #[test]
+    fn streams_json_lines_and_reports_syntax_errors() {
+        let input = "{\"temp\": 0}\n{\"temp\": 100}\n";
+        let (_, output, _) = json(input, "$.temp", &options("C", "K"));
+        assert_eq!(output, "{\"temp\": 273.15}\n{\"temp\": 373.15}\n");
+
+        let input = "{\"k\\u00e9y\": \"\\ud83c\\udf21\", \"temp\": 1}";
+        let (_, output, _) = json(input, "$.temp", &options("C", "K"));
+        assert_eq!(
+            output,
+            "{\"k\\u00e9y\": \"\\ud83c\\udf21\", \"temp\": 274.15}"
+        );
+
+        let (result, _, _) = json("{\"a\": 1,\n  \"b\" 2}", "$.a", &options("C", "K"));
+        assert!(matches!(
+            result,
+            Err(DocumentError::Json { line: 2, column: 7 })
+        ));
+    }
+
+    #[test]
+    fn reads_numbers_by_the_json_grammar() {
+        for number in ["0", "-0", "12", "-1.5", "0.25", "1e3", "2.5E-3", "6.02e+23"] {
+            let (result, output, _) = json(number, "$.a", &options("C", "K"));
+            assert!(result.is_ok(), "{}", number);
+            assert_eq!(output, number);
+        }
+        for number in ["01", "-", "-01", "1.", "1.e3", "1e", "1e+", "1-2", "0x1"] {
+            let (result, _, _) = json(number, "$.a", &options("C", "K"));
+            assert!(
+                matches!(result, Err(DocumentError::Json { .. })),
+                "{}",
+                number
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_json_nested_too_deeply() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let (result, output, _) = json(&nested(MAX_JSON_DEPTH), "$.a", &options("C", "K"));
+        assert!(result.is_ok());
+        assert_eq!(output, nested(MAX_JSON_DEPTH));
+
+        let (result, _, _) = json(&nested(200_000), "$.a", &options("C", "K"));
+        assert!(matches!(
+            result,
+            Err(DocumentError::Json { line: 1, column }) if column == MAX_JSON_DEPTH + 1
+        ));
+
+        let input = format!("{}{}", "{\"a\": ".repeat(300), "}".repeat(300));
+        let (result, _, _) = json(&input, "$.a", &options("C", "K"));
+        assert!(matches!(result, Err(DocumentError::Json { .. })));
+    }
+
+    #[test]
+    fn converts_a_csv_column_in_place() {
+        let input = "time,temp_f,note\r\n1,32,\"cold, dry\"\r\n2,,\"multi\nline\"\r\n3,oops,x\r\n4,212,boiling\r\n";
+        let (result, output, bad_lines) = csv(input, "temp_f", &options("F", "C"));
+
+        assert_eq!(
+            result.unwrap(),
+            BatchSummary {
+                converted: 2,
+                skipped: 1
+            }
+        );
+        assert_eq!(
+            output,
+            "time,temp_f,note\r\n1,0.00,\"cold, dry\"\r\n2,,\"multi\nline\"\r\n3,oops,x\r\n4,100.00,boiling\r\n"
+        );
+        assert_eq!(bad_lines, vec![5]);
+    }
+
+    #[test]
+    fn renames_or_adds_csv_columns() {
+        let input = "id;\"temp_f\"\n1;\"32\"\n2;x\n";
+        let mut options = options("F", "C");
+        options.delimiter = ';';
+
+        options.target = FieldTarget::Rename("temp_c".into());
+        let (_, output, _) = csv(input, "temp_f", &options);
+        assert_eq!(output, "id;temp_c\n1;0.00\n2;x\n");
+
+        options.target = FieldTarget::Add("temp_c".into());
+        let (_, output, _) = csv(input, "temp_f", &options);
+        assert_eq!(output, "id;\"temp_f\";temp_c\n1;\"32\";0.00\n2;x;\n");
+
+        let (result, _, _) = csv(input, "temp", &options);
+        assert!(matches!(result, Err(DocumentError::MissingColumn(name)) if name == "temp"));
+
+        options.strict = true;
+        let (result, _, _) = csv(input, "temp_f", &options);
+        assert!(matches!(result, Err(DocumentError::Line { line: 3, .. })));
+    }
+
+    #[test]
+    fn ignores_the_locale_in_documents() {
+        let mut options = options("C", "F");
+        options.number_format = options.number_format.with_locale(Locale::German);
+        let (_, output, _) = csv("temp\n1000\n", "temp", &options);
+
+        assert_eq!(output, "temp\n1832.00\n");
+    }
+}
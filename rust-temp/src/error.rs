@@ -0,0 +1,82 @@
+use std::fmt;
+
+use crate::{locale::Locale, temperature::Scale, units::Dimension};
+
+/// Reasons a temperature or other quantity could not be created or converted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConversionError {
+    /// The value is colder than absolute zero on its scale.
+    BelowAbsoluteZero { value: f64, scale: Scale },
+    /// The value is colder than absolute zero in a user-defined unit.
+    UnitBelowAbsoluteZero { value: f64, unit: String },
+    /// The value is NaN or infinite.
+    NotFinite,
+    /// The input could not be parsed as a number.
+    InvalidNumber(String),
+    /// The input does not name a supported scale.
+    UnknownScale(String),
+    /// The input does not name a known unit.
+    UnknownUnit(String),
+    /// A compound unit expression such as `kg*m/s^2` could not be parsed.
+    MalformedUnit { unit: String, reason: &'static str },
+    /// The units measure different things, e.g. length and mass.
+    IncompatibleUnits {
+        from: String,
+        from_dimension: Dimension,
+        to: String,
+        to_dimension: Dimension,
+    },
+    /// An exact conversion needed more digits than it can hold.
+    ExactOverflow,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::BelowAbsoluteZero { value, scale } => write!(
+                f,
+                "{} is below absolute zero ({}).",
+                Locale::Posix.temperature(&value.to_string(), *scale),
+                Locale::Posix.temperature(&scale.absolute_zero().to_string(), *scale)
+            ),
+            ConversionError::UnitBelowAbsoluteZero { value, unit } => {
+                write!(f, "{} {} is below absolute zero.", value, unit)
+            }
+            ConversionError::NotFinite => f.write_str("Temperature must be a finite number."),
+            ConversionError::InvalidNumber(input) => {
+                write!(f, "Problem parsing value: {}.", input)
+            }
+            ConversionError::UnknownScale(input) => write!(f, "Unknown scale: {}.", input),
+            ConversionError::UnknownUnit(input) => write!(f, "Unknown unit: {}.", input),
+            ConversionError::MalformedUnit { unit, reason } => {
+                write!(f, "Malformed unit {}: {}.", unit, reason)
+            }
+            ConversionError::IncompatibleUnits {
+                from,
+                from_dimension,
+                to,
+                to_dimension,
+            } => write!(
+                f,
+                "Cannot convert {} ({}) to {} ({}).",
+                from,
+                describe(from_dimension),
+                to,
+                describe(to_dimension)
+            ),
+            ConversionError::ExactOverflow => {
+                f.write_str("Value has too many digits to convert exactly.")
+            }
+        }
+    }
+}
+
+/// A dimension's name, if it has one, followed by its dimension vector.
+fn describe(dimension: &Dimension) -> String {
+    match dimension.name() {
+        Some(name) => format!("{}, {}", name, dimension.vector()),
+        None => dimension.vector().to_string(),
+    }
+}
+
+impl std::error::Error for ConversionError {}
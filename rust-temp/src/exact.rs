@@ -0,0 +1,690 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::{
+    error::ConversionError,
+    temperature::Scale,
+    units::{Conversion, Dimension, Unit, UnitRegistry},
+};
+
+/// Fractional digits written before a repeating expansion is cut short.
+const MAX_FRACTION_DIGITS: usize = 60;
+/// Fractional digits [`ExactNotation::Ellipsis`] writes before the "…".
+const ELLIPSIS_DIGITS: usize = 6;
+
+/// An exact fraction, kept in lowest terms with a positive denominator.
+///
+/// Arithmetic is checked: anything that would overflow the 128-bit parts
+/// returns [`ConversionError::ExactOverflow`] rather than losing precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational {
+        numerator: 0,
+        denominator: 1,
+    };
+
+    /// Creates `numerator / denominator` in lowest terms. Returns `None` for a
+    /// zero denominator, or when making the denominator positive overflows.
+    pub fn new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+
+        let divisor = gcd(numerator, denominator);
+        let sign = if denominator < 0 { -1 } else { 1 };
+        Some(Rational {
+            numerator: (numerator / divisor).checked_mul(sign)?,
+            denominator: (denominator / divisor).checked_mul(sign)?,
+        })
+    }
+
+    pub fn from_integer(value: i128) -> Self {
+        Rational {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    /// The shortest decimal that reads back as `value`, as a fraction. So
+    /// `0.1` becomes exactly 1/10 rather than the binary value nearest it.
+    pub fn from_f64(value: f64) -> Result<Self, ConversionError> {
+        if !value.is_finite() {
+            return Err(ConversionError::NotFinite);
+        }
+        // `{:e}` gives the shortest digits that read back as the same value.
+        format!("{:e}", value).parse()
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    /// The nearest `f64`. This is correctly rounded whenever both parts fit
+    /// in an `f64` mantissa, so decimals read with [`Rational::from_f64`] or
+    /// parsed from a literal come back bit for bit.
+    pub fn to_f64(&self) -> f64 {
+        const EXACT_LIMIT: i128 = 1 << f64::MANTISSA_DIGITS;
+        if self.numerator.abs() <= EXACT_LIMIT && self.denominator <= EXACT_LIMIT {
+            return self.numerator as f64 / self.denominator as f64;
+        }
+        // Fall back to a long decimal expansion and let the float parser
+        // round it.
+        self.to_string_with(ExactNotation::Truncated)
+            .parse()
+            .unwrap_or(self.numerator as f64 / self.denominator as f64)
+    }
+
+    pub fn checked_add(self, other: Rational) -> Option<Rational> {
+        let denominator = lcm(self.denominator, other.denominator)?;
+        let left = self.numerator.checked_mul(denominator / self.denominator)?;
+        let right = other
+            .numerator
+            .checked_mul(denominator / other.denominator)?;
+        Rational::new(left.checked_add(right)?, denominator)
+    }
+
+    pub fn checked_sub(self, other: Rational) -> Option<Rational> {
+        self.checked_add(other.checked_neg()?)
+    }
+
+    pub fn checked_mul(self, other: Rational) -> Option<Rational> {
+        // Cancel across before multiplying to keep the parts small.
+        let a = gcd(self.numerator, other.denominator);
+        let b = gcd(other.numerator, self.denominator);
+        let numerator = (self.numerator / a).checked_mul(other.numerator / b)?;
+        let denominator = (self.denominator / b).checked_mul(other.denominator / a)?;
+        Rational::new(numerator, denominator)
+    }
+
+    pub fn checked_div(self, other: Rational) -> Option<Rational> {
+        if other.numerator == 0 {
+            return None;
+        }
+        self.checked_mul(Rational::new(other.denominator, other.numerator)?)
+    }
+
+    pub fn checked_neg(self) -> Option<Rational> {
+        Some(Rational {
+            numerator: self.numerator.checked_neg()?,
+            denominator: self.denominator,
+        })
+    }
+
+    /// Writes the value in the given notation, e.g. 340/9 as "37.(7)",
+    /// "37.7̅", "37.777777…" or "340/9".
+    pub fn to_string_with(&self, notation: ExactNotation) -> String {
+        if notation == ExactNotation::Fraction {
+            return match self.denominator {
+                1 => self.numerator.to_string(),
+                _ => format!("{}/{}", self.numerator, self.denominator),
+            };
+        }
+
+        let sign = if self.numerator < 0 { "-" } else { "" };
+        let numerator = self.numerator.unsigned_abs();
+        let denominator = self.denominator.unsigned_abs();
+        let integer = numerator / denominator;
+        let expansion = Expansion::of(numerator % denominator, denominator);
+
+        let mut text = format!("{}{}", sign, integer);
+        if expansion.digits.is_empty() {
+            return text;
+        }
+        text.push('.');
+
+        let (fixed, repeating) = expansion.digits.split_at(expansion.repeat_start);
+        text.push_str(fixed);
+        match notation {
+            _ if repeating.is_empty() => {
+                if expansion.cut_short {
+                    text.push('…');
+                }
+            }
+            ExactNotation::Parentheses => {
+                text.push('(');
+                text.push_str(repeating);
+                text.push(')');
+            }
+            ExactNotation::Overline => {
+                for digit in repeating.chars() {
+                    text.push(digit);
+                    text.push('\u{0305}');
+                }
+            }
+            ExactNotation::Ellipsis | ExactNotation::Truncated => {
+                let mut written = fixed.len();
+                loop {
+                    text.push_str(repeating);
+                    written += repeating.len();
+                    if written >= ELLIPSIS_DIGITS.max(fixed.len() + 2 * repeating.len()) {
+                        break;
+                    }
+                }
+                if notation == ExactNotation::Ellipsis {
+                    text.push('…');
+                }
+            }
+            ExactNotation::Fraction => unreachable!("fractions return early"),
+        }
+        text
+    }
+}
+
+impl fmt::Display for Rational {
+    /// Writes the value as a decimal with any repeating digits in
+    /// parentheses, e.g. "37.(7)".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_with(ExactNotation::Parentheses))
+    }
+}
+
+impl FromStr for Rational {
+    type Err = ConversionError;
+
+    /// Parses a decimal literal such as "98.6", "-40" or "1.5e-3", or a
+    /// fraction such as "1/3", without going through `f64`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || ConversionError::InvalidNumber(input.to_string());
+        let text = input.trim();
+
+        if let Some((numerator, denominator)) = text.split_once('/') {
+            let numerator: i128 = numerator.trim().parse().map_err(|_| invalid())?;
+            let denominator: i128 = denominator.trim().parse().map_err(|_| invalid())?;
+            if denominator == 0 {
+                return Err(invalid());
+            }
+            return Rational::new(numerator, denominator).ok_or(ConversionError::ExactOverflow);
+        }
+
+        let lowered = text.to_lowercase();
+        if ["nan", "inf", "infinity"].contains(&lowered.trim_start_matches(['+', '-'])) {
+            return Err(ConversionError::NotFinite);
+        }
+
+        let (mantissa, exponent) = match text.find(['e', 'E']) {
+            Some(index) => (
+                &text[..index],
+                text[index + 1..].parse::<i32>().map_err(|_| invalid())?,
+            ),
+            None => (text, 0),
+        };
+        let (negative, mantissa) = match mantissa.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+        };
+        let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let overflow = || ConversionError::ExactOverflow;
+        let digits = format!("{}{}", whole, fraction);
+        let digits = digits.trim_start_matches('0');
+        let mut numerator: i128 = if digits.is_empty() {
+            0
+        } else {
+            digits.parse().map_err(|_| overflow())?
+        };
+        if negative {
+            numerator = -numerator;
+        }
+        if numerator == 0 {
+            return Ok(Rational::ZERO);
+        }
+
+        let scale = i32::try_from(fraction.len())
+            .ok()
+            .and_then(|digits| exponent.checked_sub(digits))
+            .ok_or_else(overflow)?;
+        let power = 10i128
+            .checked_pow(scale.unsigned_abs())
+            .ok_or_else(overflow)?;
+        if scale >= 0 {
+            let numerator = numerator.checked_mul(power).ok_or_else(overflow)?;
+            Ok(Rational::from_integer(numerator))
+        } else {
+            Rational::new(numerator, power).ok_or_else(overflow)
+        }
+    }
+}
+
+/// How [`Rational::to_string_with`] writes a value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExactNotation {
+    /// Repeating digits in parentheses: "37.(7)".
+    #[default]
+    Parentheses,
+    /// Repeating digits with a combining overline: "37.7̅".
+    Overline,
+    /// The repeating digits written out a few times, then "…": "37.777777…".
+    Ellipsis,
+    /// A fraction in lowest terms: "340/9".
+    Fraction,
+    /// Like `Ellipsis` without the "…", for reading back as a float.
+    #[doc(hidden)]
+    Truncated,
+}
+
+impl FromStr for ExactNotation {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "parentheses" | "parens" => Ok(ExactNotation::Parentheses),
+            "overline" | "vinculum" => Ok(ExactNotation::Overline),
+            "ellipsis" | "dots" => Ok(ExactNotation::Ellipsis),
+            "fraction" => Ok(ExactNotation::Fraction),
+            _ => Err(format!(
+                "Unknown notation: {}. Expected parentheses, overline, ellipsis or fraction.",
+                input
+            )),
+        }
+    }
+}
+
+/// The fractional digits of `remainder / denominator`, split into the part
+/// before the repeating cycle and the cycle itself.
+struct Expansion {
+    digits: String,
+    repeat_start: usize,
+    /// The digits stop at [`MAX_FRACTION_DIGITS`] without finding a cycle.
+    cut_short: bool,
+}
+
+impl Expansion {
+    fn of(mut remainder: u128, denominator: u128) -> Expansion {
+        let mut digits = String::new();
+        let mut seen = HashMap::new();
+
+        while remainder != 0 {
+            if let Some(&start) = seen.get(&remainder) {
+                return Expansion {
+                    digits,
+                    repeat_start: start,
+                    cut_short: false,
+                };
+            }
+            if digits.len() == MAX_FRACTION_DIGITS {
+                let repeat_start = digits.len();
+                return Expansion {
+                    digits,
+                    repeat_start,
+                    cut_short: true,
+                };
+            }
+            seen.insert(remainder, digits.len());
+
+            // remainder < denominator, so this only overflows for
+            // denominators near the top of the range; widen by halving.
+            let (digit, next) = match remainder.checked_mul(10) {
+                Some(scaled) => (scaled / denominator, scaled % denominator),
+                None => long_multiply_by_ten(remainder, denominator),
+            };
+            digits.push(char::from(b'0' + digit as u8));
+            remainder = next;
+        }
+
+        let repeat_start = digits.len();
+        Expansion {
+            digits,
+            repeat_start,
+            cut_short: false,
+        }
+    }
+}
+
+/// `(remainder * 10) / denominator` and its remainder, without overflowing.
+fn long_multiply_by_ten(remainder: u128, denominator: u128) -> (u128, u128) {
+    let mut digit = 0;
+    let mut next = 0u128;
+    for _ in 0..10 {
+        // next + remainder may exceed denominator at most once per step.
+        let gap = denominator - next;
+        if remainder >= gap {
+            next = remainder - gap;
+            digit += 1;
+        } else {
+            next += remainder;
+        }
+    }
+    (digit, next)
+}
+
+/// Converts an exact value between two units, rejecting temperatures below
+/// absolute zero. Built-in temperature scales convert exactly; other units
+/// use the simplest fraction their factors round from, so `ft` is exactly
+/// 0.3048 m and `km/h` exactly 5/18 m/s.
+pub fn convert_exact_between(
+    value: Rational,
+    from: &Unit,
+    to: &Unit,
+) -> Result<Rational, ConversionError> {
+    check_dimensions(from, to)?;
+
+    let (from_factor, from_offset) = exact_affine(from.conversion)?;
+    let base = value
+        .checked_add(from_offset)
+        .and_then(|shifted| shifted.checked_mul(from_factor))
+        .ok_or(ConversionError::ExactOverflow)?;
+
+    if from.dimension == Dimension::Temperature && base.numerator < 0 {
+        return Err(match from.scale() {
+            Some(scale) => ConversionError::BelowAbsoluteZero {
+                value: value.to_f64(),
+                scale,
+            },
+            None => ConversionError::UnitBelowAbsoluteZero {
+                value: value.to_f64(),
+                unit: from.symbol.clone(),
+            },
+        });
+    }
+
+    let (to_factor, to_offset) = exact_affine(to.conversion)?;
+    base.checked_div(to_factor)
+        .and_then(|scaled| scaled.checked_sub(to_offset))
+        .ok_or(ConversionError::ExactOverflow)
+}
+
+/// Like [`convert_exact_between`], but for a difference, so offsets are
+/// ignored and there is no lower limit.
+pub fn convert_exact_delta_between(
+    value: Rational,
+    from: &Unit,
+    to: &Unit,
+) -> Result<Rational, ConversionError> {
+    check_dimensions(from, to)?;
+
+    let (from_factor, _) = exact_affine(from.conversion)?;
+    let (to_factor, _) = exact_affine(to.conversion)?;
+    value
+        .checked_mul(from_factor)
+        .and_then(|base| base.checked_div(to_factor))
+        .ok_or(ConversionError::ExactOverflow)
+}
+
+/// Converts an exact value between two built-in units, e.g. 100 °F to
+/// exactly 340/9 °C.
+pub fn convert_exact(value: Rational, from: &str, to: &str) -> Result<Rational, ConversionError> {
+    let registry = UnitRegistry::builtin();
+    convert_exact_between(
+        value,
+        &registry.parse_unit(from)?,
+        &registry.parse_unit(to)?,
+    )
+}
+
+fn check_dimensions(from: &Unit, to: &Unit) -> Result<(), ConversionError> {
+    if from.dimension != to.dimension {
+        return Err(ConversionError::IncompatibleUnits {
+            from: from.symbol.clone(),
+            from_dimension: from.dimension,
+            to: to.symbol.clone(),
+            to_dimension: to.dimension,
+        });
+    }
+    Ok(())
+}
+
+/// The factor and offset taking a unit to its base unit as
+/// `(value + offset) * factor`, as exact fractions.
+fn exact_affine(conversion: Conversion) -> Result<(Rational, Rational), ConversionError> {
+    let exact = simplest_fraction;
+    match conversion {
+        Conversion::Linear(factor) => Ok((exact(factor)?, Rational::ZERO)),
+        Conversion::Affine { factor, offset } => Ok((exact(factor)?, exact(offset)?)),
+        Conversion::Temperature(scale) => {
+            let fraction = |numerator, denominator| {
+                Rational::new(numerator, denominator).expect("denominator is not zero")
+            };
+            Ok(match scale {
+                Scale::Celsius => (Rational::from_integer(1), fraction(27315, 100)),
+                Scale::Fahrenheit => (fraction(5, 9), fraction(45967, 100)),
+                Scale::Kelvin => (Rational::from_integer(1), Rational::ZERO),
+                Scale::Rankine => (fraction(5, 9), Rational::ZERO),
+                Scale::Reaumur => (fraction(5, 4), fraction(21852, 100)),
+                Scale::Delisle => (fraction(-2, 3), fraction(-559725, 1000)),
+                Scale::Newton => (fraction(100, 33), fraction(901395, 10000)),
+                Scale::Romer => (fraction(40, 21), fraction(13590375, 100000)),
+            })
+        }
+    }
+}
+
+/// The fraction with the smallest denominator that rounds to `value`, so a
+/// factor computed in floating point, such as `1000.0 / 3600.0`, comes back
+/// as the fraction it was meant to be.
+fn simplest_fraction(value: f64) -> Result<Rational, ConversionError> {
+    let decimal = Rational::from_f64(value)?;
+
+    // Walk the continued fraction convergents of the shortest decimal. They
+    // get closer each step and the last one is the decimal itself.
+    let (mut numerator, mut denominator) = (decimal.numerator, decimal.denominator);
+    let (mut previous, mut current) = ((0i128, 1i128), (1i128, 0i128));
+    while denominator != 0 {
+        let term = numerator.div_euclid(denominator);
+        (numerator, denominator) = (denominator, numerator.rem_euclid(denominator));
+
+        let next = term
+            .checked_mul(current.0)
+            .and_then(|p| p.checked_add(previous.0))
+            .zip(
+                term.checked_mul(current.1)
+                    .and_then(|q| q.checked_add(previous.1)),
+            );
+        let Some(next) = next else { break };
+        (previous, current) = (current, next);
+
+        if let Some(candidate) = Rational::new(current.0, current.1)
+            && candidate.to_f64() == value
+        {
+            return Ok(candidate);
+        }
+    }
+    Ok(decimal)
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    // Only 0/0 has a zero gcd, and callers never ask for it.
+    i128::try_from(a).unwrap_or(1).max(1)
+}
+
+fn lcm(a: i128, b: i128) -> Option<i128> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exact(input: &str) -> Rational {
+        input.parse().expect("literal should parse")
+    }
+
+    #[test]
+    fn parses_decimal_literals_exactly() {
+        assert_eq!(exact("98.6"), Rational::new(493, 5).unwrap());
+        assert_eq!(exact("-40"), Rational::from_integer(-40));
+        assert_eq!(exact("1.5e-3"), Rational::new(3, 2000).unwrap());
+        assert_eq!(exact("2E2"), Rational::from_integer(200));
+        assert_eq!(exact("1/3"), Rational::new(2, 6).unwrap());
+        assert_eq!(exact(".5"), Rational::new(1, 2).unwrap());
+        assert_eq!(
+            "warm".parse::<Rational>(),
+            Err(ConversionError::InvalidNumber("warm".into()))
+        );
+        assert_eq!("NaN".parse::<Rational>(), Err(ConversionError::NotFinite));
+    }
+
+    #[test]
+    fn reads_floats_as_their_shortest_decimal() {
+        assert_eq!(Rational::from_f64(0.1), Ok(Rational::new(1, 10).unwrap()));
+        assert_eq!(Rational::from_f64(-459.67).unwrap().to_f64(), -459.67);
+    }
+
+    #[test]
+    fn converts_temperatures_exactly() {
+        let celsius = convert_exact(exact("100"), "F", "C").unwrap();
+        assert_eq!(celsius, Rational::new(340, 9).unwrap());
+        assert_eq!(convert_exact(exact("0"), "C", "F"), Ok(exact("32")));
+        assert_eq!(convert_exact(exact("0"), "C", "K"), Ok(exact("273.15")));
+        assert_eq!(convert_exact(exact("0"), "K", "R"), Ok(Rational::ZERO));
+    }
+
+    #[test]
+    fn round_trips_are_bit_exact() {
+        for value in [98.6, 100.0, -40.0, 0.1, 451.0, 1e-7, 12345.678, -459.67] {
+            let fahrenheit = Rational::from_f64(value).unwrap();
+            let celsius = convert_exact(fahrenheit, "F", "C").unwrap();
+            let back = convert_exact(celsius, "C", "F").unwrap();
+
+            assert_eq!(back, fahrenheit);
+            assert_eq!(back.to_f64().to_bits(), value.to_bits(), "value: {}", value);
+        }
+    }
+
+    #[test]
+    fn converts_other_units_with_decimal_factors() {
+        assert_eq!(convert_exact(exact("1"), "ft", "in"), Ok(exact("12")));
+        assert_eq!(convert_exact(exact("1"), "mi", "ft"), Ok(exact("5280")));
+        assert!(matches!(
+            convert_exact(exact("1"), "ft", "kg"),
+            Err(ConversionError::IncompatibleUnits { .. })
+        ));
+    }
+
+    #[test]
+    fn recovers_fractions_behind_float_factors() {
+        assert_eq!(
+            simplest_fraction(1000.0 / 3600.0),
+            Ok(Rational::new(5, 18).unwrap())
+        );
+        assert_eq!(
+            simplest_fraction(1.0 / 3.0),
+            Ok(Rational::new(1, 3).unwrap())
+        );
+        assert_eq!(simplest_fraction(0.45359237), Ok(exact("0.45359237")));
+        assert_eq!(simplest_fraction(-1.8), Ok(exact("-1.8")));
+        assert_eq!(
+            convert_exact(exact("1"), "km/h", "m/s"),
+            Ok(Rational::new(5, 18).unwrap())
+        );
+    }
+
+    #[test]
+    fn converts_historical_scales_exactly() {
+        let cases = [
+            ("80", "°Ré", "°C", "100"),
+            ("37", "°C", "°De", "94.5"),
+            ("0", "K", "°De", "559.725"),
+            ("33", "°N", "°F", "212"),
+            ("37", "°C", "°Rø", "26.925"),
+            ("100", "°F", "°Ré", "30.(2)"),
+        ];
+
+        for (value, from, to, expected) in cases {
+            let converted = convert_exact(value.parse().unwrap(), from, to).unwrap();
+            assert_eq!(
+                converted.to_string(),
+                expected,
+                "{} {} to {}",
+                value,
+                from,
+                to
+            );
+        }
+        assert!(matches!(
+            convert_exact("560".parse().unwrap(), "°De", "°C"),
+            Err(ConversionError::BelowAbsoluteZero { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_temperatures_below_absolute_zero() {
+        assert!(matches!(
+            convert_exact(exact("-500"), "F", "C"),
+            Err(ConversionError::BelowAbsoluteZero { .. })
+        ));
+        let registry = UnitRegistry::builtin();
+        let (c, f) = (registry.get("C").unwrap(), registry.get("F").unwrap());
+        assert_eq!(
+            convert_exact_delta_between(exact("-500"), c, f),
+            Ok(exact("-900"))
+        );
+    }
+
+    #[test]
+    fn writes_repeating_decimals_in_each_notation() {
+        let value = Rational::new(340, 9).unwrap();
+        assert_eq!(value.to_string(), "37.(7)");
+        assert_eq!(
+            value.to_string_with(ExactNotation::Overline),
+            "37.7\u{0305}"
+        );
+        assert_eq!(value.to_string_with(ExactNotation::Ellipsis), "37.777777…");
+        assert_eq!(value.to_string_with(ExactNotation::Fraction), "340/9");
+
+        assert_eq!(Rational::new(1, 6).unwrap().to_string(), "0.1(6)");
+        assert_eq!(Rational::new(-1, 7).unwrap().to_string(), "-0.(142857)");
+        assert_eq!(exact("273.15").to_string(), "273.15");
+        assert_eq!(exact("212").to_string(), "212");
+        assert_eq!(
+            Rational::new(1, 7)
+                .unwrap()
+                .to_string_with(ExactNotation::Ellipsis),
+            "0.142857142857…"
+        );
+    }
+
+    #[test]
+    fn reports_overflow_instead_of_losing_precision() {
+        let huge = Rational::from_integer(i128::MAX);
+        assert_eq!(huge.checked_mul(Rational::from_integer(2)), None);
+        assert_eq!(
+            "1e60".parse::<Rational>(),
+            Err(ConversionError::ExactOverflow)
+        );
+        assert_eq!(
+            "1.5e-2147483648".parse::<Rational>(),
+            Err(ConversionError::ExactOverflow)
+        );
+        assert_eq!(exact("0.0e-2147483648"), Rational::ZERO);
+
+        let min = format!("{}/-1", i128::MIN);
+        assert_eq!(min.parse::<Rational>(), Err(ConversionError::ExactOverflow));
+        assert_eq!(Rational::new(i128::MIN, -1), None);
+        assert_eq!(
+            Rational::new(i128::MIN, 2),
+            Some(Rational::from_integer(i128::MIN / 2))
+        );
+    }
+
+    #[test]
+    fn long_division_handles_huge_denominators() {
+        let denominator = u128::MAX / 3;
+        let (digit, remainder) = long_multiply_by_ten(denominator - 1, denominator);
+        assert_eq!(digit, 9);
+        assert_eq!(remainder, denominator - 10);
+    }
+}
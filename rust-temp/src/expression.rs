@@ -0,0 +1,634 @@
+use std::fmt;
+
+use crate::{
+    error::ConversionError,
+    exact::{Rational, convert_exact_between, convert_exact_delta_between},
+    locale::Locale,
+    units::{Dimension, Quantity, Unit, UnitRegistry},
+};
+
+/// Words that may sit between a value and its unit, e.g. "72 degrees F".
+const DEGREE_WORDS: [&str; 3] = ["deg", "degree", "degrees"];
+/// Words that introduce the target unit, e.g. "72 F to C".
+const TARGET_WORDS: [&str; 3] = ["to", "in", "as"];
+/// Words that mark the value as a difference, e.g. "10 delta C to F".
+const DELTA_WORDS: [&str; 1] = ["delta"];
+
+/// A parsed conversion request such as "98.6 F in C" or "5 km to mi".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    pub quantity: Quantity,
+    /// The value as it was written, e.g. for counting significant figures.
+    pub literal: String,
+    /// The unit to convert to. For temperatures given without a target this
+    /// is the source scale's counterpart, e.g. fahrenheit for celsius.
+    pub target: Unit,
+    /// Whether the quantity is a difference, such as a temperature rise,
+    /// rather than an absolute value.
+    pub delta: bool,
+}
+
+impl Expression {
+    /// Converts the quantity to the target unit.
+    pub fn evaluate(&self) -> Result<Quantity, ConversionError> {
+        if self.delta {
+            self.quantity.delta_to(&self.target)
+        } else {
+            self.quantity.to(&self.target)
+        }
+    }
+
+    /// Converts the value as written to the target unit with exact
+    /// arithmetic, so "100 F to C" gives exactly 340/9.
+    pub fn evaluate_exact(&self) -> Result<Rational, ConversionError> {
+        let value: Rational = self.literal.parse()?;
+        if self.delta {
+            convert_exact_delta_between(value, &self.quantity.unit, &self.target)
+        } else {
+            convert_exact_between(value, &self.quantity.unit, &self.target)
+        }
+    }
+}
+
+/// What was wrong with an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionErrorKind {
+    MissingValue,
+    InvalidNumber,
+    MissingUnit,
+    UnknownUnit,
+    UnexpectedToken,
+    Conversion(ConversionError),
+}
+
+/// A problem with an expression, along with the byte range of the token
+/// that caused it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpressionError {
+    pub kind: ExpressionErrorKind,
+    pub input: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl ExpressionError {
+    fn new(kind: ExpressionErrorKind, input: &str, start: usize, end: usize) -> Self {
+        ExpressionError {
+            kind,
+            input: input.to_string(),
+            start,
+            end,
+        }
+    }
+
+    /// The offending token, or an empty string when the input ended early.
+    pub fn token(&self) -> &str {
+        &self.input[self.start..self.end]
+    }
+
+    /// The 1-based column, in characters, where the offending token starts.
+    pub fn column(&self) -> usize {
+        self.input[..self.start].chars().count() + 1
+    }
+
+    /// The input with a caret line underneath pointing at the offending
+    /// token, each line indented by two spaces.
+    pub fn pointer(&self) -> String {
+        let width = self.token().chars().count().max(1);
+        format!(
+            "  {}\n  {}{}",
+            self.input,
+            " ".repeat(self.column() - 1),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ExpressionErrorKind::MissingValue => f.write_str("Expected a number")?,
+            ExpressionErrorKind::InvalidNumber => write!(f, "Invalid number {:?}", self.token())?,
+            ExpressionErrorKind::MissingUnit => f.write_str("Expected a unit")?,
+            ExpressionErrorKind::UnknownUnit => write!(f, "Unknown unit {:?}", self.token())?,
+            ExpressionErrorKind::UnexpectedToken => write!(f, "Unexpected {:?}", self.token())?,
+            ExpressionErrorKind::Conversion(error) => {
+                write!(f, "{}", error.to_string().trim_end_matches('.'))?
+            }
+        }
+
+        write!(f, " at column {}:\n{}", self.column(), self.pointer())
+    }
+}
+
+impl std::error::Error for ExpressionError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Token<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+/// Parses a conversion expression such as "72°F to K", "98.6 F in C",
+/// "-40 degrees celsius" or "5 km to mi" using the built-in units.
+///
+/// The value comes first, followed by an optional degree sign or word, a
+/// unit symbol or name, and then "to", "in", "as" or "->" with a target
+/// unit. The target may be left out for temperatures.
+pub fn parse_expression(input: &str) -> Result<Expression, ExpressionError> {
+    parse_expression_with(input, UnitRegistry::builtin())
+}
+
+/// Like [`parse_expression`], but looks units up in `registry`.
+///
+/// A `Δ` or "delta" before either unit, as in "10 Δ°C to °F", marks the
+/// value as a difference.
+pub fn parse_expression_with(
+    input: &str,
+    registry: &UnitRegistry,
+) -> Result<Expression, ExpressionError> {
+    parse(input, registry, false, Locale::Posix)
+}
+
+/// Like [`parse_expression_with`], but always reads the value as a
+/// difference, so "10 C to F" converts a rise of 10 °C to 18 °F.
+pub fn parse_delta_expression_with(
+    input: &str,
+    registry: &UnitRegistry,
+) -> Result<Expression, ExpressionError> {
+    parse(input, registry, true, Locale::Posix)
+}
+
+/// Like [`parse_expression_with`], but reads the value with `locale`'s
+/// decimal and grouping separators, so "36,6 C to F" works in German.
+/// `delta` reads the value as a difference, as
+/// [`parse_delta_expression_with`] does.
+pub fn parse_localized_expression_with(
+    input: &str,
+    registry: &UnitRegistry,
+    locale: Locale,
+    delta: bool,
+) -> Result<Expression, ExpressionError> {
+    parse(input, registry, delta, locale)
+}
+
+fn parse(
+    input: &str,
+    registry: &UnitRegistry,
+    delta: bool,
+    locale: Locale,
+) -> Result<Expression, ExpressionError> {
+    let tokens = tokenize(input, locale);
+    let mut tokens = tokens.iter().peekable();
+    let error = |kind, start, end| ExpressionError::new(kind, input, start, end);
+
+    let number = tokens
+        .next()
+        .ok_or_else(|| error(ExpressionErrorKind::MissingValue, input.len(), input.len()))?;
+    let literal = locale.delocalize(number.text);
+    let value: f64 = literal
+        .parse()
+        .map_err(|_| error(ExpressionErrorKind::InvalidNumber, number.start, number.end))?;
+
+    let (unit, _, unit_delta) = parse_unit(input, registry, &mut tokens)?;
+    let mut delta = delta || unit_delta;
+    // Check the value up front so errors such as absolute zero point at it.
+    // Differences have no lower limit, only a need to be finite.
+    let checked = if delta && value.is_finite() {
+        Ok(value)
+    } else {
+        unit.to_base(value)
+    };
+    checked.map_err(|conversion| {
+        error(
+            ExpressionErrorKind::Conversion(conversion),
+            number.start,
+            number.end,
+        )
+    })?;
+    let quantity = Quantity::new(value, unit.clone());
+
+    let target = match tokens.next() {
+        Some(token)
+            if token.text == "->" || TARGET_WORDS.contains(&token.text.to_lowercase().as_str()) =>
+        {
+            let (target, token, target_delta) = parse_unit(input, registry, &mut tokens)?;
+            delta |= target_delta;
+            let converted = if delta {
+                quantity.delta_to(&target)
+            } else {
+                quantity.to(&target)
+            };
+            converted.map_err(|conversion| {
+                error(
+                    ExpressionErrorKind::Conversion(conversion),
+                    token.start,
+                    token.end,
+                )
+            })?;
+            target
+        }
+        Some(token) => {
+            return Err(error(
+                ExpressionErrorKind::UnexpectedToken,
+                token.start,
+                token.end,
+            ));
+        }
+        None => unit
+            .scale()
+            .and_then(|scale| registry.for_scale(scale.counterpart()))
+            .cloned()
+            .ok_or_else(|| error(ExpressionErrorKind::MissingUnit, input.len(), input.len()))?,
+    };
+
+    if let Some(token) = tokens.next() {
+        return Err(error(
+            ExpressionErrorKind::UnexpectedToken,
+            token.start,
+            token.end,
+        ));
+    }
+
+    Ok(Expression {
+        quantity,
+        literal,
+        target,
+        delta,
+    })
+}
+
+/// Reads an optional delta marker and degree sign or word followed by a
+/// unit or compound unit, returning the unit, the token it was read from and
+/// whether it was marked as a difference.
+fn parse_unit<'a, I>(
+    input: &str,
+    registry: &UnitRegistry,
+    tokens: &mut std::iter::Peekable<I>,
+) -> Result<(Unit, Token<'a>, bool), ExpressionError>
+where
+    I: Iterator<Item = &'a Token<'a>>,
+{
+    let is_delta = |token: &Token| {
+        token.text == "Δ" || DELTA_WORDS.contains(&token.text.to_lowercase().as_str())
+    };
+    let mut delta = tokens.next_if(|token| is_delta(token)).is_some();
+
+    let is_degree = |token: &Token| {
+        token.text == "°" || DEGREE_WORDS.contains(&token.text.to_lowercase().as_str())
+    };
+    let degree = tokens.next_if(|token| is_degree(token)).is_some();
+
+    let mut token = *tokens.next().ok_or_else(|| {
+        ExpressionError::new(
+            ExpressionErrorKind::MissingUnit,
+            input,
+            input.len(),
+            input.len(),
+        )
+    })?;
+    // A Δ stuck to the unit, as in "Δ°C", marks a difference too.
+    if let Some(rest) = token.text.strip_prefix('Δ')
+        && !rest.is_empty()
+    {
+        token = Token {
+            text: rest,
+            start: token.start + 'Δ'.len_utf8(),
+            end: token.end,
+        };
+        delta = true;
+    }
+
+    // After a degree sign or word, a temperature unit wins over a unit of
+    // the same name, as in "72 degrees newton" or "33 ° N".
+    if degree
+        && let Some(unit) = registry
+            .find_in(token.text, Dimension::Temperature)
+            .or_else(|| registry.find_in(&format!("°{}", token.text), Dimension::Temperature))
+    {
+        return Ok((unit.clone(), token, delta));
+    }
+
+    let unit = registry
+        .parse_unit(token.text)
+        .map_err(|conversion| match conversion {
+            // Point at the unknown part of a compound unit, not all of it.
+            ConversionError::UnknownUnit(name) => {
+                let start = token.start + token.text.find(name.as_str()).unwrap_or(0);
+                let end = if name.is_empty() {
+                    token.end
+                } else {
+                    start + name.len()
+                };
+                ExpressionError::new(ExpressionErrorKind::UnknownUnit, input, start, end)
+            }
+            conversion => ExpressionError::new(
+                ExpressionErrorKind::Conversion(conversion),
+                input,
+                token.start,
+                token.end,
+            ),
+        })?;
+    Ok((unit, token, delta))
+}
+
+/// Splits an expression into numbers, standalone degree signs and runs of
+/// other non-whitespace characters, remembering where each one came from.
+/// In French, a plain space between groups of three digits is part of the
+/// number, as in "1 000 m".
+fn tokenize(input: &str, locale: Locale) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let mut end = start + c.len_utf8();
+
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let starts_number = c.is_ascii_digit()
+            || ((c == '-' || c == '+' || c == '.')
+                && chars
+                    .peek()
+                    .is_some_and(|(_, next)| next.is_ascii_digit() || *next == '.'));
+
+        if starts_number {
+            while let Some(&(i, next)) = chars.peek() {
+                let exponent = (next == 'e' || next == 'E')
+                    && input[i + 1..]
+                        .trim_start_matches(['+', '-'])
+                        .starts_with(|d: char| d.is_ascii_digit());
+                let exponent_sign =
+                    (next == '+' || next == '-') && input[..i].ends_with(['e', 'E']);
+                // Commas and narrow spaces may separate decimals or groups
+                // of digits, depending on the locale.
+                let after = &input[i + next.len_utf8()..];
+                let separator = matches!(next, ',' | '\u{202F}' | '\u{00A0}')
+                    && input[..i].ends_with(|d: char| d.is_ascii_digit())
+                    && after.starts_with(|d: char| d.is_ascii_digit());
+                let space_group = next == ' '
+                    && locale == Locale::French
+                    && input[..i].ends_with(|d: char| d.is_ascii_digit())
+                    && starts_with_group(after);
+                if !(next.is_ascii_digit()
+                    || next == '.'
+                    || exponent
+                    || exponent_sign
+                    || separator
+                    || space_group)
+                {
+                    break;
+                }
+                end = i + next.len_utf8();
+                chars.next();
+            }
+        } else {
+            while let Some(&(i, next)) = chars.peek() {
+                if next.is_whitespace() {
+                    break;
+                }
+                end = i + next.len_utf8();
+                chars.next();
+            }
+        }
+
+        tokens.push(Token {
+            text: &input[start..end],
+            start,
+            end,
+        });
+    }
+
+    tokens
+}
+
+/// Whether `text` starts with exactly three digits, as a group of a grouped
+/// number does.
+fn starts_with_group(text: &str) -> bool {
+    let digits = text.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits == 3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temperature::Scale;
+
+    fn parse(input: &str) -> Expression {
+        parse_expression(input).expect("expression should parse")
+    }
+
+    fn parse_error(input: &str) -> ExpressionError {
+        parse_expression(input).expect_err("expression should not parse")
+    }
+
+    #[test]
+    fn parses_value_unit_and_target() {
+        let cases = [
+            ("98.6 F in C", 98.6, "°F", "°C"),
+            ("72°F to K", 72.0, "°F", "K"),
+            ("300K", 300.0, "K", "°C"),
+            ("-40 degrees celsius in fahrenheit", -40.0, "°C", "°F"),
+            ("1e2 c -> °R", 100.0, "°C", "°R"),
+            ("  0 ° c  as  kelvin ", 0.0, "°C", "K"),
+            ("5 km to mi", 5.0, "km", "mi"),
+            ("10 m/s in km/h", 10.0, "m/s", "km/h"),
+            ("5 kg*m/s^2 to N", 5.0, "kg*m/s^2", "N"),
+        ];
+
+        for (input, value, unit, target) in cases {
+            let expression = parse(input);
+            assert_eq!(expression.quantity.value, value, "input: {}", input);
+            assert_eq!(
+                expression.literal.parse::<f64>(),
+                Ok(value),
+                "input: {}",
+                input
+            );
+            assert_eq!(expression.quantity.unit.symbol, unit, "input: {}", input);
+            assert_eq!(expression.target.symbol, target, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn evaluates_to_the_target_unit() {
+        let result = parse("212 F to C").evaluate().unwrap();
+        assert_eq!(result.unit.scale(), Some(Scale::Celsius));
+        assert_eq!(result.value, 100.0);
+
+        let result = parse("1 ft in in").evaluate().unwrap();
+        assert_eq!(result.unit.symbol, "in");
+        assert!((result.value - 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn evaluates_temperatures_to_the_counterpart_without_a_target() {
+        let result = parse("0 C").evaluate().unwrap();
+        assert_eq!(result.unit.scale(), Some(Scale::Fahrenheit));
+        assert_eq!(result.value, 32.0);
+    }
+
+    #[test]
+    fn requires_a_target_for_other_units() {
+        let error = parse_error("5 km");
+        assert_eq!(error.kind, ExpressionErrorKind::MissingUnit);
+        assert_eq!(error.start, 4);
+    }
+
+    #[test]
+    fn reports_incompatible_targets_at_the_target() {
+        let error = parse_error("5 km to kg");
+        assert!(matches!(
+            error.kind,
+            ExpressionErrorKind::Conversion(ConversionError::IncompatibleUnits { .. })
+        ));
+        assert_eq!(error.token(), "kg");
+    }
+
+    #[test]
+    fn reports_unknown_units_with_their_position() {
+        let error = parse_error("98.6 Xyz in C");
+        assert_eq!(error.kind, ExpressionErrorKind::UnknownUnit);
+        assert_eq!((error.start, error.end), (5, 8));
+        assert_eq!(error.token(), "Xyz");
+    }
+
+    #[test]
+    fn reports_the_unknown_part_of_a_compound_unit() {
+        let error = parse_error("3 W/(m*Q) to W/(m*K)");
+        assert_eq!(error.kind, ExpressionErrorKind::UnknownUnit);
+        assert_eq!(error.token(), "Q");
+    }
+
+    #[test]
+    fn reports_unknown_target_units() {
+        let error = parse_error("72°F to Q");
+        assert_eq!(error.kind, ExpressionErrorKind::UnknownUnit);
+        assert_eq!(error.token(), "Q");
+    }
+
+    #[test]
+    fn reports_missing_pieces_at_the_end_of_input() {
+        assert_eq!(parse_error("").kind, ExpressionErrorKind::MissingValue);
+        assert_eq!(parse_error("72").kind, ExpressionErrorKind::MissingUnit);
+        assert_eq!(
+            parse_error("72 F to").kind,
+            ExpressionErrorKind::MissingUnit
+        );
+        assert_eq!(parse_error("72 F to").start, 7);
+    }
+
+    #[test]
+    fn reports_invalid_numbers_and_stray_tokens() {
+        assert_eq!(
+            parse_error("hot F").kind,
+            ExpressionErrorKind::InvalidNumber
+        );
+        assert_eq!(
+            parse_error("1.2.3 F").kind,
+            ExpressionErrorKind::InvalidNumber
+        );
+
+        let error = parse_error("72 F to C please");
+        assert_eq!(error.kind, ExpressionErrorKind::UnexpectedToken);
+        assert_eq!(error.token(), "please");
+    }
+
+    #[test]
+    fn reports_temperatures_below_absolute_zero() {
+        let error = parse_error("-500 C to F");
+        assert!(matches!(
+            error.kind,
+            ExpressionErrorKind::Conversion(ConversionError::BelowAbsoluteZero { .. })
+        ));
+        assert_eq!(error.token(), "-500");
+    }
+
+    #[test]
+    fn degree_markers_pick_temperature_units() {
+        let cases = [
+            ("72 degrees newton to C", Scale::Newton),
+            ("33 ° N to C", Scale::Newton),
+            ("80°Ré in F", Scale::Reaumur),
+            ("150 deg De to K", Scale::Delisle),
+        ];
+
+        for (input, scale) in cases {
+            assert_eq!(parse(input).quantity.unit.scale(), Some(scale), "{}", input);
+        }
+        assert_eq!(parse("33°N").evaluate().unwrap().value, 100.0);
+        assert_eq!(parse("10 N to lbf").quantity.unit.name, "newton");
+    }
+
+    #[test]
+    fn delta_markers_convert_differences() {
+        for input in [
+            "10 Δ°C to °F",
+            "10 Δ C to F",
+            "10 delta C to F",
+            "10 C to ΔF",
+            "10 ΔC",
+        ] {
+            let expression = parse(input);
+            assert!(expression.delta, "input: {}", input);
+            assert_eq!(
+                expression.evaluate().unwrap().value,
+                18.0,
+                "input: {}",
+                input
+            );
+        }
+        assert!(!parse("10 C to F").delta);
+    }
+
+    #[test]
+    fn deltas_may_go_below_absolute_zero() {
+        let registry = UnitRegistry::builtin();
+        let expression = parse_delta_expression_with("-300 C to F", registry).unwrap();
+        assert_eq!(expression.evaluate().unwrap().value, -540.0);
+    }
+
+    #[test]
+    fn reads_values_in_the_given_locale() {
+        let registry = UnitRegistry::builtin();
+        let expression =
+            parse_localized_expression_with("36,6 C to F", registry, Locale::German, false)
+                .unwrap();
+        assert_eq!(expression.quantity.value, 36.6);
+        assert_eq!(expression.literal, "36.6");
+
+        let expression =
+            parse_localized_expression_with("1,500 m to km", registry, Locale::English, false)
+                .unwrap();
+        assert_eq!(expression.quantity.value, 1500.0);
+
+        let expression =
+            parse_localized_expression_with("1 000 m to km", registry, Locale::French, false)
+                .unwrap();
+        assert_eq!(expression.quantity.value, 1000.0);
+        let expression =
+            parse_localized_expression_with("-12 345,5 m to km", registry, Locale::French, false)
+                .unwrap();
+        assert_eq!(expression.quantity.value, -12345.5);
+        // Only French groups digits with plain spaces.
+        assert!(
+            parse_localized_expression_with("1 000 m to km", registry, Locale::German, false)
+                .is_err()
+        );
+
+        let error = parse_error("36,6 C to F");
+        assert_eq!(error.kind, ExpressionErrorKind::InvalidNumber);
+        assert_eq!(error.token(), "36,6");
+    }
+
+    #[test]
+    fn display_points_at_the_bad_token() {
+        let error = parse_error("72°F to Kelvn");
+        assert_eq!(
+            error.to_string(),
+            "Unknown unit \"Kelvn\" at column 9:\n  72°F to Kelvn\n          ^^^^^"
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use std::{fmt, str::FromStr};
+
+use crate::locale::Locale;
+
+/// How a value is rounded to the digits being shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// Ties go to the even digit, so 0.125 becomes 0.12 and 0.135 becomes
+    /// 0.14.
+    #[default]
+    HalfEven,
+    /// Ties go away from zero, so 0.125 becomes 0.13.
+    HalfUp,
+    /// Extra digits are dropped, so 0.129 becomes 0.12.
+    Truncate,
+}
+
+impl Rounding {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rounding::HalfEven => "half-even",
+            Rounding::HalfUp => "half-up",
+            Rounding::Truncate => "truncate",
+        }
+    }
+}
+
+impl fmt::Display for Rounding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Rounding {
+    type Err = String;
+
+    /// Accepts a mode's name, with dashes, underscores or nothing between
+    /// the words.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().replace(['-', '_'], "").as_str() {
+            "halfeven" | "even" | "bankers" => Ok(Rounding::HalfEven),
+            "halfup" | "up" => Ok(Rounding::HalfUp),
+            "truncate" | "trunc" | "down" => Ok(Rounding::Truncate),
+            _ => Err(format!(
+                "Unknown rounding mode: {}. Expected half-even, half-up or truncate.",
+                input
+            )),
+        }
+    }
+}
+
+/// The most decimals or significant figures a format writes. More are
+/// treated as this many.
+pub const MAX_DIGITS: usize = 100;
+
+/// How many digits of a value are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    /// A fixed number of digits after the decimal point.
+    Decimals(usize),
+    /// A fixed number of significant figures.
+    SignificantFigures(usize),
+    /// As many significant figures as the input had. Use
+    /// [`NumberFormat::for_input`] to resolve it; unresolved, values are
+    /// written in full.
+    MatchInput,
+}
+
+/// Rules for writing a converted value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    pub precision: Precision,
+    pub rounding: Rounding,
+    /// The decimal and grouping separators to write.
+    pub locale: Locale,
+    /// Whether long integer parts are grouped as the locale does, e.g.
+    /// "12,345.00" rather than "12345.00".
+    pub grouping: bool,
+}
+
+impl NumberFormat {
+    /// A format in the [`Locale::Posix`] locale.
+    pub fn new(precision: Precision, rounding: Rounding) -> Self {
+        NumberFormat {
+            precision,
+            rounding,
+            locale: Locale::Posix,
+            grouping: true,
+        }
+    }
+
+    pub fn with_locale(self, locale: Locale) -> Self {
+        NumberFormat { locale, ..self }
+    }
+
+    /// The same format with the locale's decimal separator but no digit
+    /// grouping, for output that other programs read.
+    pub fn without_grouping(self) -> Self {
+        NumberFormat {
+            grouping: false,
+            ..self
+        }
+    }
+
+    /// Resolves [`Precision::MatchInput`] against the literal a value was
+    /// read from, e.g. "98.6" gives three significant figures. Other
+    /// precisions are returned unchanged.
+    pub fn for_input(&self, input: &str) -> NumberFormat {
+        match self.precision {
+            Precision::MatchInput => match significant_figures(input) {
+                Some(count) => NumberFormat {
+                    precision: Precision::SignificantFigures(count),
+                    ..*self
+                },
+                None => *self,
+            },
+            _ => *self,
+        }
+    }
+
+    /// Writes `value` with this format's precision, rounding and locale.
+    /// Values are never written in exponent notation.
+    pub fn format(&self, value: f64) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+
+        let decimal = Decimal::from_f64(value);
+        let text = match self.precision {
+            Precision::Decimals(decimals) => {
+                let decimals = decimals.min(MAX_DIGITS);
+                let keep = decimal.exponent + 1 + decimals as i32;
+                decimal.round(keep, self.rounding).render(decimals)
+            }
+            Precision::SignificantFigures(figures) => {
+                let figures = figures.clamp(1, MAX_DIGITS);
+                let rounded = decimal.round(figures as i32, self.rounding);
+                let decimals = (figures as i32 - 1 - rounded.exponent).max(0);
+                rounded.render(decimals as usize)
+            }
+            Precision::MatchInput => value.to_string(),
+        };
+        if self.grouping {
+            self.locale.localize(&text)
+        } else {
+            self.locale.localize_ungrouped(&text)
+        }
+    }
+}
+
+impl Default for NumberFormat {
+    /// Two decimal places, rounding half to even.
+    fn default() -> Self {
+        NumberFormat::new(Precision::Decimals(2), Rounding::HalfEven)
+    }
+}
+
+/// Counts the significant figures in a numeric literal, e.g. 3 for "98.6",
+/// "0.0250" or "1.20e3", and 1 for "100". Returns `None` if the input is
+/// not a plain decimal number.
+pub fn significant_figures(input: &str) -> Option<usize> {
+    let input = input.trim().trim_start_matches(['+', '-']);
+    let mantissa = match input.find(['e', 'E']) {
+        Some(index) => {
+            input[index + 1..].parse::<i32>().ok()?;
+            &input[..index]
+        }
+        None => input,
+    };
+
+    let has_point = mantissa.contains('.');
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    if digits.is_empty()
+        || !digits.chars().all(|c| c.is_ascii_digit())
+        || mantissa.matches('.').count() > 1
+    {
+        return None;
+    }
+
+    let significant = digits.trim_start_matches('0');
+    if significant.is_empty() {
+        // A zero is as precise as the decimals written after it.
+        let decimals = mantissa
+            .split_once('.')
+            .map_or(0, |(_, fraction)| fraction.len());
+        return Some(decimals.max(1));
+    }
+
+    // Trailing zeros only count when a decimal point shows they were measured.
+    let count = if has_point {
+        significant.len()
+    } else {
+        significant.trim_end_matches('0').len()
+    };
+    Some(count)
+}
+
+/// A finite value as decimal digits, so rounding works on the digits people
+/// see rather than on the nearest binary fraction. The value is
+/// `0.d₀d₁d₂… × 10^(exponent + 1)`, i.e. `digits[0]` sits at `10^exponent`.
+#[derive(Debug, Clone, PartialEq)]
+struct Decimal {
+    negative: bool,
+    digits: Vec<u8>,
+    exponent: i32,
+}
+
+impl Decimal {
+    fn from_f64(value: f64) -> Decimal {
+        // `{:e}` gives the shortest digits that read back as the same value.
+        let scientific = format!("{:e}", value.abs());
+        let (mantissa, exponent) = scientific
+            .split_once('e')
+            .expect("exponent notation always has an exponent");
+        let digits: Vec<u8> = mantissa
+            .bytes()
+            .filter(u8::is_ascii_digit)
+            .map(|digit| digit - b'0')
+            .collect();
+
+        Decimal {
+            negative: value.is_sign_negative(),
+            exponent: exponent.parse().expect("exponent is an integer"),
+            digits: if digits == [0] { Vec::new() } else { digits },
+        }
+    }
+
+    /// Keeps the first `keep` digits, rounding the rest away.
+    fn round(mut self, keep: i32, rounding: Rounding) -> Decimal {
+        if keep >= self.digits.len() as i32 {
+            return self;
+        }
+        if keep < 0 {
+            self.digits.clear();
+            return self;
+        }
+
+        let keep = keep as usize;
+        let first_dropped = self.digits[keep];
+        let rest_is_zero = self.digits[keep + 1..].iter().all(|digit| *digit == 0);
+        let last_kept_is_odd = keep > 0 && self.digits[keep - 1] % 2 == 1;
+        let round_up = match rounding {
+            Rounding::Truncate => false,
+            Rounding::HalfUp => first_dropped >= 5,
+            Rounding::HalfEven => {
+                first_dropped > 5 || (first_dropped == 5 && (!rest_is_zero || last_kept_is_odd))
+            }
+        };
+
+        self.digits.truncate(keep);
+        if round_up {
+            let mut index = keep;
+            loop {
+                if index == 0 {
+                    self.digits.insert(0, 1);
+                    self.exponent += 1;
+                    break;
+                }
+                index -= 1;
+                if self.digits[index] == 9 {
+                    self.digits[index] = 0;
+                } else {
+                    self.digits[index] += 1;
+                    break;
+                }
+            }
+        }
+        self
+    }
+
+    /// Writes the value with exactly `decimals` digits after the point.
+    fn render(&self, decimals: usize) -> String {
+        let digit_at = |place: i32| -> char {
+            let index = self.exponent - place;
+            if index >= 0 && (index as usize) < self.digits.len() {
+                char::from(b'0' + self.digits[index as usize])
+            } else {
+                '0'
+            }
+        };
+
+        let mut text = String::new();
+        for place in (0..=self.exponent.max(0)).rev() {
+            text.push(digit_at(place));
+        }
+        if decimals > 0 {
+            text.push('.');
+            for place in 1..=decimals as i32 {
+                text.push(digit_at(-place));
+            }
+        }
+
+        // Leave the sign off values that round to zero.
+        if self.negative && text.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            text.insert(0, '-');
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimals(places: usize, rounding: Rounding) -> NumberFormat {
+        NumberFormat::new(Precision::Decimals(places), rounding)
+    }
+
+    fn figures(count: usize, rounding: Rounding) -> NumberFormat {
+        NumberFormat::new(Precision::SignificantFigures(count), rounding)
+    }
+
+    #[test]
+    fn rounds_to_decimal_places() {
+        let format = NumberFormat::default();
+        assert_eq!(format.format(37.77777), "37.78");
+        assert_eq!(format.format(32.0), "32.00");
+        assert_eq!(format.format(-40.0), "-40.00");
+        assert_eq!(format.format(0.004), "0.00");
+        assert_eq!(format.format(-0.004), "0.00");
+        assert_eq!(format.format(99.999), "100.00");
+        assert_eq!(decimals(0, Rounding::HalfUp).format(2.5), "3");
+        assert_eq!(decimals(4, Rounding::HalfEven).format(1.0 / 3.0), "0.3333");
+    }
+
+    #[test]
+    fn rounding_modes_break_ties_differently() {
+        let cases = [
+            (0.125, "0.12", "0.13", "0.12"),
+            (0.135, "0.14", "0.14", "0.13"),
+            (-2.675, "-2.68", "-2.68", "-2.67"),
+            (0.1251, "0.13", "0.13", "0.12"),
+        ];
+
+        for (value, half_even, half_up, truncate) in cases {
+            assert_eq!(decimals(2, Rounding::HalfEven).format(value), half_even);
+            assert_eq!(decimals(2, Rounding::HalfUp).format(value), half_up);
+            assert_eq!(decimals(2, Rounding::Truncate).format(value), truncate);
+        }
+    }
+
+    #[test]
+    fn rounds_to_significant_figures() {
+        let format = figures(3, Rounding::HalfEven);
+        assert_eq!(format.format(37.77777), "37.8");
+        assert_eq!(format.format(310.15), "310");
+        assert_eq!(format.format(0.000123456), "0.000123");
+        assert_eq!(format.format(9.996), "10.0");
+        assert_eq!(format.format(123456.0), "123000");
+        assert_eq!(format.format(0.0), "0.00");
+        assert_eq!(figures(1, Rounding::Truncate).format(-459.67), "-400");
+    }
+
+    #[test]
+    fn counts_significant_figures_in_literals() {
+        let cases = [
+            ("98.6", Some(3)),
+            ("-40", Some(1)),
+            ("100", Some(1)),
+            ("100.", Some(3)),
+            ("0.0250", Some(3)),
+            ("1.20e3", Some(3)),
+            ("+7", Some(1)),
+            ("0", Some(1)),
+            ("0.00", Some(2)),
+            ("12.5.1", None),
+            ("warm", None),
+            ("1e", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(significant_figures(input), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn match_input_resolves_against_the_literal() {
+        let format = NumberFormat::new(Precision::MatchInput, Rounding::HalfEven);
+        assert_eq!(format.for_input("98.6").format(37.0), "37.0");
+        assert_eq!(format.for_input("212.0").format(100.0), "100.0");
+        assert_eq!(format.format(0.5), "0.5");
+    }
+
+    #[test]
+    fn writes_the_locale_separators() {
+        let format = NumberFormat::default().with_locale(Locale::German);
+        assert_eq!(format.format(12345.678), "12.345,68");
+        assert_eq!(format.for_input("36,6").locale, Locale::German);
+    }
+
+    #[test]
+    fn parses_rounding_modes() {
+        assert_eq!("half-even".parse(), Ok(Rounding::HalfEven));
+        assert_eq!("HALF_UP".parse(), Ok(Rounding::HalfUp));
+        assert_eq!("truncate".parse(), Ok(Rounding::Truncate));
+        assert!("sideways".parse::<Rounding>().is_err());
+    }
+}
@@ -1,11 +1,80 @@
-/// Converts a temperature in celsius to fahrenheit.
-pub fn c_to_f(celsius: f64) -> f64 {
-    celsius * (9.0 / 5.0) + 32.0
+mod annotate;
+mod batch;
+mod boiling;
+mod calibration;
+mod color;
+mod compound;
+mod definitions;
+mod dimension;
+mod document;
+mod error;
+mod exact;
+mod expression;
+mod format;
+mod locale;
+mod metar;
+mod rtd;
+mod sysfs;
+mod table;
+mod temperature;
+mod thermocouple;
+mod threshold;
+mod units;
+mod weather;
+
+pub use annotate::{AnnotateOptions, Annotation, Template, TemplateError, annotate, find_mentions};
+pub use batch::{BatchError, BatchOptions, BatchSummary, convert_batch};
+pub use boiling::{
+    boiling_point, boiling_point_at_altitude, boiling_pressure, pressure_at_altitude,
+};
+pub use calibration::{
+    Calibration, CalibrationError, CalibrationPoint, Fit, Residual, load_calibration_file,
+    parse_calibration,
+};
+pub use color::{BlackbodyColor, Chromaticity, ColorError, Rgb, blackbody_color, write_colors};
+pub use definitions::{DefinitionError, default_units_path, load_units, load_units_file};
+pub use dimension::{BaseDimension, DimensionVector};
+pub use document::{
+    DocumentError, DocumentOptions, FieldTarget, JsonPath, convert_csv, convert_json,
+};
+pub use error::ConversionError;
+pub use exact::{
+    ExactNotation, Rational, convert_exact, convert_exact_between, convert_exact_delta_between,
+};
+pub use expression::{
+    Expression, ExpressionError, ExpressionErrorKind, parse_delta_expression_with,
+    parse_expression, parse_expression_with, parse_localized_expression_with,
+};
+pub use format::{MAX_DIGITS, NumberFormat, Precision, Rounding, significant_figures};
+pub use locale::Locale;
+pub use metar::{
+    MetarError, Observation, parse_metar, parse_report, parse_synop, write_observations,
+};
+pub use rtd::{Rtd, RtdError};
+pub use sysfs::{
+    DEFAULT_SYSFS_ROOT, SensorError, SensorReading, read_sensor, read_sensors, write_readings,
+};
+pub use table::{ConversionTable, MAX_ROWS, TableError, TableFormat, json_string};
+pub use temperature::{Scale, Temperature, TemperatureDelta};
+pub use thermocouple::{ThermocoupleError, ThermocoupleType};
+pub use threshold::{Alert, AlertState, Side, Threshold, ThresholdMonitor};
+pub use units::{
+    Conversion, Dimension, Quantity, Unit, UnitRegistry, convert, convert_between, convert_delta,
+    convert_delta_between,
+};
+pub use weather::{
+    Estimate, RangeWarning, WeatherError, dew_point, heat_index, humidex, wind_chill,
+};
+
+/// Converts a temperature in celsius to fahrenheit. For a difference
+/// between temperatures, use [`convert_delta`] or [`TemperatureDelta`].
+pub fn c_to_f(celsius: f64) -> Result<f64, ConversionError> {
+    convert(celsius, "celsius", "fahrenheit")
 }
 
 /// Converts a temperature in fahrenheit to celsius.
-pub fn f_to_c(fahrenheit: f64) -> f64 {
-    (fahrenheit - 32.0) * (5.0 / 9.0)
+pub fn f_to_c(fahrenheit: f64) -> Result<f64, ConversionError> {
+    convert(fahrenheit, "fahrenheit", "celsius")
 }
 
 #[cfg(test)]
@@ -19,7 +88,7 @@ mod test {
 
         for (input, output) in q_and_a {
             let result = c_to_f(input);
-            assert_eq!(result, output);
+            assert_eq!(result, Ok(output));
         }
     }
 
@@ -29,7 +98,12 @@ mod test {
 
         for (input, output) in q_and_a {
             let result = f_to_c(input);
-            assert_eq!(result, output);
+            assert_eq!(result, Ok(output));
         }
     }
+
+    #[test]
+    fn rejects_celsius_below_absolute_zero() {
+        assert!(c_to_f(-500.0).is_err());
+    }
 }
@@ -0,0 +1,314 @@
+use std::{env, fmt, str::FromStr};
+
+use crate::temperature::Scale;
+
+/// Integer parts with at least this many digits have their digits grouped
+/// when written, e.g. "12,345" but "1234".
+const GROUPING_THRESHOLD: usize = 5;
+
+/// Conventions for reading and writing numbers and unit words.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// The C locale: a decimal point, no digit grouping and English words.
+    #[default]
+    Posix,
+    /// A decimal point and commas between groups of digits: "12,345.6".
+    English,
+    /// A decimal comma and points between groups of digits: "12.345,6".
+    German,
+    /// A decimal comma and narrow spaces between groups of digits:
+    /// "12 345,6". No-break and plain spaces are read as group separators
+    /// too.
+    French,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 4] = [
+        Locale::Posix,
+        Locale::English,
+        Locale::German,
+        Locale::French,
+    ];
+
+    /// The locale's short name, as accepted by `from_str`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::Posix => "C",
+            Locale::English => "en",
+            Locale::German => "de",
+            Locale::French => "fr",
+        }
+    }
+
+    /// The locale named by `LC_ALL`, `LC_NUMERIC` or `LANG`, whichever is
+    /// set first. Unset or unsupported locales give [`Locale::Posix`].
+    pub fn from_env() -> Locale {
+        ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+
+    pub fn decimal_separator(&self) -> char {
+        match self {
+            Locale::Posix | Locale::English => '.',
+            Locale::German | Locale::French => ',',
+        }
+    }
+
+    /// The character written between groups of three digits, if any.
+    pub fn grouping_separator(&self) -> Option<char> {
+        match self {
+            Locale::Posix => None,
+            Locale::English => Some(','),
+            Locale::German => Some('.'),
+            Locale::French => Some('\u{202F}'),
+        }
+    }
+
+    /// The word written between a temperature and its scale, as in
+    /// "37 degrees celsius". Kelvin takes none; see [`Locale::temperature`].
+    pub fn degrees(&self) -> &'static str {
+        match self {
+            Locale::Posix | Locale::English => "degrees",
+            Locale::German => "Grad",
+            Locale::French => "degrés",
+        }
+    }
+
+    /// A temperature in words, e.g. "37 degrees celsius", or "300 kelvin"
+    /// since kelvin takes no degree word.
+    pub fn temperature(&self, value: &str, scale: Scale) -> String {
+        match scale {
+            Scale::Kelvin => format!("{} {}", value, self.scale_name(scale)),
+            _ => format!("{} {} {}", value, self.degrees(), self.scale_name(scale)),
+        }
+    }
+
+    /// The name of a scale as written after [`Locale::degrees`].
+    pub fn scale_name(&self, scale: Scale) -> &'static str {
+        match (self, scale) {
+            (Locale::Posix | Locale::English, scale) => scale.name(),
+            (_, Scale::Celsius) => "Celsius",
+            (_, Scale::Fahrenheit) => "Fahrenheit",
+            (_, Scale::Kelvin) => "Kelvin",
+            (_, Scale::Rankine) => "Rankine",
+            (_, Scale::Reaumur) => "Réaumur",
+            (_, Scale::Delisle) => "Delisle",
+            (_, Scale::Newton) => "Newton",
+            (_, Scale::Romer) => "Rømer",
+        }
+    }
+
+    /// Rewrites a number written in this locale with a decimal point and no
+    /// grouping, ready for `str::parse`, e.g. "1.234,5" in German becomes
+    /// "1234.5". A point is still read as a decimal point where it can't be
+    /// a group separator, so "36.6" works everywhere. Input that doesn't
+    /// follow the locale's rules is returned trimmed but otherwise as it
+    /// was.
+    pub fn delocalize(&self, input: &str) -> String {
+        let text = input.trim();
+        let Some(group) = self.grouping_separator() else {
+            return text.to_string();
+        };
+        let is_group =
+            |c: char| c == group || (group == '\u{202F}' && matches!(c, '\u{00A0}' | ' '));
+
+        let (mantissa, exponent) = match text.find(['e', 'E']) {
+            Some(index) => text.split_at(index),
+            None => (text, ""),
+        };
+        let (sign, digits) = match mantissa.strip_prefix(['-', '+']) {
+            Some(rest) => mantissa.split_at(mantissa.len() - rest.len()),
+            None => ("", mantissa),
+        };
+
+        let decimal = self.decimal_separator();
+        let (whole, fraction) = match digits.split_once(decimal) {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            // A lone point that isn't grouping digits is a decimal point.
+            None if decimal != '.' && !is_grouped(digits, is_group) => {
+                match digits.split_once('.') {
+                    Some((whole, fraction)) => (whole, Some(fraction)),
+                    None => (digits, None),
+                }
+            }
+            None => (digits, None),
+        };
+
+        let whole = if whole.contains(is_group) {
+            if !is_grouped(whole, is_group) {
+                return text.to_string();
+            }
+            whole.replace(is_group, "")
+        } else {
+            whole.to_string()
+        };
+
+        match fraction {
+            Some(fraction) => format!("{}{}.{}{}", sign, whole, fraction, exponent),
+            None => format!("{}{}{}", sign, whole, exponent),
+        }
+    }
+
+    /// Like [`Locale::localize`], but with the locale's decimal separator
+    /// only and no digit grouping: "12345.6" becomes "12345,6" in German.
+    pub fn localize_ungrouped(&self, number: &str) -> String {
+        number.replacen('.', &self.decimal_separator().to_string(), 1)
+    }
+
+    /// Rewrites a number written with a decimal point, such as the output
+    /// of [`NumberFormat::format`], in this locale: "12345.6" becomes
+    /// "12.345,6" in German.
+    ///
+    /// [`NumberFormat::format`]: crate::NumberFormat::format
+    pub fn localize(&self, number: &str) -> String {
+        if *self == Locale::Posix {
+            return number.to_string();
+        }
+
+        let (sign, unsigned) = match number.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", number),
+        };
+        let (whole, fraction) = match unsigned.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (unsigned, None),
+        };
+
+        let mut text = sign.to_string();
+        match self.grouping_separator() {
+            Some(group)
+                if whole.len() >= GROUPING_THRESHOLD
+                    && whole.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                for (i, digit) in whole.chars().enumerate() {
+                    if i > 0 && (whole.len() - i) % 3 == 0 {
+                        text.push(group);
+                    }
+                    text.push(digit);
+                }
+            }
+            _ => text.push_str(whole),
+        }
+        if let Some(fraction) = fraction {
+            text.push(self.decimal_separator());
+            text.push_str(fraction);
+        }
+        text
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Accepts a language code or a full locale name such as "de_DE.UTF-8",
+    /// "fr-CA" or "C".
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let language = input
+            .trim()
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match language.as_str() {
+            "c" | "posix" => Ok(Locale::Posix),
+            "en" | "english" => Ok(Locale::English),
+            "de" | "german" | "deutsch" => Ok(Locale::German),
+            "fr" | "french" | "français" => Ok(Locale::French),
+            _ => Err(format!(
+                "Unsupported locale: {}. Expected C, en, de or fr.",
+                input
+            )),
+        }
+    }
+}
+
+/// Whether `digits` is split into groups of three by separators, with one to
+/// three digits in front, as in "1.234.567".
+fn is_grouped(digits: &str, is_group: impl Fn(char) -> bool) -> bool {
+    let mut groups = digits.split(is_group);
+    let first = groups.next().unwrap_or_default();
+    let mut rest = groups.peekable();
+    rest.peek().is_some()
+        && (1..=3).contains(&first.len())
+        && first.chars().all(|c| c.is_ascii_digit())
+        && rest.all(|group| group.len() == 3 && group.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_locale_names() {
+        assert_eq!("de_DE.UTF-8".parse(), Ok(Locale::German));
+        assert_eq!("fr-CA".parse(), Ok(Locale::French));
+        assert_eq!("en_US".parse(), Ok(Locale::English));
+        assert_eq!("C.UTF-8".parse(), Ok(Locale::Posix));
+        assert_eq!("POSIX".parse(), Ok(Locale::Posix));
+        assert!("tlh_KL".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn reads_numbers_written_in_the_locale() {
+        let cases = [
+            (Locale::German, "36,6", "36.6"),
+            (Locale::German, "-1.234,5", "-1234.5"),
+            (Locale::German, "1.500", "1500"),
+            (Locale::German, "36.6", "36.6"),
+            (Locale::German, "1,5e3", "1.5e3"),
+            (Locale::French, "36,6", "36.6"),
+            (Locale::French, "12\u{202F}345,6", "12345.6"),
+            (Locale::French, "12\u{00A0}345", "12345"),
+            (Locale::French, "1 000", "1000"),
+            (Locale::French, "1 00", "1 00"),
+            (Locale::French, "36.6", "36.6"),
+            (Locale::English, "1,234.5", "1234.5"),
+            (Locale::English, "36,6", "36,6"),
+            (Locale::Posix, "1,234", "1,234"),
+        ];
+
+        for (locale, input, expected) in cases {
+            assert_eq!(locale.delocalize(input), expected, "{} {}", locale, input);
+        }
+    }
+
+    #[test]
+    fn writes_numbers_in_the_locale() {
+        assert_eq!(Locale::German.localize("-12345.678"), "-12.345,678");
+        assert_eq!(Locale::German.localize("1234.5"), "1234,5");
+        assert_eq!(Locale::English.localize("1234567"), "1,234,567");
+        assert_eq!(Locale::French.localize("98765.4"), "98\u{202F}765,4");
+        assert_eq!(Locale::French.localize("37.(7)"), "37,(7)");
+        assert_eq!(Locale::Posix.localize("12345.6"), "12345.6");
+        assert_eq!(
+            Locale::German.localize_ungrouped("-12345.678"),
+            "-12345,678"
+        );
+        assert_eq!(Locale::English.localize_ungrouped("1234567"), "1234567");
+    }
+
+    #[test]
+    fn names_scales_in_the_locale() {
+        assert_eq!(Locale::Posix.scale_name(Scale::Celsius), "celsius");
+        assert_eq!(Locale::German.degrees(), "Grad");
+        assert_eq!(Locale::French.scale_name(Scale::Kelvin), "Kelvin");
+        assert_eq!(
+            Locale::German.temperature("37", Scale::Celsius),
+            "37 Grad Celsius"
+        );
+        assert_eq!(
+            Locale::French.temperature("300", Scale::Kelvin),
+            "300 Kelvin"
+        );
+    }
+}
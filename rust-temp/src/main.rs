@@ -1,38 +1,1075 @@
-use rust_convert::{c_to_f, f_to_c};
-use std::{env, process};
-
-const CELSIUS_FLAGS: [&str; 2] = ["-c", "--celsius"];
-const FAHRENHEIT_FLAGS: [&str; 2] = ["-f", "--fahrenheit"];
-const USAGE_STR: &str =
-    "Usage: rust-convert <arg> <val>\n\tvalid args: --celsius/-c, --fahrenheit/-f";
-
-fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    if args.len() == 3 {
-        // Get args
-        let temp_type: &str = &args[1];
-        let temp_val: f64 = match &args[2].parse::<f64>() {
-            Ok(value) => *value,
-            Err(_) => {
-                eprintln!("Problem parsing value: {}.\n{}", &args[2], USAGE_STR);
-                process::exit(1);
-            }
-        };
-
-        // Convert and report
-        if CELSIUS_FLAGS.contains(&temp_type) {
-            let fahrenheit = c_to_f(temp_val);
-            println!("{:.2} degrees fahrenheit.", fahrenheit);
-        } else if FAHRENHEIT_FLAGS.contains(&temp_type) {
-            let celsius = f_to_c(temp_val);
-            println!("{:.2} degrees celsius.", celsius);
-        } else {
-            eprintln!("Unexpected argument: {}.\n{}", temp_type, USAGE_STR);
-            process::exit(1);
+mod cli;
+mod messages;
+mod output;
+mod repl;
+mod watch;
+
+use clap::{
+    Parser,
+    error::{ContextKind, ContextValue, ErrorKind},
+};
+use rust_convert::{
+    AnnotateOptions, BatchError, BatchOptions, Calibration, CalibrationError, ColorError,
+    ConversionError, ConversionTable, DefinitionError, Dimension, DocumentError, DocumentOptions,
+    Estimate, ExactNotation, Expression, ExpressionError, ExpressionErrorKind, FieldTarget, Locale,
+    NumberFormat, Precision, Quantity, RangeWarning, Rational, Rtd, RtdError, Scale, SensorError,
+    TableError, Temperature, TemperatureDelta, ThermocoupleError, Unit, UnitRegistry, WeatherError,
+    annotate, blackbody_color, boiling_point, boiling_point_at_altitude, boiling_pressure,
+    convert_batch, convert_between, convert_csv, convert_delta_between, convert_exact_between,
+    convert_exact_delta_between, convert_json, default_units_path, dew_point, find_mentions,
+    heat_index, humidex, load_calibration_file, load_units_file, parse_localized_expression_with,
+    parse_report, read_sensors, significant_figures, wind_chill, write_colors, write_observations,
+    write_readings,
+};
+use std::{
+    env, fmt,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, IsTerminal, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use crate::{
+    cli::{
+        AirArgs, AnnotateArgs, BatchArgs, BoilingPointArgs, BoilingPointInput, Cli, ColorArgs,
+        Command, ConvertArgs, DocArgs, HumidityArgs, MetarArgs, RtdArgs, SensorsArgs, TableArgs,
+        ThermocoupleArgs, raw_option,
+    },
+    output::{OutputFormat, Report, print_error, print_report, temperature_sentence},
+};
+
+/// Everything that can go wrong between reading argv and printing a result.
+#[derive(Debug)]
+enum CliError {
+    IncorrectUsage,
+    UnexpectedArgument(String),
+    /// Any other problem clap found with the arguments.
+    Arguments(clap::Error),
+    Conversion(ConversionError),
+    Expression(ExpressionError),
+    Table(TableError),
+    Batch(BatchError),
+    Document(DocumentError),
+    Weather(WeatherError),
+    Thermocouple(ThermocoupleError),
+    Rtd(RtdError),
+    Color(ColorError),
+    Sensors(SensorError),
+    /// Neither the thermal nor the hwmon class under this sysfs root had a
+    /// temperature sensor.
+    NoSensors(PathBuf),
+    /// `watch` raised this many alerts.
+    AlertsFired(usize),
+    Definitions(DefinitionError),
+    Calibration(CalibrationError),
+    Io(io::Error),
+    /// A bare number was entered in the REPL before any units were chosen.
+    NoScales,
+    /// The REPL has no command with this name.
+    UnknownCommand(String),
+    /// A REPL command was given a value it can't use.
+    InvalidSetting {
+        command: String,
+        value: String,
+    },
+}
+
+impl CliError {
+    /// Translates a clap parse failure, keeping the messages the converter has
+    /// always printed for the single-value flags.
+    fn from_clap(error: clap::Error) -> Self {
+        match error.kind() {
+            ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => error.exit(),
+            ErrorKind::UnknownArgument => match error.get(ContextKind::InvalidArg) {
+                Some(ContextValue::String(arg)) if arg.starts_with('-') => {
+                    CliError::UnexpectedArgument(arg.clone())
+                }
+                _ => CliError::IncorrectUsage,
+            },
+            ErrorKind::ArgumentConflict => CliError::IncorrectUsage,
+            _ => CliError::Arguments(error),
+        }
+    }
+
+    /// Each failure gets its own exit code so scripts can tell them apart.
+    fn code(&self) -> u8 {
+        match self {
+            CliError::IncorrectUsage
+            | CliError::UnexpectedArgument(_)
+            | CliError::Arguments(_)
+            | CliError::NoScales
+            | CliError::UnknownCommand(_)
+            | CliError::InvalidSetting { .. } => 2,
+            CliError::Conversion(error)
+            | CliError::Expression(ExpressionError {
+                kind: ExpressionErrorKind::Conversion(error),
+                ..
+            })
+            | CliError::Table(TableError::Conversion(error))
+            | CliError::Batch(BatchError::Line { error, .. })
+            | CliError::Document(
+                DocumentError::Field { error, .. } | DocumentError::Line { error, .. },
+            )
+            | CliError::Weather(WeatherError::Conversion(error))
+            | CliError::Rtd(RtdError::Conversion(error)) => conversion_exit_code(error),
+            CliError::Expression(_)
+            | CliError::Weather(_)
+            | CliError::Document(DocumentError::Json { .. }) => 3,
+            CliError::Thermocouple(_) | CliError::Rtd(_) | CliError::Color(_) => 10,
+            CliError::Table(_)
+            | CliError::Batch(BatchError::MissingColumn(_) | BatchError::MissingField { .. })
+            | CliError::Document(
+                DocumentError::InvalidPath(_)
+                | DocumentError::UnnamedField(_)
+                | DocumentError::MissingColumn(_),
+            ) => 2,
+            CliError::Definitions(_) => 9,
+            CliError::AlertsFired(_) => 11,
+            CliError::Calibration(_) => 12,
+            CliError::Io(_)
+            | CliError::Batch(BatchError::Io(_))
+            | CliError::Document(DocumentError::Io(_))
+            | CliError::Sensors(_)
+            | CliError::NoSensors(_) => 1,
+        }
+    }
+
+    /// A short name for the kind of failure, for JSON output.
+    fn kind(&self) -> &'static str {
+        match self {
+            CliError::IncorrectUsage => "usage",
+            CliError::UnexpectedArgument(_) => "unexpected_argument",
+            CliError::Arguments(_) => "invalid_arguments",
+            CliError::Conversion(error)
+            | CliError::Expression(ExpressionError {
+                kind: ExpressionErrorKind::Conversion(error),
+                ..
+            })
+            | CliError::Table(TableError::Conversion(error))
+            | CliError::Batch(BatchError::Line { error, .. })
+            | CliError::Document(
+                DocumentError::Field { error, .. } | DocumentError::Line { error, .. },
+            )
+            | CliError::Weather(WeatherError::Conversion(error))
+            | CliError::Rtd(RtdError::Conversion(error)) => conversion_kind(error),
+            CliError::Expression(_) => "invalid_expression",
+            CliError::Weather(WeatherError::InvalidHumidity(_)) => "invalid_humidity",
+            CliError::Weather(WeatherError::InvalidWindSpeed(_)) => "invalid_wind_speed",
+            CliError::Weather(WeatherError::InvalidPressure(_)) => "invalid_pressure",
+            CliError::Weather(WeatherError::InvalidAltitude(_)) => "invalid_altitude",
+            CliError::Weather(WeatherError::NoBoilingPoint(_)) => "no_boiling_point",
+            CliError::Weather(WeatherError::HeatIndexOutOfRange(_)) => "out_of_formula_range",
+            CliError::Thermocouple(_) | CliError::Rtd(_) => "out_of_sensor_range",
+            CliError::Color(_) => "out_of_color_range",
+            CliError::Table(_) => "invalid_table",
+            CliError::Batch(BatchError::MissingColumn(_))
+            | CliError::Document(DocumentError::MissingColumn(_)) => "missing_column",
+            CliError::Batch(BatchError::MissingField { .. }) => "missing_field",
+            CliError::Document(DocumentError::InvalidPath(_) | DocumentError::UnnamedField(_)) => {
+                "invalid_path"
+            }
+            CliError::Document(DocumentError::Json { .. }) => "invalid_json",
+            CliError::Definitions(_) => "invalid_definitions",
+            CliError::Calibration(_) => "invalid_calibration",
+            CliError::Io(_)
+            | CliError::Batch(BatchError::Io(_))
+            | CliError::Document(DocumentError::Io(_))
+            | CliError::Sensors(_) => "io",
+            CliError::NoSensors(_) => "no_sensors",
+            CliError::AlertsFired(_) => "alerts_fired",
+            CliError::NoScales => "no_units",
+            CliError::UnknownCommand(_) => "unknown_command",
+            CliError::InvalidSetting { .. } => "invalid_setting",
+        }
+    }
+
+    fn shows_usage(&self) -> bool {
+        matches!(
+            self,
+            CliError::IncorrectUsage
+                | CliError::UnexpectedArgument(_)
+                | CliError::Conversion(ConversionError::InvalidNumber(_))
+        )
+    }
+}
+
+fn conversion_kind(error: &ConversionError) -> &'static str {
+    match error {
+        ConversionError::InvalidNumber(_) => "invalid_number",
+        ConversionError::NotFinite => "not_finite",
+        ConversionError::BelowAbsoluteZero { .. }
+        | ConversionError::UnitBelowAbsoluteZero { .. } => "below_absolute_zero",
+        ConversionError::UnknownScale(_) => "unknown_scale",
+        ConversionError::UnknownUnit(_) => "unknown_unit",
+        ConversionError::MalformedUnit { .. } => "malformed_unit",
+        ConversionError::IncompatibleUnits { .. } => "incompatible_units",
+        ConversionError::ExactOverflow => "exact_overflow",
+    }
+}
+
+fn conversion_exit_code(error: &ConversionError) -> u8 {
+    match error {
+        ConversionError::InvalidNumber(_) => 3,
+        ConversionError::NotFinite | ConversionError::ExactOverflow => 4,
+        ConversionError::BelowAbsoluteZero { .. }
+        | ConversionError::UnitBelowAbsoluteZero { .. } => 5,
+        ConversionError::UnknownScale(_) => 6,
+        ConversionError::UnknownUnit(_) | ConversionError::MalformedUnit { .. } => 7,
+        ConversionError::IncompatibleUnits { .. } => 8,
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::IncorrectUsage => f.write_str("Incorrect usage."),
+            CliError::UnexpectedArgument(arg) => write!(f, "Unexpected argument: {}.", arg),
+            CliError::Arguments(error) => write!(f, "{}", error.to_string().trim_end()),
+            CliError::Conversion(error) => write!(f, "{}", error),
+            CliError::Expression(error) => write!(f, "{}", error),
+            CliError::Table(error) => write!(f, "{}", error),
+            CliError::Batch(error) => write!(f, "{}", error),
+            CliError::Document(error) => write!(f, "{}", error),
+            CliError::Weather(error) => write!(f, "{}", error),
+            CliError::Thermocouple(error) => write!(f, "{}", error),
+            CliError::Rtd(error) => write!(f, "{}", error),
+            CliError::Color(error) => write!(f, "{}", error),
+            CliError::Sensors(error) => write!(f, "{}", error),
+            CliError::NoSensors(root) => {
+                write!(f, "No temperature sensors found under {}.", root.display())
+            }
+            CliError::AlertsFired(1) => f.write_str("1 alert fired."),
+            CliError::AlertsFired(count) => write!(f, "{} alerts fired.", count),
+            CliError::Definitions(error) => write!(f, "{}", error),
+            CliError::Calibration(error) => write!(f, "{}", error),
+            CliError::Io(error) => write!(f, "{}", error),
+            CliError::NoScales => f.write_str(
+                "No units to convert between yet. Enter an expression such as 72 F to C, or use :scale and :to.",
+            ),
+            CliError::UnknownCommand(command) => {
+                write!(f, "Unknown command: :{}. Type :help for a list.", command)
+            }
+            CliError::InvalidSetting { command, value } => {
+                write!(f, "Invalid value for :{}: {:?}.", command, value)
+            }
+        }
+    }
+}
+
+impl From<ConversionError> for CliError {
+    fn from(error: ConversionError) -> Self {
+        CliError::Conversion(error)
+    }
+}
+
+impl From<ExpressionError> for CliError {
+    fn from(error: ExpressionError) -> Self {
+        CliError::Expression(error)
+    }
+}
+
+impl From<TableError> for CliError {
+    fn from(error: TableError) -> Self {
+        CliError::Table(error)
+    }
+}
+
+impl From<WeatherError> for CliError {
+    fn from(error: WeatherError) -> Self {
+        CliError::Weather(error)
+    }
+}
+
+impl From<ThermocoupleError> for CliError {
+    fn from(error: ThermocoupleError) -> Self {
+        CliError::Thermocouple(error)
+    }
+}
+
+impl From<RtdError> for CliError {
+    fn from(error: RtdError) -> Self {
+        CliError::Rtd(error)
+    }
+}
+
+impl From<ColorError> for CliError {
+    fn from(error: ColorError) -> Self {
+        CliError::Color(error)
+    }
+}
+
+impl From<SensorError> for CliError {
+    fn from(error: SensorError) -> Self {
+        CliError::Sensors(error)
+    }
+}
+
+impl From<BatchError> for CliError {
+    fn from(error: BatchError) -> Self {
+        CliError::Batch(error)
+    }
+}
+
+impl From<DocumentError> for CliError {
+    fn from(error: DocumentError) -> Self {
+        CliError::Document(error)
+    }
+}
+
+impl From<DefinitionError> for CliError {
+    fn from(error: DefinitionError) -> Self {
+        CliError::Definitions(error)
+    }
+}
+
+impl From<CalibrationError> for CliError {
+    fn from(error: CalibrationError) -> Self {
+        CliError::Calibration(error)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(error: io::Error) -> Self {
+        CliError::Io(error)
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::try_parse();
+    let (locale, output) = match &cli {
+        Ok(cli) => (cli.locale(), cli.output),
+        Err(_) => {
+            let args: Vec<String> = env::args().collect();
+            let locale = raw_option(&args, "--locale")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(Locale::from_env);
+            let output = raw_option(&args, "--format")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_default();
+            (locale, output)
+        }
+    };
+
+    match cli.map_err(CliError::from_clap).and_then(run) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            print_error(&error, output, locale);
+            ExitCode::from(error.code())
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), CliError> {
+    if cli.mixes_subcommand_with_conversion() {
+        return Err(CliError::IncorrectUsage);
+    }
+    let registry = load_registry(&cli)?;
+    let number_format = cli.number_format();
+    let exact = cli.exact_notation();
+    let output = cli.output;
+    let calibration = cli
+        .calibration
+        .as_deref()
+        .map(|path| load_calibration_file(path, cli.fit))
+        .transpose()?;
+    let calibration = calibration.as_ref();
+
+    match cli.command {
+        Some(Command::Table(args)) => print_table(args, &registry, number_format),
+        Some(Command::Batch(args)) => {
+            // Grouped digits would split the value column for other tools,
+            // so batch output is grouped only when a locale is asked for.
+            let number_format = if cli.locale.is_some() {
+                number_format
+            } else {
+                number_format.without_grouping()
+            };
+            run_batch(args, &registry, number_format, calibration)
+        }
+        Some(Command::Doc(args)) => convert_document(args, &registry, number_format, calibration),
+        Some(Command::Annotate(args)) => {
+            // Prose reads better in whole degrees unless asked otherwise.
+            let number_format = match (cli.precision, &cli.sig_figs) {
+                (None, None) => NumberFormat {
+                    precision: Precision::Decimals(0),
+                    ..number_format
+                },
+                _ => number_format,
+            };
+            annotate_text(args, number_format)
+        }
+        Some(Command::HeatIndex(args)) => {
+            let (temperature, humidity) =
+                read_humidity_args(&args, number_format.locale, calibration)?;
+            let estimate = heat_index(temperature, humidity)?;
+            print_temperature_estimate(temperature, estimate, number_format, output)
+        }
+        Some(Command::WindChill(args)) => {
+            let locale = number_format.locale;
+            let temperature = read_temperature(&args.air, locale, calibration)?;
+            let unit = registry.parse_unit(&args.wind_unit)?;
+            let wind = Quantity::parse(&locale.delocalize(&args.wind), &unit)?;
+            let estimate = wind_chill(temperature, &wind)?;
+            print_temperature_estimate(temperature, estimate, number_format, output)
+        }
+        Some(Command::DewPoint(args)) => {
+            let (temperature, humidity) =
+                read_humidity_args(&args, number_format.locale, calibration)?;
+            let estimate = dew_point(temperature, humidity)?;
+            print_temperature_estimate(temperature, estimate, number_format, output)
+        }
+        Some(Command::Humidex(args)) => {
+            let (temperature, humidity) =
+                read_humidity_args(&args, number_format.locale, calibration)?;
+            let estimate = humidex(temperature, humidity)?;
+            print_temperature_estimate(temperature, estimate, number_format, output)
+        }
+        Some(Command::BoilingPoint(args)) => {
+            find_boiling_point(args, &registry, number_format, output)
+        }
+        Some(Command::Thermocouple(args)) => convert_thermocouple(args, number_format, calibration),
+        Some(Command::Rtd(args)) => convert_rtd(args, number_format, calibration),
+        Some(Command::Sensors(args)) => list_sensors(args, number_format, calibration),
+        Some(Command::Watch(args)) => watch::run(args, number_format, calibration),
+        Some(Command::Metar(args)) => read_metars(args, number_format),
+        Some(Command::Color(args)) => show_colors(args, number_format),
+        None if !cli.expression.is_empty() => evaluate(
+            &cli.expression.join(" "),
+            cli.delta,
+            &registry,
+            number_format,
+            exact,
+            output,
+            calibration,
+        ),
+        None if cli.interactive
+            || (cli.convert.conversion().is_none() && io::stdin().is_terminal()) =>
+        {
+            repl::run(repl::Session::new(
+                &registry,
+                number_format,
+                exact,
+                cli.delta,
+                output,
+                calibration,
+            ))
+        }
+        None => convert(
+            cli.convert,
+            cli.delta,
+            number_format,
+            exact,
+            output,
+            calibration,
+        ),
+    }
+}
+
+/// The built-in units plus the user's definitions: the `--units-file` if one
+/// was given, otherwise the default units file if it exists.
+fn load_registry(cli: &Cli) -> Result<UnitRegistry, CliError> {
+    let mut registry = UnitRegistry::default();
+    match &cli.units_file {
+        Some(path) => {
+            load_units_file(path, &mut registry)?;
+        }
+        None => {
+            if let Some(path) = default_units_path().filter(|path| path.is_file()) {
+                load_units_file(&path, &mut registry)?;
+            }
+        }
+    }
+    Ok(registry)
+}
+
+fn convert(
+    args: ConvertArgs,
+    delta: bool,
+    number_format: NumberFormat,
+    exact: Option<ExactNotation>,
+    output: OutputFormat,
+    calibration: Option<&Calibration>,
+) -> Result<(), CliError> {
+    let (value, from, to) = args.conversion().ok_or(CliError::IncorrectUsage)?;
+    let locale = number_format.locale;
+    let value = &locale.delocalize(value);
+    let number_format = number_format.for_input(value);
+
+    let registry = UnitRegistry::builtin();
+    let (Some(from_unit), Some(to_unit)) = (registry.for_scale(from), registry.for_scale(to))
+    else {
+        unreachable!("every scale has a built-in unit");
+    };
+
+    let (input_value, output_value, text) = match exact {
+        Some(notation) => {
+            let value: Rational = value.parse()?;
+            let converted = if delta {
+                convert_exact_delta_between(value, from_unit, to_unit)?
+            } else {
+                convert_exact_between(value, from_unit, to_unit)?
+            };
+            let text = locale.localize(&converted.to_string_with(notation));
+            (value.to_f64(), converted.to_f64(), text)
+        }
+        None if delta => {
+            let difference = TemperatureDelta::parse(value, from)?;
+            let converted = convert_delta_between(difference.value(), from_unit, to_unit)?;
+            (
+                difference.value(),
+                converted,
+                number_format.format(converted),
+            )
+        }
+        None => {
+            let temperature = Temperature::parse(value, from)?;
+            let calibrated = calibrate_temperature(temperature, calibration)?;
+            let converted = convert_between(calibrated.value(), from_unit, to_unit)?;
+            (
+                temperature.value(),
+                converted,
+                number_format.format(converted),
+            )
+        }
+    };
+
+    let report = Report {
+        input_value,
+        from: from_unit,
+        output_value,
+        to: to_unit,
+        text,
+        delta,
+    };
+    print_report(&report, output, locale);
+    Ok(())
+}
+
+fn evaluate(
+    expression: &str,
+    delta: bool,
+    registry: &UnitRegistry,
+    number_format: NumberFormat,
+    exact: Option<ExactNotation>,
+    output: OutputFormat,
+    calibration: Option<&Calibration>,
+) -> Result<(), CliError> {
+    let expression =
+        parse_localized_expression_with(expression, registry, number_format.locale, delta)?;
+    print_expression(&expression, number_format, exact, output, calibration)
+}
+
+/// Converts a parsed expression and prints the result. Differences and
+/// exact conversions are never calibrated, and other quantities than
+/// temperatures can't be.
+fn print_expression(
+    expression: &Expression,
+    number_format: NumberFormat,
+    exact: Option<ExactNotation>,
+    output: OutputFormat,
+    calibration: Option<&Calibration>,
+) -> Result<(), CliError> {
+    let locale = number_format.locale;
+    check_calibrated_unit(&expression.quantity.unit, calibration)?;
+    let (output_value, text) = match exact {
+        Some(notation) => {
+            let converted = expression.evaluate_exact()?;
+            let text = locale.localize(&converted.to_string_with(notation));
+            (converted.to_f64(), text)
+        }
+        None => {
+            let converted = match calibration {
+                Some(calibration) if !expression.delta => {
+                    let raw = &expression.quantity;
+                    let quantity = Quantity::new(calibration.apply(raw.value), raw.unit.clone());
+                    Expression {
+                        quantity,
+                        ..expression.clone()
+                    }
+                    .evaluate()?
+                    .value
+                }
+                _ => expression.evaluate()?.value,
+            };
+            let text = number_format
+                .for_input(&expression.literal)
+                .format(converted);
+            (converted, text)
+        }
+    };
+
+    let report = Report {
+        input_value: expression.quantity.value,
+        from: &expression.quantity.unit,
+        output_value,
+        to: &expression.target,
+        text,
+        delta: expression.delta,
+    };
+    print_report(&report, output, locale);
+    Ok(())
+}
+
+fn print_table(
+    args: TableArgs,
+    registry: &UnitRegistry,
+    number_format: NumberFormat,
+) -> Result<(), CliError> {
+    let unit = registry.parse_unit(&args.scale)?;
+    let table = ConversionTable::for_unit(args.from, args.to, args.step, &unit, registry)?;
+
+    // Match the most precise of the range's values, since rows have no
+    // literal of their own.
+    let number_format = match number_format.precision {
+        Precision::MatchInput => [args.from, args.to, args.step]
+            .iter()
+            .filter_map(|value| significant_figures(&value.to_string()))
+            .max()
+            .map_or(number_format, |figures| NumberFormat {
+                precision: Precision::SignificantFigures(figures),
+                ..number_format
+            }),
+        _ => number_format,
+    };
+
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    table.write_with(args.format, &number_format, &mut handle)?;
+    handle.flush()?;
+    Ok(())
+}
+
+fn run_batch(
+    args: BatchArgs,
+    registry: &UnitRegistry,
+    number_format: NumberFormat,
+    calibration: Option<&Calibration>,
+) -> Result<(), CliError> {
+    let from = registry.parse_unit(&args.from)?;
+    let to = registry.parse_unit(&args.to)?;
+    check_calibrated_unit(&from, calibration)?;
+    let options = BatchOptions {
+        column: args.column,
+        delimiter: args.delimiter,
+        strict: args.strict,
+        number_format,
+        calibration: calibration.cloned(),
+        ..BatchOptions::new(from, to)
+    };
+    let locale = number_format.locale;
+    let report_bad_line = |error: &BatchError| {
+        eprintln!("{}", messages::skipped_batch_line(error, locale));
+    };
+
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    match args.input {
+        Some(path) if path.as_os_str() != "-" => {
+            let file = File::open(path)?;
+            convert_batch(BufReader::new(file), &mut writer, &options, report_bad_line)?;
+        }
+        _ => {
+            let stdin = io::stdin();
+            convert_batch(stdin.lock(), &mut writer, &options, report_bad_line)?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn convert_document(
+    args: DocArgs,
+    registry: &UnitRegistry,
+    number_format: NumberFormat,
+    calibration: Option<&Calibration>,
+) -> Result<(), CliError> {
+    let from = registry.parse_unit(&args.from)?;
+    let to = registry.parse_unit(&args.to)?;
+    check_calibrated_unit(&from, calibration)?;
+    let target = match (args.rename, args.add) {
+        (Some(name), _) => FieldTarget::Rename(name),
+        (None, Some(name)) => FieldTarget::Add(name),
+        (None, None) => FieldTarget::Replace,
+    };
+    let options = DocumentOptions {
+        target,
+        delimiter: args.delimiter,
+        strict: args.strict,
+        number_format,
+        calibration: calibration.cloned(),
+        ..DocumentOptions::new(from, to)
+    };
+    let locale = number_format.locale;
+
+    let reader: Box<dyn BufRead> = match args.input {
+        Some(path) if path.as_os_str() != "-" => Box::new(BufReader::new(File::open(path)?)),
+        _ => Box::new(io::stdin().lock()),
+    };
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    match (args.path, args.column) {
+        (Some(path), _) => {
+            convert_json(reader, &mut writer, &path, &options, |path, error| {
+                eprintln!("{}", messages::skipped_field(path, error, locale));
+            })?;
+        }
+        (None, Some(column)) => {
+            convert_csv(reader, &mut writer, &column, &options, |line, error| {
+                eprintln!("{}", messages::skipped_line(line, error, locale));
+            })?;
+        }
+        (None, None) => return Err(CliError::IncorrectUsage),
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn annotate_text(args: AnnotateArgs, number_format: NumberFormat) -> Result<(), CliError> {
+    let options = AnnotateOptions {
+        template: args.template,
+        to: args.to.as_deref().map(str::parse).transpose()?,
+        ..AnnotateOptions::new(number_format)
+    };
+
+    let stdout = io::stdout();
+    let mut writer = BufWriter::new(stdout.lock());
+    let mut annotate_from = |mut reader: Box<dyn BufRead>, name: Option<&str>| {
+        let mut offset = 0;
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 0 {
+            if args.dry_run {
+                for mention in find_mentions(&line, &options) {
+                    if let Some(name) = name {
+                        write!(writer, "{}:", name)?;
+                    }
+                    writeln!(
+                        writer,
+                        "{}-{}: {} -> {}",
+                        offset + mention.start,
+                        offset + mention.end,
+                        mention.original,
+                        mention.replacement
+                    )?;
+                }
+            } else {
+                writer.write_all(annotate(&line, &options).as_bytes())?;
+            }
+            offset += line.len();
+            line.clear();
+        }
+        Ok::<(), CliError>(())
+    };
+
+    if args.input.is_empty() {
+        annotate_from(Box::new(io::stdin().lock()), None)?;
+    }
+    for path in &args.input {
+        let reader = BufReader::new(File::open(path)?);
+        annotate_from(Box::new(reader), Some(&path.display().to_string()))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn find_boiling_point(
+    args: BoilingPointArgs,
+    registry: &UnitRegistry,
+    number_format: NumberFormat,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    let locale = number_format.locale;
+    let scale: Scale = args.scale.parse()?;
+    let pressure_unit = registry.parse_unit(&args.pressure_unit)?;
+    let BoilingPointInput {
+        altitude,
+        pressure,
+        boils_at,
+    } = args.input;
+
+    if let Some(input) = boils_at {
+        let temperature = Temperature::parse(&locale.delocalize(&input), scale)?;
+        let estimate = boiling_pressure(temperature, &pressure_unit)?;
+        return print_estimate(
+            &temperature_quantity(temperature),
+            estimate,
+            number_format,
+            output,
+        );
+    }
+
+    let (input, estimate) = match (altitude, pressure) {
+        (Some(input), _) => {
+            let unit = registry.parse_unit(&args.altitude_unit)?;
+            let altitude = Quantity::parse(&locale.delocalize(&input), &unit)?;
+            let estimate = boiling_point_at_altitude(&altitude, scale)?;
+            (altitude, estimate)
+        }
+        (None, Some(input)) => {
+            let pressure = Quantity::parse(&locale.delocalize(&input), &pressure_unit)?;
+            let estimate = boiling_point(&pressure, scale)?;
+            (pressure, estimate)
         }
+        (None, None) => unreachable!("clap requires one of the inputs"),
+    };
+    let Estimate { value, warnings } = estimate;
+    let estimate = Estimate {
+        value: temperature_quantity(value),
+        warnings,
+    };
+    print_estimate(&input, estimate, number_format, output)
+}
+
+fn convert_thermocouple(
+    args: ThermocoupleArgs,
+    number_format: NumberFormat,
+    calibration: Option<&Calibration>,
+) -> Result<(), CliError> {
+    let locale = number_format.locale;
+    let scale: Scale = args.scale.parse()?;
+    let value = locale.delocalize(&args.value);
+    let cold_junction = match &args.cold_junction {
+        Some(input) => Temperature::parse(&locale.delocalize(input), scale)?,
+        None => Temperature::new(0.0, Scale::Celsius)?,
+    };
+
+    if args.reverse {
+        let temperature = Temperature::parse(&value, scale)?;
+        let millivolts = args.kind.compensated_voltage(temperature, cold_junction)?;
+        println!("{} mV.", number_format.format(millivolts));
+    } else {
+        let millivolts = parse_number(&value, &args.value)?;
+        let temperature = args
+            .kind
+            .compensated_temperature(millivolts, cold_junction)?
+            .to(Scale::Celsius);
+        let temperature = calibrate_temperature(temperature, calibration)?.to(scale);
+        let text = number_format.format(temperature.value());
+        println!("{}", temperature_sentence(&text, scale, locale));
+    }
+    Ok(())
+}
+
+fn convert_rtd(
+    args: RtdArgs,
+    number_format: NumberFormat,
+    calibration: Option<&Calibration>,
+) -> Result<(), CliError> {
+    let locale = number_format.locale;
+    let scale: Scale = args.scale.parse()?;
+    let value = locale.delocalize(&args.value);
+    let rtd = Rtd::iec_60751(args.r0);
+
+    if args.reverse {
+        let ohms = rtd.resistance(Temperature::parse(&value, scale)?)?;
+        println!("{} Ω.", number_format.format(ohms));
     } else {
-        eprintln!("Incorrect usage.\n{}", USAGE_STR);
-        process::exit(1);
+        let ohms = parse_number(&value, &args.value)?;
+        let temperature = rtd.temperature(ohms)?.to(Scale::Celsius);
+        let temperature = calibrate_temperature(temperature, calibration)?.to(scale);
+        let text = number_format.format(temperature.value());
+        println!("{}", temperature_sentence(&text, scale, locale));
+    }
+    Ok(())
+}
+
+fn list_sensors(
+    args: SensorsArgs,
+    number_format: NumberFormat,
+    calibration: Option<&Calibration>,
+) -> Result<(), CliError> {
+    let locale = number_format.locale;
+    let scale: Scale = args.scale.parse()?;
+    let mut readings = read_sensors(&args.root, |error| {
+        eprintln!("{}", messages::skipped_reading(error, locale));
+    })?;
+    readings.retain_mut(
+        |reading| match calibrate_temperature(reading.temperature, calibration) {
+            Ok(temperature) => {
+                reading.temperature = temperature;
+                true
+            }
+            Err(error) => {
+                eprintln!("{}", messages::skipped_value(&reading.path, &error, locale));
+                false
+            }
+        },
+    );
+    if readings.is_empty() {
+        return Err(CliError::NoSensors(args.root));
+    }
+
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    write_readings(&readings, scale, args.format, &number_format, &mut handle)?;
+    handle.flush()?;
+    Ok(())
+}
+
+fn read_metars(args: MetarArgs, number_format: NumberFormat) -> Result<(), CliError> {
+    let locale = number_format.locale;
+    let scale: Scale = args.scale.parse()?;
+    let reader: Box<dyn BufRead> = match args.input {
+        Some(path) if path.as_os_str() != "-" => Box::new(BufReader::new(File::open(path)?)),
+        _ => Box::new(io::stdin().lock()),
+    };
+
+    let mut observations = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_report(&line) {
+            Ok(observation) => observations.push(observation),
+            Err(error) => eprintln!("{}", messages::skipped_report(index + 1, &error, locale)),
+        }
+    }
+
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    write_observations(
+        &observations,
+        scale,
+        args.format,
+        &number_format,
+        &mut handle,
+    )?;
+    handle.flush()?;
+    Ok(())
+}
+
+fn show_colors(args: ColorArgs, number_format: NumberFormat) -> Result<(), CliError> {
+    let locale = number_format.locale;
+    let scale: Scale = args.scale.parse()?;
+    let colors = args
+        .temperatures
+        .iter()
+        .map(|input| {
+            let temperature = Temperature::parse(&locale.delocalize(input), scale)?;
+            Ok(blackbody_color(temperature)?)
+        })
+        .collect::<Result<Vec<_>, CliError>>()?;
+
+    // Swatches are escape codes, so only for a terminal that hasn't opted
+    // out with NO_COLOR.
+    let stdout = io::stdout();
+    let swatches = stdout.is_terminal() && env::var_os("NO_COLOR").is_none();
+    let mut handle = stdout.lock();
+    write_colors(
+        &colors,
+        scale,
+        args.format,
+        &number_format,
+        swatches,
+        &mut handle,
+    )?;
+    handle.flush()?;
+    Ok(())
+}
+
+/// Fails if a --calibration was given for values that aren't temperatures,
+/// since its points map raw temperatures to reference ones.
+fn check_calibrated_unit(unit: &Unit, calibration: Option<&Calibration>) -> Result<(), CliError> {
+    if calibration.is_some() && unit.dimension != Dimension::Temperature {
+        return Err(CalibrationError::NotTemperature(unit.symbol.clone()).into());
+    }
+    Ok(())
+}
+
+/// Corrects a raw temperature with the --calibration, on its own scale.
+fn calibrate_temperature(
+    temperature: Temperature,
+    calibration: Option<&Calibration>,
+) -> Result<Temperature, ConversionError> {
+    match calibration {
+        Some(calibration) => {
+            Temperature::new(calibration.apply(temperature.value()), temperature.scale())
+        }
+        None => Ok(temperature),
+    }
+}
+
+/// Parses a delocalized number, reporting the input as it was written.
+fn parse_number(value: &str, input: &str) -> Result<f64, ConversionError> {
+    value
+        .parse()
+        .map_err(|_| ConversionError::InvalidNumber(input.to_string()))
+}
+
+/// Reads the air temperature of a weather subcommand.
+fn read_temperature(
+    args: &AirArgs,
+    locale: Locale,
+    calibration: Option<&Calibration>,
+) -> Result<Temperature, CliError> {
+    let scale: Scale = args.scale.parse()?;
+    let temperature = Temperature::parse(&locale.delocalize(&args.temperature), scale)?;
+    Ok(calibrate_temperature(temperature, calibration)?)
+}
+
+/// Reads the air temperature and relative humidity of a weather subcommand.
+fn read_humidity_args(
+    args: &HumidityArgs,
+    locale: Locale,
+    calibration: Option<&Calibration>,
+) -> Result<(Temperature, f64), CliError> {
+    let temperature = read_temperature(&args.air, locale, calibration)?;
+    let humidity = parse_number(&locale.delocalize(&args.humidity), &args.humidity)?;
+    Ok((temperature, humidity))
+}
+
+/// Prints a quantity derived from `input` as a report in the --format, after
+/// any warnings about the formula's range.
+fn print_estimate(
+    input: &Quantity,
+    estimate: Estimate<Quantity>,
+    number_format: NumberFormat,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    let locale = number_format.locale;
+    print_warnings(&estimate.warnings, locale);
+    let report = Report {
+        input_value: input.value,
+        from: &input.unit,
+        output_value: estimate.value.value,
+        to: &estimate.value.unit,
+        text: number_format.format(estimate.value.value),
+        delta: false,
+    };
+    print_report(&report, output, locale);
+    Ok(())
+}
+
+/// Like [`print_estimate`], for a temperature derived from another.
+fn print_temperature_estimate(
+    input: Temperature,
+    estimate: Estimate,
+    number_format: NumberFormat,
+    output: OutputFormat,
+) -> Result<(), CliError> {
+    let Estimate { value, warnings } = estimate;
+    let estimate = Estimate {
+        value: temperature_quantity(value),
+        warnings,
+    };
+    print_estimate(
+        &temperature_quantity(input),
+        estimate,
+        number_format,
+        output,
+    )
+}
+
+/// A temperature as a quantity in its scale's built-in unit.
+fn temperature_quantity(temperature: Temperature) -> Quantity {
+    let Some(unit) = UnitRegistry::builtin().for_scale(temperature.scale()) else {
+        unreachable!("every scale has a built-in unit");
+    };
+    Quantity::new(temperature.value(), unit.clone())
+}
+
+fn print_warnings(warnings: &[RangeWarning], locale: Locale) {
+    for warning in warnings {
+        eprintln!("{}", messages::range_warning(*warning, locale));
     }
 }
@@ -0,0 +1,899 @@
+//! Usage and error text in each supported locale. English text comes from
+//! the errors' own `Display` impls; German and French are translated here.
+
+use std::path::Path;
+
+use rust_convert::{
+    Alert, AlertState, BatchError, CalibrationError, ColorError, ConversionError, Dimension,
+    DocumentError, ExpressionError, ExpressionErrorKind, Locale, MAX_ROWS, MetarError,
+    NumberFormat, RangeWarning, RtdError, SensorError, Side, TableError, ThermocoupleError,
+    WeatherError,
+};
+
+use crate::CliError;
+
+const USAGE_EN: &str = "Usage: rust-convert [--delta] [--exact] <expression>
+\te.g. \"72°F to K\", 98.6 F in C, -40 c
+       rust-convert [--delta] [--exact] <arg> <val>
+\tvalid args: --celsius/-c, --fahrenheit/-f, --kelvin/-k, --rankine/-r,
+\t            --reaumur, --delisle, --newton, --romer
+       rust-convert table --from <val> --to <val> [--step <val>] [--scale <scale>] [--format <format>]
+       rust-convert batch --from <unit> --to <unit> [--column <name>] [--strict] [file]
+       rust-convert doc --path <path>|--column <name> --from <unit> --to <unit> [--rename <name>|--add <name>] [--strict] [file]
+       rust-convert annotate [--to <scale>] [--template <template>] [--dry-run] [file...]
+       rust-convert heat-index|dew-point|humidex <val> [--scale <scale>] --humidity <percent>
+       rust-convert wind-chill <val> [--scale <scale>] --wind <val> [--wind-unit <unit>]
+       rust-convert boiling-point --altitude <val> [--altitude-unit <unit>] | --pressure <val> [--pressure-unit <unit>] [--scale <scale>]
+       rust-convert boiling-point --boils-at <val> [--scale <scale>] [--pressure-unit <unit>]
+       rust-convert thermocouple --type <K|J|T|E> <mV> [--cold-junction <val>] [--scale <scale>] [--reverse]
+       rust-convert rtd <ohms> [--r0 <ohms>] [--scale <scale>] [--reverse]
+       rust-convert sensors [--scale <scale>] [--root <path>] [--format <format>]
+       rust-convert metar [--scale <scale>] [--format <format>] [file]
+       rust-convert color <val>... [--scale <scale>] [--format <format>]
+       rust-convert watch [--sensor <path> | --file <path>] [--from <scale>] [--to <scale>] [--interval <seconds>]
+\t[--above <val>] [--below <val>] [--hysteresis <val>] [--alert-log <path>] [--alert-command <command>]
+\toptions: --units-file <path> loads extra unit definitions, --locale <locale> picks the language,
+\t         --format json writes JSON objects,
+\t         --calibration <file> [--fit <method>] corrects raw readings";
+
+const USAGE_DE: &str = "Verwendung: rust-convert [--delta] [--exact] <Ausdruck>
+\tz. B. \"72°F to K\", 98,6 F in C, -40 c
+       rust-convert [--delta] [--exact] <Option> <Wert>
+\tgültige Optionen: --celsius/-c, --fahrenheit/-f, --kelvin/-k, --rankine/-r,
+\t                  --reaumur, --delisle, --newton, --romer
+       rust-convert table --from <Wert> --to <Wert> [--step <Wert>] [--scale <Skala>] [--format <Format>]
+       rust-convert batch --from <Einheit> --to <Einheit> [--column <Name>] [--strict] [Datei]
+       rust-convert doc --path <Pfad>|--column <Name> --from <Einheit> --to <Einheit> [--rename <Name>|--add <Name>] [--strict] [Datei]
+       rust-convert annotate [--to <Skala>] [--template <Vorlage>] [--dry-run] [Datei...]
+       rust-convert heat-index|dew-point|humidex <Wert> [--scale <Skala>] --humidity <Prozent>
+       rust-convert wind-chill <Wert> [--scale <Skala>] --wind <Wert> [--wind-unit <Einheit>]
+       rust-convert boiling-point --altitude <Wert> [--altitude-unit <Einheit>] | --pressure <Wert> [--pressure-unit <Einheit>] [--scale <Skala>]
+       rust-convert boiling-point --boils-at <Wert> [--scale <Skala>] [--pressure-unit <Einheit>]
+       rust-convert thermocouple --type <K|J|T|E> <mV> [--cold-junction <Wert>] [--scale <Skala>] [--reverse]
+       rust-convert rtd <Ohm> [--r0 <Ohm>] [--scale <Skala>] [--reverse]
+       rust-convert sensors [--scale <Skala>] [--root <Pfad>] [--format <Format>]
+       rust-convert metar [--scale <Skala>] [--format <Format>] [Datei]
+       rust-convert color <Wert>... [--scale <Skala>] [--format <Format>]
+       rust-convert watch [--sensor <Pfad> | --file <Pfad>] [--from <Skala>] [--to <Skala>] [--interval <Sekunden>]
+\t[--above <Wert>] [--below <Wert>] [--hysteresis <Wert>] [--alert-log <Pfad>] [--alert-command <Befehl>]
+\tOptionen: --units-file <Pfad> lädt zusätzliche Einheiten, --locale <Gebietsschema> wählt die Sprache,
+\t          --format json gibt JSON-Objekte aus,
+\t          --calibration <Datei> [--fit <Verfahren>] korrigiert Rohwerte";
+
+const USAGE_FR: &str = "Utilisation : rust-convert [--delta] [--exact] <expression>
+\tp. ex. \"72°F to K\", 98,6 F in C, -40 c
+       rust-convert [--delta] [--exact] <option> <valeur>
+\toptions valides : --celsius/-c, --fahrenheit/-f, --kelvin/-k, --rankine/-r,
+\t                  --reaumur, --delisle, --newton, --romer
+       rust-convert table --from <valeur> --to <valeur> [--step <valeur>] [--scale <échelle>] [--format <format>]
+       rust-convert batch --from <unité> --to <unité> [--column <nom>] [--strict] [fichier]
+       rust-convert doc --path <chemin>|--column <nom> --from <unité> --to <unité> [--rename <nom>|--add <nom>] [--strict] [fichier]
+       rust-convert annotate [--to <échelle>] [--template <modèle>] [--dry-run] [fichier...]
+       rust-convert heat-index|dew-point|humidex <valeur> [--scale <échelle>] --humidity <pourcentage>
+       rust-convert wind-chill <valeur> [--scale <échelle>] --wind <valeur> [--wind-unit <unité>]
+       rust-convert boiling-point --altitude <valeur> [--altitude-unit <unité>] | --pressure <valeur> [--pressure-unit <unité>] [--scale <échelle>]
+       rust-convert boiling-point --boils-at <valeur> [--scale <échelle>] [--pressure-unit <unité>]
+       rust-convert thermocouple --type <K|J|T|E> <mV> [--cold-junction <valeur>] [--scale <échelle>] [--reverse]
+       rust-convert rtd <ohms> [--r0 <ohms>] [--scale <échelle>] [--reverse]
+       rust-convert sensors [--scale <échelle>] [--root <chemin>] [--format <format>]
+       rust-convert metar [--scale <échelle>] [--format <format>] [fichier]
+       rust-convert color <valeur>... [--scale <échelle>] [--format <format>]
+       rust-convert watch [--sensor <chemin> | --file <chemin>] [--from <échelle>] [--to <échelle>] [--interval <secondes>]
+\t[--above <valeur>] [--below <valeur>] [--hysteresis <valeur>] [--alert-log <chemin>] [--alert-command <commande>]
+\toptions : --units-file <chemin> charge des unités supplémentaires, --locale <locale> choisit la langue,
+\t          --format json écrit des objets JSON,
+\t          --calibration <fichier> [--fit <méthode>] corrige les valeurs brutes";
+
+const REPL_HELP_EN: &str = "Enter an expression such as 72 F to C, or a bare number to convert it
+between the last units used.
+  :scale <unit>        convert bare numbers from this unit
+  :to <unit>           convert bare numbers to this unit
+  :precision <n>       show n digits after the decimal point
+  :sig-figs <n|auto>   show n significant figures
+  :rounding <mode>     round half-even, half-up or truncate
+  :delta [on|off]      treat values as temperature differences
+  :exact [on|off|notation]  convert with exact fractions
+  :help                show this help
+  :quit                leave (or press Ctrl-D)";
+
+const REPL_HELP_DE: &str = "Gib einen Ausdruck wie 72 F to C ein oder eine Zahl allein, um sie
+zwischen den zuletzt benutzten Einheiten umzurechnen.
+  :scale <Einheit>     Zahlen aus dieser Einheit umrechnen
+  :to <Einheit>        Zahlen in diese Einheit umrechnen
+  :precision <n>       n Nachkommastellen anzeigen
+  :sig-figs <n|auto>   n signifikante Stellen anzeigen
+  :rounding <Modus>    half-even, half-up oder truncate runden
+  :delta [on|off]      Werte als Temperaturdifferenzen lesen
+  :exact [on|off|Schreibweise]  mit exakten Brüchen rechnen
+  :help                diese Hilfe anzeigen
+  :quit                beenden (oder Strg-D)";
+
+const REPL_HELP_FR: &str = "Saisissez une expression comme 72 F to C, ou un nombre seul pour le
+convertir entre les dernières unités utilisées.
+  :scale <unité>       convertir les nombres depuis cette unité
+  :to <unité>          convertir les nombres vers cette unité
+  :precision <n>       afficher n chiffres après la virgule
+  :sig-figs <n|auto>   afficher n chiffres significatifs
+  :rounding <mode>     arrondir en half-even, half-up ou truncate
+  :delta [on|off]      lire les valeurs comme des écarts de température
+  :exact [on|off|notation]  convertir avec des fractions exactes
+  :help                afficher cette aide
+  :quit                quitter (ou Ctrl-D)";
+
+pub fn usage(locale: Locale) -> &'static str {
+    match locale {
+        Locale::German => USAGE_DE,
+        Locale::French => USAGE_FR,
+        Locale::Posix | Locale::English => USAGE_EN,
+    }
+}
+
+/// The message for a failed run. Problems clap found with the arguments,
+/// unit definition files and IO errors are always reported in English.
+pub fn error(error: &CliError, locale: Locale) -> String {
+    match (error, locale) {
+        (_, Locale::Posix | Locale::English) => error.to_string(),
+        (CliError::IncorrectUsage, Locale::German) => "Falsche Verwendung.".to_string(),
+        (CliError::IncorrectUsage, _) => "Utilisation incorrecte.".to_string(),
+        (CliError::UnexpectedArgument(arg), Locale::German) => {
+            format!("Unerwartetes Argument: {}.", arg)
+        }
+        (CliError::UnexpectedArgument(arg), _) => format!("Argument inattendu : {}.", arg),
+        (CliError::Conversion(error), _) => conversion(error, locale),
+        (CliError::Expression(error), _) => expression(error, locale),
+        (CliError::Table(error), _) => table(error, locale),
+        (CliError::Batch(error), _) => batch(error, locale),
+        (CliError::Document(error), _) => document(error, locale),
+        (CliError::Weather(error), _) => weather(error, locale),
+        (CliError::Thermocouple(error), _) => thermocouple(error, locale),
+        (CliError::Rtd(error), _) => rtd(error, locale),
+        (CliError::Color(error), _) => color(error, locale),
+        (CliError::Sensors(error), _) => sensor(error, locale),
+        (CliError::Calibration(error), _) => calibration(error, locale),
+        (CliError::NoSensors(root), Locale::German) => format!(
+            "Keine Temperatursensoren unter {} gefunden.",
+            root.display()
+        ),
+        (CliError::NoSensors(root), _) => format!(
+            "Aucun capteur de température trouvé sous {}.",
+            root.display()
+        ),
+        (CliError::AlertsFired(1), Locale::German) => "1 Alarm ausgelöst.".to_string(),
+        (CliError::AlertsFired(count), Locale::German) => format!("{} Alarme ausgelöst.", count),
+        (CliError::AlertsFired(1), _) => "1 alerte déclenchée.".to_string(),
+        (CliError::AlertsFired(count), _) => format!("{} alertes déclenchées.", count),
+        (CliError::Arguments(_) | CliError::Definitions(_) | CliError::Io(_), _) => {
+            error.to_string()
+        }
+        (CliError::NoScales, Locale::German) => "Noch keine Einheiten zum Umrechnen. Gib einen \
+            Ausdruck wie 72 F to C ein oder nutze :scale und :to."
+            .to_string(),
+        (CliError::NoScales, _) => "Pas encore d'unités à convertir. Saisissez une expression \
+            comme 72 F to C ou utilisez :scale et :to."
+            .to_string(),
+        (CliError::UnknownCommand(command), Locale::German) => format!(
+            "Unbekannter Befehl: :{}. :help listet alle Befehle auf.",
+            command
+        ),
+        (CliError::UnknownCommand(command), _) => format!(
+            "Commande inconnue : :{}. Tapez :help pour la liste.",
+            command
+        ),
+        (CliError::InvalidSetting { command, value }, Locale::German) => {
+            format!("Ungültiger Wert für :{}: {:?}.", command, value)
+        }
+        (CliError::InvalidSetting { command, value }, _) => {
+            format!("Valeur invalide pour :{} : {:?}.", command, value)
+        }
+    }
+}
+
+/// The REPL's `:help` text.
+pub fn repl_help(locale: Locale) -> &'static str {
+    match locale {
+        Locale::German => REPL_HELP_DE,
+        Locale::French => REPL_HELP_FR,
+        Locale::Posix | Locale::English => REPL_HELP_EN,
+    }
+}
+
+/// The warning for a line `batch` skips.
+pub fn skipped_line(line: usize, error: &ConversionError, locale: Locale) -> String {
+    let error = conversion(error, locale);
+    match locale {
+        Locale::German => format!("Zeile {}: {} Wird übersprungen.", line, error),
+        Locale::French => format!("ligne {} : {} Ignorée.", line, error),
+        Locale::Posix | Locale::English => format!("line {}: {} Skipping.", line, error),
+    }
+}
+
+/// The warning for a line `batch` skips.
+pub fn skipped_batch_line(error: &BatchError, locale: Locale) -> String {
+    let error = batch(error, locale);
+    match locale {
+        Locale::German => format!("{} Wird übersprungen.", error),
+        Locale::French => format!("{} Ignorée.", error),
+        Locale::Posix | Locale::English => format!("{} Skipping.", error),
+    }
+}
+
+/// The warning for a JSON value `doc` leaves as it was.
+pub fn skipped_field(path: &str, error: &ConversionError, locale: Locale) -> String {
+    let error = conversion(error, locale);
+    match locale {
+        Locale::German => format!("{}: {} Wird übersprungen.", path, error),
+        Locale::French => format!("{} : {} Ignorée.", path, error),
+        Locale::Posix | Locale::English => format!("{}: {} Skipping.", path, error),
+    }
+}
+
+/// The warning for a sensor `sensors` skips.
+pub fn skipped_reading(error: &SensorError, locale: Locale) -> String {
+    let error = sensor(error, locale);
+    match locale {
+        Locale::German => format!("{} Wird übersprungen.", error),
+        Locale::French => format!("{} Ignoré.", error),
+        Locale::Posix | Locale::English => format!("{} Skipping.", error),
+    }
+}
+
+/// The warning for a report `metar` skips.
+pub fn skipped_report(line: usize, error: &MetarError, locale: Locale) -> String {
+    match (error, locale) {
+        (MetarError::NoTemperature, Locale::German) => format!(
+            "Zeile {}: Keine Temperaturgruppe im Bericht. Wird übersprungen.",
+            line
+        ),
+        (MetarError::NoTemperature, Locale::French) => format!(
+            "ligne {} : Aucun groupe de température dans le bulletin. Ignorée.",
+            line
+        ),
+        (_, Locale::Posix | Locale::English) => format!("line {}: {} Skipping.", line, error),
+    }
+}
+
+/// The warning for a value `watch` skips in a file.
+pub fn skipped_value(path: &Path, error: &ConversionError, locale: Locale) -> String {
+    let error = conversion(error, locale);
+    match locale {
+        Locale::German => format!("{}: {} Wird übersprungen.", path.display(), error),
+        Locale::French => format!("{} : {} Ignorée.", path.display(), error),
+        Locale::Posix | Locale::English => format!("{}: {} Skipping.", path.display(), error),
+    }
+}
+
+/// An alert from `watch`, e.g. "Alert: 81.50 °C is above 80.00 °C."
+pub fn alert(alert: &Alert, number_format: NumberFormat) -> String {
+    let symbol = alert.reading.scale().symbol();
+    let reading = format!("{} {}", number_format.format(alert.reading.value()), symbol);
+    let limit = alert.threshold.limit.to(alert.reading.scale()).value();
+    let limit = format!("{} {}", number_format.format(limit), symbol);
+
+    match (number_format.locale, alert.state, alert.threshold.side) {
+        (Locale::German, AlertState::Triggered, Side::Above) => {
+            format!("Alarm: {} liegt über {}.", reading, limit)
+        }
+        (Locale::German, AlertState::Triggered, Side::Below) => {
+            format!("Alarm: {} liegt unter {}.", reading, limit)
+        }
+        (Locale::German, AlertState::Cleared, Side::Above) => {
+            format!("Aufgehoben: {} liegt nicht mehr über {}.", reading, limit)
+        }
+        (Locale::German, AlertState::Cleared, Side::Below) => {
+            format!("Aufgehoben: {} liegt nicht mehr unter {}.", reading, limit)
+        }
+        (Locale::French, AlertState::Triggered, Side::Above) => {
+            format!("Alerte : {} est au-dessus de {}.", reading, limit)
+        }
+        (Locale::French, AlertState::Triggered, Side::Below) => {
+            format!("Alerte : {} est en dessous de {}.", reading, limit)
+        }
+        (Locale::French, AlertState::Cleared, Side::Above) => {
+            format!("Levée : {} n'est plus au-dessus de {}.", reading, limit)
+        }
+        (Locale::French, AlertState::Cleared, Side::Below) => {
+            format!("Levée : {} n'est plus en dessous de {}.", reading, limit)
+        }
+        (_, AlertState::Triggered, side) => {
+            format!("Alert: {} is {} {}.", reading, side.name(), limit)
+        }
+        (_, AlertState::Cleared, side) => {
+            format!(
+                "Cleared: {} is no longer {} {}.",
+                reading,
+                side.name(),
+                limit
+            )
+        }
+    }
+}
+
+fn conversion(error: &ConversionError, locale: Locale) -> String {
+    let number = |value: f64| locale.localize(&value.to_string());
+
+    match (error, locale) {
+        (_, Locale::Posix | Locale::English) => error.to_string(),
+        (ConversionError::BelowAbsoluteZero { value, scale }, Locale::German) => format!(
+            "{} liegt unter dem absoluten Nullpunkt ({}).",
+            locale.temperature(&number(*value), *scale),
+            locale.temperature(&number(scale.absolute_zero()), *scale)
+        ),
+        (ConversionError::BelowAbsoluteZero { value, scale }, _) => format!(
+            "{} est en dessous du zéro absolu ({}).",
+            locale.temperature(&number(*value), *scale),
+            locale.temperature(&number(scale.absolute_zero()), *scale)
+        ),
+        (ConversionError::UnitBelowAbsoluteZero { value, unit }, Locale::German) => {
+            format!(
+                "{} {} liegt unter dem absoluten Nullpunkt.",
+                number(*value),
+                unit
+            )
+        }
+        (ConversionError::UnitBelowAbsoluteZero { value, unit }, _) => {
+            format!("{} {} est en dessous du zéro absolu.", number(*value), unit)
+        }
+        (ConversionError::NotFinite, Locale::German) => {
+            "Die Temperatur muss eine endliche Zahl sein.".to_string()
+        }
+        (ConversionError::NotFinite, _) => "La température doit être un nombre fini.".to_string(),
+        (ConversionError::InvalidNumber(input), Locale::German) => {
+            format!("Wert konnte nicht gelesen werden: {}.", input)
+        }
+        (ConversionError::InvalidNumber(input), _) => {
+            format!("Impossible de lire la valeur : {}.", input)
+        }
+        (ConversionError::UnknownScale(input), Locale::German) => {
+            format!("Unbekannte Skala: {}.", input)
+        }
+        (ConversionError::UnknownScale(input), _) => format!("Échelle inconnue : {}.", input),
+        (ConversionError::UnknownUnit(input), Locale::German) => {
+            format!("Unbekannte Einheit: {}.", input)
+        }
+        (ConversionError::UnknownUnit(input), _) => format!("Unité inconnue : {}.", input),
+        (ConversionError::MalformedUnit { unit, reason }, Locale::German) => {
+            format!(
+                "Ungültige Einheit {}: {}.",
+                unit,
+                malformed_reason(reason, locale)
+            )
+        }
+        (ConversionError::MalformedUnit { unit, reason }, _) => {
+            format!(
+                "Unité mal formée {} : {}.",
+                unit,
+                malformed_reason(reason, locale)
+            )
+        }
+        (
+            ConversionError::IncompatibleUnits {
+                from,
+                from_dimension,
+                to,
+                to_dimension,
+            },
+            Locale::German,
+        ) => format!(
+            "{} ({}) kann nicht in {} ({}) umgerechnet werden.",
+            from,
+            describe(from_dimension, locale),
+            to,
+            describe(to_dimension, locale)
+        ),
+        (
+            ConversionError::IncompatibleUnits {
+                from,
+                from_dimension,
+                to,
+                to_dimension,
+            },
+            _,
+        ) => format!(
+            "Impossible de convertir {} ({}) en {} ({}).",
+            from,
+            describe(from_dimension, locale),
+            to,
+            describe(to_dimension, locale)
+        ),
+        (ConversionError::ExactOverflow, Locale::German) => {
+            "Der Wert hat zu viele Stellen für eine exakte Umrechnung.".to_string()
+        }
+        (ConversionError::ExactOverflow, _) => {
+            "La valeur a trop de chiffres pour une conversion exacte.".to_string()
+        }
+    }
+}
+
+fn expression(error: &ExpressionError, locale: Locale) -> String {
+    let token = error.token();
+    let message = match (&error.kind, locale) {
+        (_, Locale::Posix | Locale::English) => return error.to_string(),
+        (ExpressionErrorKind::MissingValue, Locale::German) => "Zahl erwartet".to_string(),
+        (ExpressionErrorKind::MissingValue, _) => "Nombre attendu".to_string(),
+        (ExpressionErrorKind::InvalidNumber, Locale::German) => {
+            format!("Ungültige Zahl {:?}", token)
+        }
+        (ExpressionErrorKind::InvalidNumber, _) => format!("Nombre invalide {:?}", token),
+        (ExpressionErrorKind::MissingUnit, Locale::German) => "Einheit erwartet".to_string(),
+        (ExpressionErrorKind::MissingUnit, _) => "Unité attendue".to_string(),
+        (ExpressionErrorKind::UnknownUnit, Locale::German) => {
+            format!("Unbekannte Einheit {:?}", token)
+        }
+        (ExpressionErrorKind::UnknownUnit, _) => format!("Unité inconnue {:?}", token),
+        (ExpressionErrorKind::UnexpectedToken, Locale::German) => {
+            format!("Unerwartetes {:?}", token)
+        }
+        (ExpressionErrorKind::UnexpectedToken, _) => format!("{:?} inattendu", token),
+        (ExpressionErrorKind::Conversion(error), _) => {
+            conversion(error, locale).trim_end_matches('.').to_string()
+        }
+    };
+
+    match locale {
+        Locale::German => format!(
+            "{} bei Spalte {}:\n{}",
+            message,
+            error.column(),
+            error.pointer()
+        ),
+        _ => format!(
+            "{} à la colonne {} :\n{}",
+            message,
+            error.column(),
+            error.pointer()
+        ),
+    }
+}
+
+fn table(error: &TableError, locale: Locale) -> String {
+    let number = |value: f64| locale.localize(&value.to_string());
+
+    match (error, locale) {
+        (_, Locale::Posix | Locale::English) => error.to_string(),
+        (TableError::InvalidStep(step), Locale::German) => format!(
+            "Die Schrittweite muss eine positive Zahl sein, nicht {}.",
+            number(*step)
+        ),
+        (TableError::InvalidStep(step), _) => {
+            format!("Le pas doit être un nombre positif, pas {}.", number(*step))
+        }
+        (TableError::InvalidRange { from, to }, Locale::German) => format!(
+            "Das Ende {} des Bereichs liegt unter seinem Anfang {}.",
+            number(*to),
+            number(*from)
+        ),
+        (TableError::InvalidRange { from, to }, _) => format!(
+            "La fin {} de la plage est inférieure à son début {}.",
+            number(*to),
+            number(*from)
+        ),
+        (TableError::TooManyRows(rows), Locale::German) => format!(
+            "Die Tabelle hätte {} Zeilen; erlaubt sind höchstens {}.",
+            number(*rows),
+            number(MAX_ROWS as f64)
+        ),
+        (TableError::TooManyRows(rows), _) => format!(
+            "Le tableau aurait {} lignes ; {} au maximum sont autorisées.",
+            number(*rows),
+            number(MAX_ROWS as f64)
+        ),
+        (TableError::UnknownFormat(input), Locale::German) => format!(
+            "Unbekanntes Tabellenformat: {}. Erwartet: text, markdown, csv oder json.",
+            input
+        ),
+        (TableError::UnknownFormat(input), _) => format!(
+            "Format de tableau inconnu : {}. Attendu : text, markdown, csv ou json.",
+            input
+        ),
+        (TableError::Conversion(error), _) => conversion(error, locale),
+    }
+}
+
+fn weather(error: &WeatherError, locale: Locale) -> String {
+    let number = |value: f64| locale.localize(&value.to_string());
+
+    match (error, locale) {
+        (_, Locale::Posix | Locale::English) => error.to_string(),
+        (WeatherError::InvalidHumidity(humidity), Locale::German) => format!(
+            "Die relative Luftfeuchte muss zwischen 0 und 100 % liegen, nicht {}.",
+            number(*humidity)
+        ),
+        (WeatherError::InvalidHumidity(humidity), _) => format!(
+            "L'humidité relative doit être comprise entre 0 et 100 %, pas {}.",
+            number(*humidity)
+        ),
+        (WeatherError::InvalidWindSpeed(speed), Locale::German) => format!(
+            "Die Windgeschwindigkeit darf nicht negativ sein, nicht {}.",
+            number(*speed)
+        ),
+        (WeatherError::InvalidWindSpeed(speed), _) => format!(
+            "La vitesse du vent ne peut pas être négative, pas {}.",
+            number(*speed)
+        ),
+        (WeatherError::InvalidPressure(pressure), Locale::German) => format!(
+            "Der Druck muss größer als null sein, nicht {}.",
+            number(*pressure)
+        ),
+        (WeatherError::InvalidPressure(pressure), _) => format!(
+            "La pression doit être supérieure à zéro, pas {}.",
+            number(*pressure)
+        ),
+        (WeatherError::InvalidAltitude(altitude), Locale::German) => format!(
+            "In einer Höhe von {} m hat die Standardatmosphäre keine Luft mehr.",
+            number(*altitude)
+        ),
+        (WeatherError::InvalidAltitude(altitude), _) => format!(
+            "L'atmosphère normalisée n'a plus d'air à une altitude de {} m.",
+            number(*altitude)
+        ),
+        (WeatherError::HeatIndexOutOfRange(temperature), Locale::German) => format!(
+            "Der Hitzeindex wird nur bis 135 °F (57,2 °C) berechnet, nicht {} {}.",
+            number(temperature.value()),
+            temperature.scale().symbol()
+        ),
+        (WeatherError::HeatIndexOutOfRange(temperature), _) => format!(
+            "L'indice de chaleur n'est calculé que jusqu'à 135 °F (57,2 °C), pas {} {}.",
+            number(temperature.value()),
+            temperature.scale().symbol()
+        ),
+        (WeatherError::NoBoilingPoint(celsius), Locale::German) => format!(
+            "Wasser kann bei {} °C nicht sieden, nur zwischen seinem Tripelpunkt (0,01 °C) und kritischen Punkt (373,95 °C).",
+            locale.localize(&format!("{:.2}", celsius))
+        ),
+        (WeatherError::NoBoilingPoint(celsius), _) => format!(
+            "L'eau ne peut pas bouillir à {} °C, seulement entre son point triple (0,01 °C) et son point critique (373,95 °C).",
+            locale.localize(&format!("{:.2}", celsius))
+        ),
+        (WeatherError::Conversion(error), _) => conversion(error, locale),
+    }
+}
+
+fn thermocouple(error: &ThermocoupleError, locale: Locale) -> String {
+    let number = |value: f64| locale.localize(&value.to_string());
+
+    match (error, locale) {
+        (_, Locale::Posix | Locale::English) => error.to_string(),
+        (ThermocoupleError::TemperatureOutOfRange { kind, celsius }, _) => {
+            let (from, to) = kind.temperature_range();
+            let (from, to) = (number(from), number(to));
+            match locale {
+                Locale::German => format!(
+                    "{} °C liegt außerhalb des Bereichs des Thermoelements Typ {} ({} °C bis {} °C).",
+                    number(*celsius),
+                    kind.name(),
+                    from,
+                    to
+                ),
+                _ => format!(
+                    "{} °C est hors de la plage du thermocouple de type {} ({} °C à {} °C).",
+                    number(*celsius),
+                    kind.name(),
+                    from,
+                    to
+                ),
+            }
+        }
+        (ThermocoupleError::VoltageOutOfRange { kind, millivolts }, _) => {
+            let (from, to) = kind.voltage_range();
+            let (from, to) = (number(from), number(to));
+            match locale {
+                Locale::German => format!(
+                    "{} mV liegt außerhalb des Bereichs des Thermoelements Typ {} ({} mV bis {} mV).",
+                    number(*millivolts),
+                    kind.name(),
+                    from,
+                    to
+                ),
+                _ => format!(
+                    "{} mV est hors de la plage du thermocouple de type {} ({} mV à {} mV).",
+                    number(*millivolts),
+                    kind.name(),
+                    from,
+                    to
+                ),
+            }
+        }
+    }
+}
+
+fn rtd(error: &RtdError, locale: Locale) -> String {
+    let number = |value: f64| locale.localize(&value.to_string());
+    let rounded = |value: f64| locale.localize(&format!("{:.2}", value));
+
+    match (error, locale) {
+        (_, Locale::Posix | Locale::English) => error.to_string(),
+        (RtdError::TemperatureOutOfRange(celsius), Locale::German) => format!(
+            "{} °C liegt außerhalb des Bereichs des Widerstandsthermometers (-200 °C bis 850 °C).",
+            number(*celsius)
+        ),
+        (RtdError::TemperatureOutOfRange(celsius), _) => format!(
+            "{} °C est hors de la plage de la sonde à résistance (-200 °C à 850 °C).",
+            number(*celsius)
+        ),
+        (RtdError::ResistanceOutOfRange { ohms, min, max }, Locale::German) => format!(
+            "{} Ω liegt außerhalb des Bereichs des Widerstandsthermometers ({} Ω bis {} Ω).",
+            number(*ohms),
+            rounded(*min),
+            rounded(*max)
+        ),
+        (RtdError::ResistanceOutOfRange { ohms, min, max }, _) => format!(
+            "{} Ω est hors de la plage de la sonde à résistance ({} Ω à {} Ω).",
+            number(*ohms),
+            rounded(*min),
+            rounded(*max)
+        ),
+        (RtdError::Conversion(error), _) => conversion(error, locale),
+    }
+}
+
+fn color(error: &ColorError, locale: Locale) -> String {
+    let number = |value: f64| locale.localize(&value.to_string());
+
+    match (error, locale) {
+        (_, Locale::Posix | Locale::English) => error.to_string(),
+        (ColorError::OutOfRange(kelvin), Locale::German) => format!(
+            "{} K liegt außerhalb des Farbtemperaturbereichs (1000 K bis 40000 K).",
+            number(*kelvin)
+        ),
+        (ColorError::OutOfRange(kelvin), _) => format!(
+            "{} K est hors de la plage de températures de couleur (1000 K à 40000 K).",
+            number(*kelvin)
+        ),
+    }
+}
+
+/// The warning printed when a weather formula is used outside its range.
+pub fn range_warning(warning: RangeWarning, locale: Locale) -> String {
+    let message = match (warning, locale) {
+        (_, Locale::Posix | Locale::English) => warning.to_string(),
+        (RangeWarning::HeatIndexTooCold, Locale::German) => {
+            "Der Hitzeindex ist erst ab 80 °F (26,7 °C) aussagekräftig.".to_string()
+        }
+        (RangeWarning::HeatIndexTooCold, _) => {
+            "L'indice de chaleur n'a de sens qu'à partir de 80 °F (26,7 °C).".to_string()
+        }
+        (RangeWarning::WindChillTooWarm, Locale::German) => {
+            "Der Windchill ist nur bis 50 °F (10 °C) definiert; gezeigt wird die Lufttemperatur."
+                .to_string()
+        }
+        (RangeWarning::WindChillTooWarm, _) => {
+            "Le refroidissement éolien n'est défini que jusqu'à 50 °F (10 °C) ; température de l'air affichée."
+                .to_string()
+        }
+        (RangeWarning::WindChillTooCalm, Locale::German) => {
+            "Der Windchill ist erst ab 3 mph (4,8 km/h) Wind definiert; gezeigt wird die Lufttemperatur."
+                .to_string()
+        }
+        (RangeWarning::WindChillTooCalm, _) => {
+            "Le refroidissement éolien n'est défini qu'à partir de 3 mph (4,8 km/h) de vent ; température de l'air affichée."
+                .to_string()
+        }
+        (RangeWarning::DewPointOutOfRange, Locale::German) => {
+            "Die Taupunktformel ist nur von -45 °C bis 60 °C genau.".to_string()
+        }
+        (RangeWarning::DewPointOutOfRange, _) => {
+            "La formule du point de rosée n'est précise que de -45 °C à 60 °C.".to_string()
+        }
+        (RangeWarning::HumidexTooCold, Locale::German) => {
+            "Der Humidex ist erst ab 20 °C aussagekräftig.".to_string()
+        }
+        (RangeWarning::HumidexTooCold, _) => {
+            "L'humidex n'a de sens qu'à partir de 20 °C.".to_string()
+        }
+        (RangeWarning::AboveTroposphere, Locale::German) => {
+            "Die barometrische Höhenformel ist nur bis 11 km (36.089 ft) genau.".to_string()
+        }
+        (RangeWarning::AboveTroposphere, _) => {
+            "Le nivellement barométrique n'est précis que jusqu'à 11 km (36 089 ft).".to_string()
+        }
+    };
+    match locale {
+        Locale::German => format!("Warnung: {}", message),
+        Locale::French => format!("Avertissement : {}", message),
+        Locale::Posix | Locale::English => format!("Warning: {}", message),
+    }
+}
+
+fn sensor(error: &SensorError, locale: Locale) -> String {
+    match (error, locale) {
+        (_, Locale::Posix | Locale::English) | (SensorError::Io { .. }, _) => error.to_string(),
+        (SensorError::InvalidReading { path, text }, Locale::German) => format!(
+            "{}: {:?} ist keine Temperatur in Milligrad Celsius.",
+            path.display(),
+            text
+        ),
+        (SensorError::InvalidReading { path, text }, _) => format!(
+            "{} : {:?} n'est pas une température en millidegrés Celsius.",
+            path.display(),
+            text
+        ),
+    }
+}
+
+fn calibration(error: &CalibrationError, locale: Locale) -> String {
+    match (error, locale) {
+        (_, Locale::Posix | Locale::English) | (CalibrationError::Io { .. }, _) => {
+            error.to_string()
+        }
+        (CalibrationError::Parse { line, text }, Locale::German) => format!(
+            "Kalibrierzeile {}: Rohwert und Referenzwert erwartet, erhalten: {:?}.",
+            line, text
+        ),
+        (CalibrationError::Parse { line, text }, _) => format!(
+            "Ligne de calibration {} : valeur brute et valeur de référence attendues, reçu {:?}.",
+            line, text
+        ),
+        (CalibrationError::UnknownFit(input), Locale::German) => format!(
+            "Unbekanntes Ausgleichsverfahren: {}. Erwartet: linear, poly<N> oder piecewise.",
+            input
+        ),
+        (CalibrationError::UnknownFit(input), _) => format!(
+            "Ajustement inconnu : {}. Attendu : linear, poly<N> ou piecewise.",
+            input
+        ),
+        (CalibrationError::TooFewPoints { needed, found }, Locale::German) => format!(
+            "Der Ausgleich braucht {} Kalibrierpunkte mit verschiedenen Rohwerten, erhalten: {}.",
+            needed, found
+        ),
+        (CalibrationError::TooFewPoints { needed, found }, _) => format!(
+            "L'ajustement nécessite {} points de calibration aux valeurs brutes distinctes, reçu {}.",
+            needed, found
+        ),
+        (CalibrationError::DuplicateRaw(raw), Locale::German) => format!(
+            "Der Rohwert {} kommt mehrfach vor, was die stückweise Interpolation nicht erlaubt.",
+            raw
+        ),
+        (CalibrationError::DuplicateRaw(raw), _) => format!(
+            "La valeur brute {} apparaît plusieurs fois, ce que l'interpolation par morceaux ne permet pas.",
+            raw
+        ),
+        (CalibrationError::NotTemperature(unit), Locale::German) => format!(
+            "Eine Kalibrierung korrigiert Temperaturen und gilt daher nicht für Werte in {}.",
+            unit
+        ),
+        (CalibrationError::NotTemperature(unit), _) => format!(
+            "Un étalonnage corrige des températures et ne s'applique donc pas aux valeurs en {}.",
+            unit
+        ),
+    }
+}
+
+fn batch(error: &BatchError, locale: Locale) -> String {
+    match (error, locale) {
+        (_, Locale::Posix | Locale::English) | (BatchError::Io(_), _) => error.to_string(),
+        (BatchError::MissingColumn(name), Locale::German) => {
+            format!("Keine Spalte namens {} in der Kopfzeile.", name)
+        }
+        (BatchError::MissingColumn(name), _) => {
+            format!("Aucune colonne nommée {} dans l'en-tête.", name)
+        }
+        (BatchError::Line { line, error }, Locale::German) => {
+            format!("Zeile {}: {}", line, conversion(error, locale))
+        }
+        (BatchError::Line { line, error }, _) => {
+            format!("ligne {} : {}", line, conversion(error, locale))
+        }
+        (BatchError::MissingField { line, column }, Locale::German) => {
+            format!("Zeile {}: Der Zeile fehlt das Feld {}.", line, column)
+        }
+        (BatchError::MissingField { line, column }, _) => {
+            format!("ligne {} : La ligne n'a pas de champ {}.", line, column)
+        }
+    }
+}
+
+fn document(error: &DocumentError, locale: Locale) -> String {
+    match (error, locale) {
+        (_, Locale::Posix | Locale::English) | (DocumentError::Io(_), _) => error.to_string(),
+        (DocumentError::InvalidPath(path), Locale::German) => {
+            format!("Ungültiger JSON-Pfad: {}.", path)
+        }
+        (DocumentError::InvalidPath(path), _) => format!("Chemin JSON invalide : {}.", path),
+        (DocumentError::UnnamedField(path), Locale::German) => format!(
+            "Der Pfad {} muss auf einen Feldnamen enden, um ein Feld umzubenennen oder hinzuzufügen.",
+            path
+        ),
+        (DocumentError::UnnamedField(path), _) => format!(
+            "Le chemin {} doit se terminer par un nom de champ pour renommer ou ajouter un champ.",
+            path
+        ),
+        (DocumentError::MissingColumn(name), Locale::German) => {
+            format!("Keine Spalte namens {} in der Kopfzeile.", name)
+        }
+        (DocumentError::MissingColumn(name), _) => {
+            format!("Aucune colonne nommée {} dans l'en-tête.", name)
+        }
+        (DocumentError::Json { line, column }, Locale::German) => {
+            format!("Ungültiges JSON in Zeile {}, Spalte {}.", line, column)
+        }
+        (DocumentError::Json { line, column }, _) => {
+            format!("JSON invalide à la ligne {}, colonne {}.", line, column)
+        }
+        (DocumentError::Field { path, error }, Locale::German) => {
+            format!("{}: {}", path, conversion(error, locale))
+        }
+        (DocumentError::Field { path, error }, _) => {
+            format!("{} : {}", path, conversion(error, locale))
+        }
+        (DocumentError::Line { line, error }, Locale::German) => {
+            format!("Zeile {}: {}", line, conversion(error, locale))
+        }
+        (DocumentError::Line { line, error }, _) => {
+            format!("ligne {} : {}", line, conversion(error, locale))
+        }
+    }
+}
+
+/// A dimension's translated name, if it has one, followed by its vector.
+fn describe(dimension: &Dimension, locale: Locale) -> String {
+    let name = match (dimension, locale) {
+        (Dimension::Derived(_), _) => return dimension.vector().to_string(),
+        (Dimension::Length, Locale::German) => "Länge",
+        (Dimension::Length, _) => "longueur",
+        (Dimension::Mass, Locale::German) => "Masse",
+        (Dimension::Mass, _) => "masse",
+        (Dimension::Volume, Locale::German) => "Volumen",
+        (Dimension::Volume, _) => "volume",
+        (Dimension::Area, Locale::German) => "Fläche",
+        (Dimension::Area, _) => "aire",
+        (Dimension::Speed, Locale::German) => "Geschwindigkeit",
+        (Dimension::Speed, _) => "vitesse",
+        (Dimension::Pressure, Locale::German) => "Druck",
+        (Dimension::Pressure, _) => "pression",
+        (Dimension::Energy, Locale::German) => "Energie",
+        (Dimension::Energy, _) => "énergie",
+        (Dimension::Force, Locale::German) => "Kraft",
+        (Dimension::Force, _) => "force",
+        (Dimension::Power, Locale::German) => "Leistung",
+        (Dimension::Power, _) => "puissance",
+        (Dimension::Time, Locale::German) => "Zeit",
+        (Dimension::Time, _) => "temps",
+        (Dimension::DataSize, Locale::German) => "Datenmenge",
+        (Dimension::DataSize, _) => "quantité de données",
+        (Dimension::Temperature, Locale::German) => "Temperatur",
+        (Dimension::Temperature, _) => "température",
+    };
+    format!("{}, {}", name, dimension.vector())
+}
+
+/// Translates the reasons `MalformedUnit` gives, falling back to English
+/// for any this table doesn't know.
+fn malformed_reason(reason: &'static str, locale: Locale) -> &'static str {
+    let translations: [(&str, &str, &str); 6] = [
+        (
+            "unclosed parenthesis",
+            "Klammer nicht geschlossen",
+            "parenthèse non fermée",
+        ),
+        (
+            "unmatched closing parenthesis",
+            "schließende Klammer ohne öffnende",
+            "parenthèse fermante en trop",
+        ),
+        (
+            "expected an integer after `^`",
+            "nach `^` wird eine ganze Zahl erwartet",
+            "un entier est attendu après `^`",
+        ),
+        ("expected a unit", "Einheit erwartet", "unité attendue"),
+        (
+            "expected `*` or `/` between units",
+            "zwischen Einheiten wird `*` oder `/` erwartet",
+            "`*` ou `/` attendu entre les unités",
+        ),
+        (
+            "exponent out of range",
+            "Exponent außerhalb des gültigen Bereichs",
+            "exposant hors limites",
+        ),
+    ];
+
+    translations
+        .into_iter()
+        .find(|(english, _, _)| *english == reason)
+        .map_or(reason, |(_, german, french)| match locale {
+            Locale::German => german,
+            Locale::French => french,
+            Locale::Posix | Locale::English => reason,
+        })
+}
@@ -0,0 +1,414 @@
+//! Temperatures from METAR and SYNOP weather reports.
+
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+use crate::{
+    format::NumberFormat,
+    locale::Locale,
+    table::{TableFormat, csv_field, json_string},
+    temperature::{Scale, Temperature},
+};
+
+/// The temperature and dew point from one report, in celsius.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    /// The ICAO code of a METAR's airport or the WMO index number of a
+    /// SYNOP's station, if the report has one.
+    pub station: Option<String>,
+    pub temperature: Temperature,
+    /// Reports may leave the dew point out.
+    pub dew_point: Option<Temperature>,
+    /// Whether the temperature is given to a tenth of a degree, from a
+    /// METAR's `T` remark or a SYNOP, rather than to a whole degree.
+    pub precise: bool,
+}
+
+/// Reasons a report could not be read.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetarError {
+    /// The report has no temperature group.
+    NoTemperature,
+}
+
+impl fmt::Display for MetarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetarError::NoTemperature => f.write_str("No temperature group in the report."),
+        }
+    }
+}
+
+impl std::error::Error for MetarError {}
+
+/// Reads a METAR or, if it has an `AAXX` section, a SYNOP report.
+pub fn parse_report(report: &str) -> Result<Observation, MetarError> {
+    if tokens(report).any(|token| token == "AAXX") {
+        parse_synop(report)
+    } else {
+        parse_metar(report)
+    }
+}
+
+/// Reads the temperature and dew point from a METAR.
+///
+/// The body's `TT/TdTd` group gives whole degrees, with `M` for minus,
+/// e.g. `M05/M12`. When the remarks carry a `TsTTTsTdTdTd` group, e.g.
+/// `T10511122` for -5.1 °C and -12.2 °C, its tenths are used instead.
+pub fn parse_metar(report: &str) -> Result<Observation, MetarError> {
+    let tokens: Vec<&str> = tokens(report).collect();
+    let remarks = tokens
+        .iter()
+        .position(|token| *token == "RMK")
+        .unwrap_or(tokens.len());
+    let (body, remarks) = tokens.split_at(remarks);
+
+    let station = body
+        .iter()
+        .find(|token| !matches!(**token, "METAR" | "SPECI" | "COR"))
+        .filter(|token| {
+            token.len() == 4
+                && token
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        })
+        .map(|token| token.to_string());
+    let whole = body.iter().find_map(|token| temperature_group(token));
+    let precise = remarks.iter().find_map(|token| remark_group(token));
+
+    let (temperature, dew_point, precise) = match (precise, whole) {
+        (Some((temperature, dew_point)), whole) => (
+            temperature,
+            dew_point.or(whole.and_then(|(_, dew_point)| dew_point)),
+            true,
+        ),
+        (None, Some((temperature, dew_point))) => (temperature, dew_point, false),
+        (None, None) => return Err(MetarError::NoTemperature),
+    };
+
+    Ok(Observation {
+        station,
+        temperature: celsius(temperature),
+        dew_point: dew_point.map(celsius),
+        precise,
+    })
+}
+
+/// Reads the temperature and dew point from a land station SYNOP.
+///
+/// Section 1 follows the `AAXX`, date, station, `iRiXhVV` and `Nddff`
+/// groups, and gives the temperature as `1snTTT` and the dew point as
+/// `2snTdTdTd`, in tenths of a degree with a sign digit of 1 for minus.
+pub fn parse_synop(report: &str) -> Result<Observation, MetarError> {
+    let tokens: Vec<&str> = tokens(report).collect();
+    let start = tokens
+        .iter()
+        .position(|token| *token == "AAXX")
+        .ok_or(MetarError::NoTemperature)?;
+    let station = tokens.get(start + 2).map(|token| token.to_string());
+
+    let mut section = tokens.iter().skip(start + 3).peekable();
+    // iRiXhVV, then Nddff, which is followed by 00fff when the wind speed
+    // needs three digits.
+    section.next();
+    if section.next().is_some_and(|wind| wind.ends_with("99")) {
+        section.next_if(|token| token.starts_with("00"));
+    }
+
+    let mut temperature = None;
+    let mut dew_point = None;
+    for group in section.take_while(|group| !matches!(**group, "333" | "444" | "555")) {
+        if group.starts_with("222") {
+            break;
+        }
+        match group.split_at_checked(1) {
+            Some(("1", rest)) => temperature = temperature.or(tenths(rest)),
+            Some(("2", rest)) => dew_point = dew_point.or(tenths(rest)),
+            _ => {}
+        }
+    }
+
+    Ok(Observation {
+        station,
+        temperature: celsius(temperature.ok_or(MetarError::NoTemperature)?),
+        dew_point: dew_point.map(celsius),
+        precise: true,
+    })
+}
+
+/// A report's groups, without the `=` that ends it.
+fn tokens(report: &str) -> impl Iterator<Item = &str> {
+    report
+        .split_whitespace()
+        .map(|token| token.trim_end_matches('='))
+        .filter(|token| !token.is_empty())
+}
+
+/// Reads a METAR body group such as `12/08`, `M05/M12` or `M02/`.
+fn temperature_group(group: &str) -> Option<(f64, Option<f64>)> {
+    let (temperature, dew_point) = group.split_once('/')?;
+    let dew_point = match dew_point {
+        "" | "//" => None,
+        dew_point => Some(whole_degrees(dew_point)?),
+    };
+    Some((whole_degrees(temperature)?, dew_point))
+}
+
+fn whole_degrees(text: &str) -> Option<f64> {
+    let (sign, digits) = match text.strip_prefix('M') {
+        Some(digits) => (-1.0, digits),
+        None => (1.0, text),
+    };
+    if digits.len() != 2 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(sign * digits.parse::<f64>().ok()?)
+}
+
+/// Reads a METAR remark such as `T10511122`, or `T0123` without a dew
+/// point.
+fn remark_group(group: &str) -> Option<(f64, Option<f64>)> {
+    let digits = group.strip_prefix('T')?;
+    match digits.len() {
+        4 => Some((tenths(digits)?, None)),
+        8 => Some((tenths(&digits[..4])?, Some(tenths(&digits[4..])?))),
+        _ => None,
+    }
+}
+
+/// Reads a sign digit (0 for plus, 1 for minus) and three digits of
+/// tenths of a degree, e.g. `1051` for -5.1.
+fn tenths(group: &str) -> Option<f64> {
+    let sign = match group.get(..1)? {
+        "0" => 1.0,
+        "1" => -1.0,
+        _ => return None,
+    };
+    let digits = group.get(1..)?;
+    if digits.len() != 3 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(sign * digits.parse::<f64>().ok()? / 10.0)
+}
+
+fn celsius(value: f64) -> Temperature {
+    Temperature::new(value, Scale::Celsius).expect("report temperatures stay above absolute zero")
+}
+
+/// Writes the observations as a table of stations, temperatures and dew
+/// points in `scale`. As with conversion tables, CSV and JSON always use a
+/// decimal point and no grouping.
+pub fn write_observations<W: Write>(
+    observations: &[Observation],
+    scale: Scale,
+    format: TableFormat,
+    number_format: &NumberFormat,
+    writer: &mut W,
+) -> io::Result<()> {
+    let number_format = match format {
+        TableFormat::Csv | TableFormat::Json => number_format.with_locale(Locale::Posix),
+        TableFormat::Text | TableFormat::Markdown => *number_format,
+    };
+    let format_value =
+        |temperature: Temperature| number_format.format(temperature.to(scale).value());
+    let rows: Vec<(&str, String, Option<String>)> = observations
+        .iter()
+        .map(|observation| {
+            (
+                observation.station.as_deref().unwrap_or_default(),
+                format_value(observation.temperature),
+                observation.dew_point.map(format_value),
+            )
+        })
+        .collect();
+    let headers = [
+        "station".to_string(),
+        format!("T {}", scale.symbol()),
+        format!("Td {}", scale.symbol()),
+    ];
+
+    match format {
+        TableFormat::Text => {
+            let station_width = rows
+                .iter()
+                .map(|(station, _, _)| station.chars().count())
+                .chain([headers[0].len()])
+                .max()
+                .unwrap_or(0);
+            let value_width = rows
+                .iter()
+                .flat_map(|(_, temperature, dew_point)| [Some(temperature), dew_point.as_ref()])
+                .flatten()
+                .map(|value| value.chars().count())
+                .chain(headers[1..].iter().map(|header| header.chars().count()))
+                .max()
+                .unwrap_or(0);
+            let line = |station: &str, temperature: &str, dew_point: &str| {
+                format!(
+                    "{:<station_width$}  {:>value_width$}  {:>value_width$}",
+                    station, temperature, dew_point
+                )
+            };
+            writeln!(writer, "{}", line(&headers[0], &headers[1], &headers[2]))?;
+            for (station, temperature, dew_point) in &rows {
+                let dew_point = dew_point.as_deref().unwrap_or("-");
+                writeln!(writer, "{}", line(station, temperature, dew_point))?;
+            }
+        }
+        TableFormat::Markdown => {
+            writeln!(writer, "| {} |", headers.join(" | "))?;
+            writeln!(writer, "| --- | ---: | ---: |")?;
+            for (station, temperature, dew_point) in &rows {
+                let dew_point = dew_point.as_deref().unwrap_or("-");
+                writeln!(writer, "| {} | {} | {} |", station, temperature, dew_point)?;
+            }
+        }
+        TableFormat::Csv => {
+            writeln!(writer, "station,scale,temperature,dew_point")?;
+            for (station, temperature, dew_point) in &rows {
+                writeln!(
+                    writer,
+                    "{},{},{},{}",
+                    csv_field(station),
+                    scale.name(),
+                    temperature,
+                    dew_point.as_deref().unwrap_or_default()
+                )?;
+            }
+        }
+        TableFormat::Json => {
+            writeln!(writer, "[")?;
+            for (i, ((station, temperature, dew_point), observation)) in
+                rows.iter().zip(observations).enumerate()
+            {
+                let station = match observation.station {
+                    Some(_) => json_string(station),
+                    None => "null".to_string(),
+                };
+                let separator = if i + 1 < rows.len() { "," } else { "" };
+                writeln!(
+                    writer,
+                    "  {{ \"station\": {}, \"scale\": \"{}\", \"temperature\": {}, \"dew_point\": {}, \"precise\": {} }}{}",
+                    station,
+                    scale.name(),
+                    temperature,
+                    dew_point.as_deref().unwrap_or("null"),
+                    observation.precise,
+                    separator
+                )?;
+            }
+            writeln!(writer, "]")?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(observation: &Observation) -> (f64, Option<f64>) {
+        (
+            observation.temperature.value(),
+            observation.dew_point.map(|dew_point| dew_point.value()),
+        )
+    }
+
+    #[test]
+    fn reads_the_body_group_in_whole_degrees() {
+        let observation =
+            parse_metar("METAR EGLL 121650Z 24010KT 9999 SCT030 12/08 Q1015=").unwrap();
+
+        assert_eq!(observation.station.as_deref(), Some("EGLL"));
+        assert_eq!(values(&observation), (12.0, Some(8.0)));
+        assert!(!observation.precise);
+    }
+
+    #[test]
+    fn reads_minus_signs_and_missing_dew_points() {
+        let observation = parse_metar("KJFK 121651Z 31015G25KT 10SM M05/M12 A3012").unwrap();
+        assert_eq!(values(&observation), (-5.0, Some(-12.0)));
+
+        let observation = parse_metar("ENSB 121650Z 09005KT CAVOK M02/ Q1002").unwrap();
+        assert_eq!(values(&observation), (-2.0, None));
+
+        let observation = parse_metar("ENSB 121650Z 09005KT CAVOK M00/// Q1002").unwrap();
+        assert_eq!(values(&observation), (-0.0, None));
+    }
+
+    #[test]
+    fn prefers_the_precise_remark_group() {
+        let observation = parse_metar(
+            "METAR KJFK 121651Z 31015G25KT 1 1/2SM R04R/2600FT M05/M12 A3012 RMK AO2 SLP201 T10511122",
+        )
+        .unwrap();
+
+        assert_eq!(values(&observation), (-5.1, Some(-12.2)));
+        assert!(observation.precise);
+
+        // A remark without a dew point keeps the body's.
+        let observation = parse_metar("KBOS 121654Z 20/15 A2992 RMK AO2 T0203").unwrap();
+        assert_eq!(values(&observation), (20.3, Some(15.0)));
+    }
+
+    #[test]
+    fn rejects_reports_without_a_temperature() {
+        assert_eq!(
+            parse_metar("KJFK 121651Z 31015KT 1/2SM FG"),
+            Err(MetarError::NoTemperature)
+        );
+        assert_eq!(parse_report(""), Err(MetarError::NoTemperature));
+    }
+
+    #[test]
+    fn reads_synop_section_one() {
+        let observation = parse_report(
+            "AAXX 01004 88889 12782 61506 10094 20047 30111 40197 53007 60001 7555/ 333 10119",
+        )
+        .unwrap();
+
+        assert_eq!(observation.station.as_deref(), Some("88889"));
+        assert_eq!(values(&observation), (9.4, Some(4.7)));
+        assert!(observation.precise);
+
+        // A wind speed of 99 knots or more adds a 00fff group, and a sign
+        // digit of 9 means the 2 group holds humidity instead.
+        let observation =
+            parse_synop("AAXX 12121 03772 32970 81299 00105 11021 29085 39950=").unwrap();
+        assert_eq!(values(&observation), (-2.1, None));
+    }
+
+    #[test]
+    fn writes_observations_in_the_chosen_scale() {
+        let observations = vec![
+            parse_metar("KJFK 121651Z M05/M12 RMK T10511122").unwrap(),
+            parse_metar("ENSB 121650Z M02/").unwrap(),
+        ];
+        let render = |format| {
+            let mut buffer = Vec::new();
+            write_observations(
+                &observations,
+                Scale::Fahrenheit,
+                format,
+                &NumberFormat::default(),
+                &mut buffer,
+            )
+            .unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        assert_eq!(
+            render(TableFormat::Text),
+            "station   T °F  Td °F\nKJFK     22.82  10.04\nENSB     28.40      -\n"
+        );
+        assert_eq!(
+            render(TableFormat::Csv),
+            "station,scale,temperature,dew_point\nKJFK,fahrenheit,22.82,10.04\nENSB,fahrenheit,28.40,\n"
+        );
+        assert!(render(TableFormat::Json).contains(
+            "{ \"station\": \"ENSB\", \"scale\": \"fahrenheit\", \"temperature\": 28.40, \"dew_point\": null, \"precise\": false }"
+        ));
+    }
+}
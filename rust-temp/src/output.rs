@@ -0,0 +1,99 @@
+//! How results and errors are written: as sentences for people or as JSON
+//! objects for scripts.
+
+use std::str::FromStr;
+
+use rust_convert::{Locale, Scale, Unit, json_string};
+
+use crate::{CliError, messages};
+
+/// The value of the top-level --format option.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// A sentence such as "32.00 degrees fahrenheit."
+    #[default]
+    Text,
+    /// One JSON object per result, and per error on stderr.
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("Unknown format: {}. Expected text or json.", input)),
+        }
+    }
+}
+
+/// A finished conversion.
+pub struct Report<'a> {
+    pub input_value: f64,
+    pub from: &'a Unit,
+    pub output_value: f64,
+    pub to: &'a Unit,
+    /// The output value as it is shown, rounded or in exact notation.
+    pub text: String,
+    /// Whether the values are differences rather than temperatures.
+    pub delta: bool,
+}
+
+impl Report<'_> {
+    /// The result as a sentence, e.g. "37.78 degrees celsius." or
+    /// "3.11 mi."
+    pub fn sentence(&self, locale: Locale) -> String {
+        match self.to.scale() {
+            Some(scale) if self.delta => format!("{} Δ{}.", self.text, scale.symbol()),
+            Some(scale) => temperature_sentence(&self.text, scale, locale),
+            None => format!("{} {}.", self.text, self.to),
+        }
+    }
+
+    /// The result as a JSON object. Values are written in full; `formatted`
+    /// holds the sentence the text format would print.
+    pub fn to_json(&self, locale: Locale) -> String {
+        format!(
+            "{{\"input_value\": {}, \"input_scale\": {}, \"output_value\": {}, \"output_scale\": {}, \"delta\": {}, \"formatted\": {}}}",
+            self.input_value,
+            json_string(&self.from.name),
+            self.output_value,
+            json_string(&self.to.name),
+            self.delta,
+            json_string(&self.sentence(locale))
+        )
+    }
+}
+
+/// A temperature as a sentence, e.g. "37.78 degrees celsius." or
+/// "310.93 kelvin."
+pub fn temperature_sentence(text: &str, scale: Scale, locale: Locale) -> String {
+    format!("{}.", locale.temperature(text, scale))
+}
+
+pub fn print_report(report: &Report, format: OutputFormat, locale: Locale) {
+    match format {
+        OutputFormat::Text => println!("{}", report.sentence(locale)),
+        OutputFormat::Json => println!("{}", report.to_json(locale)),
+    }
+}
+
+/// Writes an error to stderr, with the usage text for usage errors in the
+/// text format, or as a JSON object with the error's kind and exit code.
+pub fn print_error(error: &CliError, format: OutputFormat, locale: Locale) {
+    let message = messages::error(error, locale);
+    match format {
+        OutputFormat::Text if error.shows_usage() => {
+            eprintln!("{}\n{}", message, messages::usage(locale));
+        }
+        OutputFormat::Text => eprintln!("{}", message),
+        OutputFormat::Json => eprintln!(
+            "{{\"error\": {}, \"message\": {}, \"exit_code\": {}}}",
+            json_string(error.kind()),
+            json_string(&message),
+            error.code()
+        ),
+    }
+}
@@ -0,0 +1,262 @@
+//! The interactive mode started when rust-convert runs on a terminal with
+//! nothing to convert.
+
+use std::{
+    env, fs,
+    io::{self, BufRead, IsTerminal},
+    path::PathBuf,
+};
+
+use rust_convert::{
+    Calibration, ExactNotation, Expression, Locale, NumberFormat, Precision, Quantity, Rounding,
+    Unit, UnitRegistry, parse_localized_expression_with,
+};
+use rustyline::{DefaultEditor, error::ReadlineError};
+
+use crate::{
+    CliError,
+    cli::{SigFigs, parse_precision},
+    messages,
+    output::{OutputFormat, print_error},
+    print_expression,
+};
+
+/// What the user can change while the session runs, along with the units
+/// of the last conversion.
+pub struct Session<'a> {
+    registry: &'a UnitRegistry,
+    number_format: NumberFormat,
+    exact: Option<ExactNotation>,
+    delta: bool,
+    output: OutputFormat,
+    calibration: Option<&'a Calibration>,
+    /// The unit bare numbers are read in.
+    from: Option<Unit>,
+    /// The unit bare numbers are converted to.
+    to: Option<Unit>,
+}
+
+/// Whether to keep reading after a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flow {
+    Continue,
+    Quit,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(
+        registry: &'a UnitRegistry,
+        number_format: NumberFormat,
+        exact: Option<ExactNotation>,
+        delta: bool,
+        output: OutputFormat,
+        calibration: Option<&'a Calibration>,
+    ) -> Self {
+        Session {
+            registry,
+            number_format,
+            exact,
+            delta,
+            output,
+            calibration,
+            from: None,
+            to: None,
+        }
+    }
+
+    fn locale(&self) -> Locale {
+        self.number_format.locale
+    }
+
+    /// The prompt, showing the units bare numbers convert between.
+    fn prompt(&self) -> String {
+        match (&self.from, &self.to) {
+            (Some(from), Some(to)) => format!("{}→{}> ", from.symbol, to.symbol),
+            (Some(from), None) => format!("{}> ", from.symbol),
+            _ => "> ".to_string(),
+        }
+    }
+
+    /// Runs one line of input: a command, a bare number or an expression.
+    fn handle(&mut self, line: &str) -> Result<Flow, CliError> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(Flow::Continue);
+        }
+        if let Some(command) = line.strip_prefix(':') {
+            return self.command(command);
+        }
+
+        let literal = self.locale().delocalize(line);
+        let expression = match literal.parse::<f64>() {
+            Ok(value) => {
+                let (Some(from), Some(to)) = (&self.from, &self.to) else {
+                    return Err(CliError::NoScales);
+                };
+                Expression {
+                    quantity: Quantity::new(value, from.clone()),
+                    literal,
+                    target: to.clone(),
+                    delta: self.delta,
+                }
+            }
+            Err(_) => {
+                let expression = parse_localized_expression_with(
+                    line,
+                    self.registry,
+                    self.locale(),
+                    self.delta,
+                )?;
+                self.from = Some(expression.quantity.unit.clone());
+                self.to = Some(expression.target.clone());
+                expression
+            }
+        };
+
+        print_expression(
+            &expression,
+            self.number_format,
+            self.exact,
+            self.output,
+            self.calibration,
+        )?;
+        Ok(Flow::Continue)
+    }
+
+    fn command(&mut self, input: &str) -> Result<Flow, CliError> {
+        let (name, argument) = match input.trim().split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (input.trim(), ""),
+        };
+        let invalid = || CliError::InvalidSetting {
+            command: name.to_string(),
+            value: argument.to_string(),
+        };
+
+        match name {
+            "q" | "quit" | "exit" => return Ok(Flow::Quit),
+            "help" | "h" | "?" => println!("{}", messages::repl_help(self.locale())),
+            "scale" | "from" => {
+                let from = self.registry.parse_unit(argument)?;
+                // Keep the target if it still fits, otherwise pick the
+                // scale's counterpart.
+                if self
+                    .to
+                    .as_ref()
+                    .is_none_or(|to| to.dimension != from.dimension)
+                {
+                    self.to = from
+                        .scale()
+                        .and_then(|scale| self.registry.for_scale(scale.counterpart()))
+                        .cloned();
+                }
+                self.from = Some(from);
+            }
+            "to" => self.to = Some(self.registry.parse_unit(argument)?),
+            "precision" => {
+                let decimals = parse_precision(argument).map_err(|_| invalid())?;
+                self.number_format.precision = Precision::Decimals(decimals);
+                self.exact = None;
+            }
+            "sig-figs" | "sigfigs" => {
+                self.number_format.precision = match argument.parse() {
+                    Ok(SigFigs::Count(count)) => Precision::SignificantFigures(count),
+                    Ok(SigFigs::Auto) => Precision::MatchInput,
+                    Err(_) => return Err(invalid()),
+                };
+                self.exact = None;
+            }
+            "rounding" => {
+                self.number_format.rounding =
+                    argument.parse::<Rounding>().map_err(|_| invalid())?;
+            }
+            "delta" => self.delta = switch(argument, self.delta).ok_or_else(invalid)?,
+            // Calibrated values have no exact form.
+            "exact" if self.calibration.is_some() => return Err(invalid()),
+            "exact" => {
+                self.exact = match switch(argument, self.exact.is_some()) {
+                    Some(true) => Some(self.exact.unwrap_or_default()),
+                    Some(false) => None,
+                    None => Some(argument.parse().map_err(|_| invalid())?),
+                };
+            }
+            _ => return Err(CliError::UnknownCommand(input.trim().to_string())),
+        }
+        Ok(Flow::Continue)
+    }
+}
+
+/// Reads an on/off argument, toggling `current` when there is none.
+fn switch(argument: &str, current: bool) -> Option<bool> {
+    match argument.to_lowercase().as_str() {
+        "" => Some(!current),
+        "on" | "yes" | "true" => Some(true),
+        "off" | "no" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Runs the session until the input ends or the user quits. On a terminal
+/// lines are read with editing and history, which is kept between runs;
+/// otherwise they are read from stdin as they come.
+pub fn run(mut session: Session) -> Result<(), CliError> {
+    if !io::stdin().is_terminal() {
+        for line in io::stdin().lock().lines() {
+            if step(&mut session, &line?) == Flow::Quit {
+                break;
+            }
+        }
+        return Ok(());
+    }
+
+    let mut editor = DefaultEditor::new().map_err(io::Error::other)?;
+    let history = history_path();
+    if let Some(path) = &history {
+        // A missing history file just means this is the first session.
+        let _ = editor.load_history(path);
+    }
+
+    loop {
+        match editor.readline(&session.prompt()) {
+            Ok(line) => {
+                let _ = editor.add_history_entry(line.as_str());
+                if step(&mut session, &line) == Flow::Quit {
+                    break;
+                }
+            }
+            // Ctrl-C abandons the line being typed, Ctrl-D ends the session.
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(error) => return Err(io::Error::other(error).into()),
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = editor.save_history(path);
+    }
+    Ok(())
+}
+
+/// Handles a line, reporting any error without ending the session.
+fn step(session: &mut Session, line: &str) -> Flow {
+    session.handle(line).unwrap_or_else(|error| {
+        print_error(&error, session.output, session.locale());
+        Flow::Continue
+    })
+}
+
+/// Where the REPL keeps its history: `$XDG_STATE_HOME/rust-convert/history`,
+/// falling back to `~/.local/state/rust-convert/history`.
+fn history_path() -> Option<PathBuf> {
+    let state_home = env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })?;
+
+    Some(state_home.join("rust-convert").join("history"))
+}
@@ -0,0 +1,248 @@
+use std::fmt;
+
+use crate::{
+    error::ConversionError,
+    temperature::{Scale, Temperature},
+};
+
+/// The temperatures, in °C, the Callendar–Van Dusen equation is defined for
+/// by IEC 60751.
+const RANGE: (f64, f64) = (-200.0, 850.0);
+
+/// How far, in ohms, a reading may fall outside the range's resistances and
+/// still count as its endpoint. The range is reported to hundredths of an
+/// ohm, so a reading of an endpoint as printed is accepted.
+const RESISTANCE_TOLERANCE: f64 = 0.005;
+
+/// A platinum resistance thermometer described by the Callendar–Van Dusen
+/// equation:
+///
+/// R(t) = R0 (1 + A t + B t² + C (t - 100) t³)
+///
+/// with t in °C and C only used below 0 °C.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rtd {
+    /// Resistance at 0 °C, in ohms.
+    pub r0: f64,
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+impl Rtd {
+    /// An industrial platinum RTD with the IEC 60751 coefficients and
+    /// resistance `r0` at 0 °C.
+    pub fn iec_60751(r0: f64) -> Self {
+        Rtd {
+            r0,
+            a: 3.9083e-3,
+            b: -5.775e-7,
+            c: -4.183e-12,
+        }
+    }
+
+    /// A Pt100: 100 Ω at 0 °C.
+    pub fn pt100() -> Self {
+        Rtd::iec_60751(100.0)
+    }
+
+    /// A Pt1000: 1000 Ω at 0 °C.
+    pub fn pt1000() -> Self {
+        Rtd::iec_60751(1000.0)
+    }
+
+    /// The resistance, in ohms, at `temperature`.
+    pub fn resistance(&self, temperature: Temperature) -> Result<f64, RtdError> {
+        let t = temperature.to(Scale::Celsius).value();
+        if !(RANGE.0..=RANGE.1).contains(&t) {
+            return Err(RtdError::TemperatureOutOfRange(t));
+        }
+
+        Ok(self.resistance_at(t))
+    }
+
+    /// The temperature at which the RTD has a resistance of `ohms`. Readings
+    /// within half a hundredth of an ohm of the range's ends count as the ends.
+    ///
+    /// From 0 °C up the equation is a quadratic and is solved directly;
+    /// below it, Newton's method refines the quadratic's answer.
+    pub fn temperature(&self, ohms: f64) -> Result<Temperature, RtdError> {
+        let (low, high) = (self.resistance_at(RANGE.0), self.resistance_at(RANGE.1));
+        if !(low - RESISTANCE_TOLERANCE..=high + RESISTANCE_TOLERANCE).contains(&ohms) {
+            return Err(RtdError::ResistanceOutOfRange {
+                ohms,
+                min: low,
+                max: high,
+            });
+        }
+        let ohms = ohms.clamp(low, high);
+
+        let ratio = ohms / self.r0;
+        let mut t =
+            (-self.a + (self.a * self.a - 4.0 * self.b * (1.0 - ratio)).sqrt()) / (2.0 * self.b);
+        if t < 0.0 {
+            for _ in 0..10 {
+                let error = self.resistance_at(t) - ohms;
+                let slope =
+                    self.r0 * (self.a + 2.0 * self.b * t + self.c * (4.0 * t - 300.0) * t * t);
+                t -= error / slope;
+                if error.abs() < 1e-12 * self.r0 {
+                    break;
+                }
+            }
+        }
+
+        Ok(Temperature::new(t, Scale::Celsius)?)
+    }
+
+    fn resistance_at(&self, t: f64) -> f64 {
+        let c = if t < 0.0 { self.c } else { 0.0 };
+        self.r0 * (1.0 + self.a * t + self.b * t * t + c * (t - 100.0) * t * t * t)
+    }
+}
+
+impl Default for Rtd {
+    fn default() -> Self {
+        Rtd::pt100()
+    }
+}
+
+/// Reasons an RTD reading could not be converted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RtdError {
+    /// The temperature, in °C, is outside -200 °C to 850 °C.
+    TemperatureOutOfRange(f64),
+    /// The resistance is outside what the RTD has from -200 °C to 850 °C.
+    ResistanceOutOfRange { ohms: f64, min: f64, max: f64 },
+    /// The RTD's coefficients don't give a temperature, as with an `r0` of
+    /// zero.
+    Conversion(ConversionError),
+}
+
+impl fmt::Display for RtdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RtdError::TemperatureOutOfRange(celsius) => write!(
+                f,
+                "{} °C is outside the RTD range of {} °C to {} °C.",
+                celsius, RANGE.0, RANGE.1
+            ),
+            RtdError::ResistanceOutOfRange { ohms, min, max } => write!(
+                f,
+                "{} Ω is outside the RTD range of {:.2} Ω to {:.2} Ω.",
+                ohms, min, max
+            ),
+            RtdError::Conversion(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for RtdError {}
+
+impl From<ConversionError> for RtdError {
+    fn from(error: ConversionError) -> Self {
+        RtdError::Conversion(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn celsius(value: f64) -> Temperature {
+        Temperature::new(value, Scale::Celsius).expect("temperature should be valid")
+    }
+
+    // Values from the IEC 60751 Pt100 table, in °C and Ω.
+    const PT100_TABLE: [(f64, f64); 8] = [
+        (-190.0, 22.83),
+        (-100.0, 60.26),
+        (-40.0, 84.27),
+        (0.0, 100.00),
+        (25.0, 109.73),
+        (100.0, 138.51),
+        (400.0, 247.09),
+        (850.0, 390.48),
+    ];
+
+    #[test]
+    fn resistances_match_the_pt100_table() {
+        let rtd = Rtd::pt100();
+        for (t, ohms) in PT100_TABLE {
+            let resistance = rtd.resistance(celsius(t)).unwrap();
+            assert!(
+                (resistance - ohms).abs() <= 0.005,
+                "{} °C: {} Ω",
+                t,
+                resistance
+            );
+        }
+    }
+
+    #[test]
+    fn temperatures_match_the_pt100_table() {
+        let rtd = Rtd::pt100();
+        for (t, ohms) in PT100_TABLE {
+            let temperature = rtd.temperature(ohms).unwrap();
+            assert!(
+                (temperature.value() - t).abs() <= 0.02,
+                "{} Ω: {} °C",
+                ohms,
+                temperature.value()
+            );
+        }
+    }
+
+    #[test]
+    fn inverts_its_own_resistances() {
+        let rtd = Rtd::pt1000();
+        for t in [-195.5, -73.2, -0.5, 0.0, 0.5, 321.7] {
+            let ohms = rtd.resistance(celsius(t)).unwrap();
+            assert!((rtd.temperature(ohms).unwrap().value() - t).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn accepts_temperatures_on_any_scale() {
+        let boiling = Temperature::new(212.0, Scale::Fahrenheit).unwrap();
+        let resistance = Rtd::pt100().resistance(boiling).unwrap();
+        assert!((resistance - 138.51).abs() <= 0.005);
+    }
+
+    #[test]
+    fn rejects_readings_outside_the_range() {
+        let rtd = Rtd::pt100();
+        assert_eq!(
+            rtd.resistance(celsius(900.0)),
+            Err(RtdError::TemperatureOutOfRange(900.0))
+        );
+        assert!(matches!(
+            rtd.temperature(10.0),
+            Err(RtdError::ResistanceOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn reports_coefficients_without_a_temperature() {
+        assert_eq!(
+            Rtd::iec_60751(0.0).temperature(0.0),
+            Err(RtdError::Conversion(ConversionError::NotFinite))
+        );
+        for r0 in [f64::INFINITY, f64::NAN] {
+            assert!(Rtd::iec_60751(r0).temperature(100.0).is_err(), "{}", r0);
+        }
+    }
+
+    #[test]
+    fn accepts_the_range_ends_as_printed() {
+        let rtd = Rtd::pt100();
+        assert_eq!(rtd.temperature(18.52).unwrap(), celsius(-200.0));
+        assert!((rtd.temperature(390.48).unwrap().value() - 850.0).abs() < 0.01);
+        for ohms in [18.51, 390.49] {
+            assert!(matches!(
+                rtd.temperature(ohms),
+                Err(RtdError::ResistanceOutOfRange { .. })
+            ));
+        }
+    }
+}
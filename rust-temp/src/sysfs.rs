@@ -0,0 +1,412 @@
+//! Temperature readings from the Linux thermal and hwmon sysfs interfaces.
+
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    format::NumberFormat,
+    locale::Locale,
+    table::{TableFormat, csv_field, json_string},
+    temperature::{Scale, Temperature},
+};
+
+/// Where sysfs is normally mounted.
+pub const DEFAULT_SYSFS_ROOT: &str = "/sys";
+
+/// One temperature sensor's current reading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorReading {
+    /// The sensor's name: a thermal zone's type, or a hwmon chip's name
+    /// followed by the sensor's label.
+    pub label: String,
+    /// The file the reading came from.
+    pub path: PathBuf,
+    pub temperature: Temperature,
+}
+
+/// Reasons sensors could not be read.
+#[derive(Debug)]
+pub enum SensorError {
+    /// A sensor directory or file could not be read.
+    Io { path: PathBuf, error: io::Error },
+    /// A file held something other than a temperature in millidegrees
+    /// celsius.
+    InvalidReading { path: PathBuf, text: String },
+}
+
+impl fmt::Display for SensorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SensorError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            SensorError::InvalidReading { path, text } => write!(
+                f,
+                "{}: {:?} is not a temperature in millidegrees celsius.",
+                path.display(),
+                text
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SensorError {}
+
+/// Reads every `class/thermal/thermal_zone*/temp` and
+/// `class/hwmon/*/temp*_input` file under the sysfs mounted at `root`,
+/// thermal zones first, each in numeric order.
+///
+/// A missing `thermal` or `hwmon` class just has no sensors. Files that
+/// can't be read or don't hold a temperature, which some drivers produce
+/// for sensors that are switched off, are passed to `on_bad_reading` and
+/// skipped.
+pub fn read_sensors<F>(
+    root: &Path,
+    mut on_bad_reading: F,
+) -> Result<Vec<SensorReading>, SensorError>
+where
+    F: FnMut(&SensorError),
+{
+    let mut readings = Vec::new();
+    let mut read = |label: String, path: PathBuf| match read_sensor(&path) {
+        Ok(temperature) => readings.push(SensorReading {
+            label,
+            path,
+            temperature,
+        }),
+        Err(error) => on_bad_reading(&error),
+    };
+
+    for zone in entries(&root.join("class").join("thermal"), "thermal_zone")? {
+        let path = zone.join("temp");
+        if !path.is_file() {
+            continue;
+        }
+        let label = read_label(&zone.join("type")).unwrap_or_else(|| file_name(&zone));
+        read(label, path);
+    }
+
+    for chip in entries(&root.join("class").join("hwmon"), "")? {
+        let name = read_label(&chip.join("name")).unwrap_or_else(|| file_name(&chip));
+        for input in entries(&chip, "temp")? {
+            let file = file_name(&input);
+            let Some(sensor) = file.strip_suffix("_input") else {
+                continue;
+            };
+            let label = read_label(&chip.join(format!("{}_label", sensor)))
+                .unwrap_or_else(|| sensor.to_string());
+            read(format!("{} {}", name, label), input);
+        }
+    }
+
+    Ok(readings)
+}
+
+/// The entries of `dir` whose names start with `prefix`, sorted so that
+/// numbered names like `thermal_zone10` come after `thermal_zone9`.
+fn entries(dir: &Path, prefix: &str) -> Result<Vec<PathBuf>, SensorError> {
+    let io_error = |error| SensorError::Io {
+        path: dir.to_path_buf(),
+        error,
+    };
+    let listing = match fs::read_dir(dir) {
+        Ok(listing) => listing,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(io_error(error)),
+    };
+
+    let mut paths = Vec::new();
+    for entry in listing {
+        let path = entry.map_err(io_error)?.path();
+        if file_name(&path).starts_with(prefix) {
+            paths.push(path);
+        }
+    }
+    paths.sort_by_cached_key(|path| natural_key(&file_name(path)));
+    Ok(paths)
+}
+
+/// Splits a name at its first run of digits, so it sorts by the number
+/// those digits spell rather than character by character.
+fn natural_key(name: &str) -> (String, u64, String) {
+    let start = name
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(name.len());
+    let digits = name[start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(name.len(), |end| start + end);
+    (
+        name[..start].to_string(),
+        name[start..digits].parse().unwrap_or(0),
+        name[digits..].to_string(),
+    )
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// A name or label file's contents, if it exists and isn't blank.
+fn read_label(path: &Path) -> Option<String> {
+    let label = fs::read_to_string(path).ok()?;
+    let label = label.trim();
+    (!label.is_empty()).then(|| label.to_string())
+}
+
+/// Reads one sysfs temperature file, which holds an integer in
+/// millidegrees celsius.
+pub fn read_sensor(path: &Path) -> Result<Temperature, SensorError> {
+    let text = fs::read_to_string(path).map_err(|error| SensorError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    let invalid = || SensorError::InvalidReading {
+        path: path.to_path_buf(),
+        text: text.trim().to_string(),
+    };
+    let millidegrees: i64 = text.trim().parse().map_err(|_| invalid())?;
+    Temperature::new(millidegrees as f64 / 1000.0, Scale::Celsius).map_err(|_| invalid())
+}
+
+/// Writes the readings as a two-column table of sensor names and
+/// temperatures in `scale`. As with conversion tables, CSV and JSON always
+/// use a decimal point and no grouping.
+pub fn write_readings<W: Write>(
+    readings: &[SensorReading],
+    scale: Scale,
+    format: TableFormat,
+    number_format: &NumberFormat,
+    writer: &mut W,
+) -> io::Result<()> {
+    let number_format = match format {
+        TableFormat::Csv | TableFormat::Json => number_format.with_locale(Locale::Posix),
+        TableFormat::Text | TableFormat::Markdown => *number_format,
+    };
+    let values: Vec<String> = readings
+        .iter()
+        .map(|reading| number_format.format(reading.temperature.to(scale).value()))
+        .collect();
+
+    match format {
+        TableFormat::Text => {
+            let label_width = readings
+                .iter()
+                .map(|reading| reading.label.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max("sensor".len());
+            let value_width = values
+                .iter()
+                .map(|value| value.chars().count())
+                .chain([scale.symbol().chars().count()])
+                .max()
+                .unwrap_or(0);
+            writeln!(
+                writer,
+                "{:<label_width$}  {:>value_width$}",
+                "sensor",
+                scale.symbol()
+            )?;
+            for (reading, value) in readings.iter().zip(&values) {
+                writeln!(
+                    writer,
+                    "{:<label_width$}  {:>value_width$}",
+                    reading.label, value
+                )?;
+            }
+        }
+        TableFormat::Markdown => {
+            writeln!(writer, "| sensor | {} |", scale.symbol())?;
+            writeln!(writer, "| --- | ---: |")?;
+            for (reading, value) in readings.iter().zip(&values) {
+                writeln!(
+                    writer,
+                    "| {} | {} |",
+                    reading.label.replace('|', "\\|"),
+                    value
+                )?;
+            }
+        }
+        TableFormat::Csv => {
+            writeln!(writer, "sensor,path,{}", scale.name())?;
+            for (reading, value) in readings.iter().zip(&values) {
+                writeln!(
+                    writer,
+                    "{},{},{}",
+                    csv_field(&reading.label),
+                    csv_field(&reading.path.to_string_lossy()),
+                    value
+                )?;
+            }
+        }
+        TableFormat::Json => {
+            writeln!(writer, "[")?;
+            for (i, (reading, value)) in readings.iter().zip(&values).enumerate() {
+                let separator = if i + 1 < readings.len() { "," } else { "" };
+                writeln!(
+                    writer,
+                    "  {{ \"sensor\": {}, \"path\": {}, \"{}\": {} }}{}",
+                    json_string(&reading.label),
+                    json_string(&reading.path.to_string_lossy()),
+                    scale.name(),
+                    value,
+                    separator
+                )?;
+            }
+            writeln!(writer, "]")?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A throwaway sysfs tree, removed when dropped.
+    struct FakeSysfs(PathBuf);
+
+    impl FakeSysfs {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!(
+                "rust-convert-sysfs-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&root);
+            FakeSysfs(root)
+        }
+
+        fn file(&self, path: &str, contents: &str) -> &Self {
+            let path = self.0.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+            self
+        }
+    }
+
+    impl Drop for FakeSysfs {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn labels_and_values(readings: &[SensorReading]) -> Vec<(&str, f64)> {
+        readings
+            .iter()
+            .map(|reading| (reading.label.as_str(), reading.temperature.value()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_thermal_zones_and_hwmon_sensors() {
+        let sysfs = FakeSysfs::new("both");
+        sysfs
+            .file("class/thermal/thermal_zone0/type", "acpitz\n")
+            .file("class/thermal/thermal_zone0/temp", "27800\n")
+            .file("class/thermal/cooling_device0/type", "Processor\n")
+            .file("class/hwmon/hwmon1/name", "coretemp\n")
+            .file("class/hwmon/hwmon1/temp1_input", "45000\n")
+            .file("class/hwmon/hwmon1/temp1_label", "Package id 0\n")
+            .file("class/hwmon/hwmon1/temp2_input", "-5500\n")
+            .file("class/hwmon/hwmon1/temp2_max", "100000\n")
+            .file("class/hwmon/hwmon1/in0_input", "1200\n");
+
+        let readings = read_sensors(&sysfs.0, |error| panic!("{}", error)).unwrap();
+
+        assert_eq!(
+            labels_and_values(&readings),
+            vec![
+                ("acpitz", 27.8),
+                ("coretemp Package id 0", 45.0),
+                ("coretemp temp2", -5.5),
+            ]
+        );
+        assert_eq!(
+            readings[0].path,
+            sysfs.0.join("class/thermal/thermal_zone0/temp")
+        );
+    }
+
+    #[test]
+    fn sorts_numbered_sensors_by_number() {
+        let sysfs = FakeSysfs::new("order");
+        for zone in [10, 2, 1] {
+            sysfs.file(
+                &format!("class/thermal/thermal_zone{}/temp", zone),
+                &format!("{}000", zone),
+            );
+        }
+
+        let readings = read_sensors(&sysfs.0, |_| {}).unwrap();
+
+        assert_eq!(
+            labels_and_values(&readings),
+            vec![
+                ("thermal_zone1", 1.0),
+                ("thermal_zone2", 2.0),
+                ("thermal_zone10", 10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_bad_readings() {
+        let sysfs = FakeSysfs::new("bad");
+        sysfs
+            .file("class/thermal/thermal_zone0/temp", "not a number\n")
+            .file("class/thermal/thermal_zone1/temp", "-300000\n")
+            .file("class/thermal/thermal_zone2/temp", "31000\n");
+
+        let mut bad = Vec::new();
+        let readings = read_sensors(&sysfs.0, |error| bad.push(error.to_string())).unwrap();
+
+        assert_eq!(labels_and_values(&readings), vec![("thermal_zone2", 31.0)]);
+        assert_eq!(bad.len(), 2);
+        assert!(bad[0].contains("\"not a number\""));
+    }
+
+    #[test]
+    fn finds_nothing_without_sysfs() {
+        let sysfs = FakeSysfs::new("empty");
+        let readings = read_sensors(&sysfs.0, |_| {}).unwrap();
+        assert!(readings.is_empty());
+    }
+
+    #[test]
+    fn writes_readings_in_the_chosen_scale() {
+        let readings = vec![SensorReading {
+            label: "acpitz, zone 0".to_string(),
+            path: PathBuf::from("/sys/class/thermal/thermal_zone0/temp"),
+            temperature: Temperature::new(25.0, Scale::Celsius).unwrap(),
+        }];
+        let render = |format| {
+            let mut buffer = Vec::new();
+            write_readings(
+                &readings,
+                Scale::Fahrenheit,
+                format,
+                &NumberFormat::default(),
+                &mut buffer,
+            )
+            .unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        assert_eq!(
+            render(TableFormat::Text),
+            "sensor             °F\nacpitz, zone 0  77.00\n"
+        );
+        assert_eq!(
+            render(TableFormat::Csv),
+            "sensor,path,fahrenheit\n\"acpitz, zone 0\",/sys/class/thermal/thermal_zone0/temp,77.00\n"
+        );
+        assert_eq!(
+            render(TableFormat::Json),
+            "[\n  { \"sensor\": \"acpitz, zone 0\", \"path\": \"/sys/class/thermal/thermal_zone0/temp\", \"fahrenheit\": 77.00 }\n]\n"
+        );
+    }
+}
@@ -0,0 +1,518 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+};
+
+use crate::{
+    error::ConversionError,
+    format::NumberFormat,
+    locale::Locale,
+    temperature::Scale,
+    units::{Unit, UnitRegistry, convert_between},
+};
+
+/// The most rows a table may have, so a tiny step over a wide range can't
+/// exhaust memory.
+pub const MAX_ROWS: usize = 10_000;
+
+/// Output formats a conversion table can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Text,
+    Markdown,
+    Csv,
+    Json,
+}
+
+impl FromStr for TableFormat {
+    type Err = TableError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "text" | "txt" => Ok(TableFormat::Text),
+            "markdown" | "md" => Ok(TableFormat::Markdown),
+            "csv" => Ok(TableFormat::Csv),
+            "json" => Ok(TableFormat::Json),
+            _ => Err(TableError::UnknownFormat(input.to_string())),
+        }
+    }
+}
+
+/// Reasons a conversion table could not be built.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableError {
+    /// The step between rows must be a positive, finite number.
+    InvalidStep(f64),
+    /// The range end is lower than its start.
+    InvalidRange { from: f64, to: f64 },
+    /// The range and step would need this many rows, more than [`MAX_ROWS`].
+    TooManyRows(f64),
+    /// The requested output format is not supported.
+    UnknownFormat(String),
+    /// One of the rows is not a valid temperature.
+    Conversion(ConversionError),
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::InvalidStep(step) => {
+                write!(f, "Step must be a positive number, got {}.", step)
+            }
+            TableError::InvalidRange { from, to } => {
+                write!(f, "Range end {} is lower than its start {}.", to, from)
+            }
+            TableError::TooManyRows(rows) => write!(
+                f,
+                "The table would have {} rows; at most {} are allowed.",
+                rows, MAX_ROWS
+            ),
+            TableError::UnknownFormat(input) => write!(
+                f,
+                "Unknown table format: {}. Expected text, markdown, csv or json.",
+                input
+            ),
+            TableError::Conversion(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+impl From<ConversionError> for TableError {
+    fn from(error: ConversionError) -> Self {
+        TableError::Conversion(error)
+    }
+}
+
+/// A chart of one range of values expressed in every unit of its dimension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionTable {
+    units: Vec<Unit>,
+    rows: Vec<Vec<f64>>,
+}
+
+impl ConversionTable {
+    /// Builds a temperature table from `from` to `to` (inclusive) in steps of
+    /// `step`, with `scale` as the first column followed by every other scale
+    /// in use today. Historical scales only appear as the source column.
+    pub fn new(from: f64, to: f64, step: f64, scale: Scale) -> Result<Self, TableError> {
+        let registry = UnitRegistry::builtin();
+        let unit = registry
+            .for_scale(scale)
+            .expect("the built-in registry has every scale");
+        ConversionTable::for_unit(from, to, step, unit, registry)
+    }
+
+    /// Like [`ConversionTable::new`], but for any unit: the columns are `unit`
+    /// followed by every other unit in `registry` with the same dimension,
+    /// apart from historical temperature scales.
+    pub fn for_unit(
+        from: f64,
+        to: f64,
+        step: f64,
+        unit: &Unit,
+        registry: &UnitRegistry,
+    ) -> Result<Self, TableError> {
+        if !step.is_finite() || step <= 0.0 {
+            return Err(TableError::InvalidStep(step));
+        }
+        if to < from {
+            return Err(TableError::InvalidRange { from, to });
+        }
+
+        let mut units = vec![unit.clone()];
+        units.extend(
+            registry
+                .units()
+                .iter()
+                .filter(|other| {
+                    other.dimension == unit.dimension
+                        && other.symbol != unit.symbol
+                        && !other.scale().is_some_and(|scale| scale.is_historical())
+                })
+                .cloned(),
+        );
+
+        // Step by index rather than accumulating so rows don't drift, and
+        // allow a little slack so the end of the range is included.
+        let count = ((to - from) / step + 1e-9).floor() + 1.0;
+        if count.is_nan() || count > MAX_ROWS as f64 {
+            return Err(TableError::TooManyRows(count));
+        }
+        let count = count as usize;
+        let rows = (0..count)
+            .map(|i| {
+                let value = from + i as f64 * step;
+                units
+                    .iter()
+                    .map(|target| convert_between(value, unit, target))
+                    .collect()
+            })
+            .collect::<Result<Vec<_>, ConversionError>>()?;
+
+        Ok(ConversionTable { units, rows })
+    }
+
+    pub fn units(&self) -> &[Unit] {
+        &self.units
+    }
+
+    pub fn rows(&self) -> &[Vec<f64>] {
+        &self.rows
+    }
+
+    /// Writes the table in the requested format, with values to two
+    /// decimal places.
+    pub fn write<W: Write>(&self, format: TableFormat, writer: &mut W) -> io::Result<()> {
+        self.write_with(format, &NumberFormat::default(), writer)
+    }
+
+    /// Writes the table in the requested format, with values written by
+    /// `number_format`. CSV and JSON always use a decimal point and no
+    /// grouping, whatever the format's locale, so they stay machine-readable.
+    pub fn write_with<W: Write>(
+        &self,
+        format: TableFormat,
+        number_format: &NumberFormat,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        let cells = match format {
+            TableFormat::Csv | TableFormat::Json => {
+                self.formatted_rows(&number_format.with_locale(Locale::Posix))
+            }
+            TableFormat::Text | TableFormat::Markdown => self.formatted_rows(number_format),
+        };
+        match format {
+            TableFormat::Text => self.write_text(&cells, writer),
+            TableFormat::Markdown => self.write_markdown(&cells, writer),
+            TableFormat::Csv => self.write_csv(&cells, writer),
+            TableFormat::Json => self.write_json(&cells, writer),
+        }
+    }
+
+    fn write_text<W: Write>(&self, cells: &[Vec<String>], writer: &mut W) -> io::Result<()> {
+        let width = cells
+            .iter()
+            .flatten()
+            .map(|cell| cell.len())
+            .max()
+            .unwrap_or(0)
+            .max(3);
+
+        let header: Vec<String> = self
+            .units
+            .iter()
+            .map(|unit| format!("{:>width$}", unit.symbol))
+            .collect();
+        writeln!(writer, "{}", header.join("  ").trim_end())?;
+
+        for row in cells {
+            let row: Vec<String> = row.iter().map(|cell| format!("{:>width$}", cell)).collect();
+            writeln!(writer, "{}", row.join("  "))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_markdown<W: Write>(&self, cells: &[Vec<String>], writer: &mut W) -> io::Result<()> {
+        let header: Vec<&str> = self.units.iter().map(|unit| unit.symbol.as_str()).collect();
+        writeln!(writer, "| {} |", header.join(" | "))?;
+        writeln!(writer, "|{}", " ---: |".repeat(self.units.len()))?;
+
+        for row in cells {
+            writeln!(writer, "| {} |", row.join(" | "))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_csv<W: Write>(&self, cells: &[Vec<String>], writer: &mut W) -> io::Result<()> {
+        let header: Vec<String> = self
+            .units
+            .iter()
+            .map(|unit| csv_field(&unit.name))
+            .collect();
+        writeln!(writer, "{}", header.join(","))?;
+
+        for row in cells {
+            writeln!(writer, "{}", row.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_json<W: Write>(&self, cells: &[Vec<String>], writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "[")?;
+
+        for (i, row) in cells.iter().enumerate() {
+            let fields: Vec<String> = self
+                .units
+                .iter()
+                .zip(row)
+                .map(|(unit, value)| format!("{}: {}", json_string(&unit.name), value))
+                .collect();
+            let separator = if i + 1 < cells.len() { "," } else { "" };
+            writeln!(writer, "  {{ {} }}{}", fields.join(", "), separator)?;
+        }
+
+        writeln!(writer, "]")
+    }
+
+    fn formatted_rows(&self, number_format: &NumberFormat) -> Vec<Vec<String>> {
+        self.rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|value| number_format.format(*value))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Quotes a CSV field if it holds a delimiter, quote or line break.
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes `text` as a quoted JSON string, escaping quotes, backslashes and
+/// control characters.
+pub fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::{Precision, Rounding};
+    use crate::units::{Conversion, Dimension};
+
+    fn render(table: &ConversionTable, format: TableFormat) -> String {
+        let mut buffer = Vec::new();
+        table.write(format, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn includes_both_ends_of_the_range() {
+        let table = ConversionTable::new(-40.0, 100.0, 5.0, Scale::Celsius).unwrap();
+        let rows = table.rows();
+
+        assert_eq!(rows.len(), 29);
+        assert_eq!(rows[0][0], -40.0);
+        assert_eq!(rows[28][0], 100.0);
+    }
+
+    #[test]
+    fn fractional_steps_do_not_drift() {
+        let table = ConversionTable::new(0.0, 1.0, 0.1, Scale::Kelvin).unwrap();
+        assert_eq!(table.rows().len(), 11);
+    }
+
+    #[test]
+    fn source_scale_is_the_first_column() {
+        let table = ConversionTable::new(0.0, 0.0, 1.0, Scale::Kelvin).unwrap();
+        let scales: Vec<Option<Scale>> = table.units().iter().map(Unit::scale).collect();
+        assert_eq!(
+            scales,
+            vec![
+                Some(Scale::Kelvin),
+                Some(Scale::Celsius),
+                Some(Scale::Fahrenheit),
+                Some(Scale::Rankine)
+            ]
+        );
+    }
+
+    #[test]
+    fn historical_scales_only_appear_as_the_source() {
+        let table = ConversionTable::new(80.0, 80.0, 1.0, Scale::Reaumur).unwrap();
+        let scales: Vec<Option<Scale>> = table.units().iter().map(Unit::scale).collect();
+        assert_eq!(
+            scales,
+            vec![
+                Some(Scale::Reaumur),
+                Some(Scale::Celsius),
+                Some(Scale::Fahrenheit),
+                Some(Scale::Kelvin),
+                Some(Scale::Rankine)
+            ]
+        );
+        assert_eq!(table.rows()[0][1], 100.0);
+    }
+
+    #[test]
+    fn writes_values_with_the_given_number_format() {
+        let table = ConversionTable::new(100.0, 100.0, 1.0, Scale::Celsius).unwrap();
+        let number_format = NumberFormat::new(Precision::SignificantFigures(2), Rounding::HalfEven);
+        let mut buffer = Vec::new();
+        table
+            .write_with(TableFormat::Csv, &number_format, &mut buffer)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "celsius,fahrenheit,kelvin,rankine\n100,210,370,670\n"
+        );
+    }
+
+    #[test]
+    fn only_human_readable_formats_are_localized() {
+        let table = ConversionTable::new(100.0, 100.0, 1.0, Scale::Celsius).unwrap();
+        let number_format = NumberFormat::default().with_locale(Locale::German);
+        let render_with = |format| {
+            let mut buffer = Vec::new();
+            table
+                .write_with(format, &number_format, &mut buffer)
+                .unwrap();
+            String::from_utf8(buffer).unwrap()
+        };
+
+        assert!(
+            render_with(TableFormat::Markdown).contains("| 100,00 | 212,00 | 373,15 | 671,67 |")
+        );
+        assert!(render_with(TableFormat::Csv).ends_with("100.00,212.00,373.15,671.67\n"));
+    }
+
+    #[test]
+    fn tables_cover_every_unit_of_the_dimension() {
+        let registry = UnitRegistry::builtin();
+        let table =
+            ConversionTable::for_unit(1.0, 1.0, 1.0, registry.get("h").unwrap(), registry).unwrap();
+        let symbols: Vec<&str> = table
+            .units()
+            .iter()
+            .map(|unit| unit.symbol.as_str())
+            .collect();
+
+        assert_eq!(
+            symbols,
+            vec!["h", "s", "ms", "µs", "ns", "min", "d", "wk", "yr"]
+        );
+        assert_eq!(table.rows()[0][5], 60.0);
+    }
+
+    #[test]
+    fn rejects_non_positive_steps() {
+        for step in [0.0, -5.0, f64::NAN] {
+            let result = ConversionTable::new(0.0, 10.0, step, Scale::Celsius);
+            assert!(matches!(result, Err(TableError::InvalidStep(_))));
+        }
+    }
+
+    #[test]
+    fn rejects_tables_with_too_many_rows() {
+        let result = ConversionTable::new(0.0, 10.0, 1e-300, Scale::Celsius);
+        assert!(matches!(result, Err(TableError::TooManyRows(_))));
+
+        let result = ConversionTable::new(0.0, f64::MAX, 1.0, Scale::Kelvin);
+        assert!(matches!(result, Err(TableError::TooManyRows(_))));
+
+        let table = ConversionTable::new(0.0, (MAX_ROWS - 1) as f64, 1.0, Scale::Celsius).unwrap();
+        assert_eq!(table.rows().len(), MAX_ROWS);
+    }
+
+    #[test]
+    fn rejects_reversed_ranges() {
+        let result = ConversionTable::new(10.0, 0.0, 1.0, Scale::Celsius);
+        assert_eq!(
+            result,
+            Err(TableError::InvalidRange {
+                from: 10.0,
+                to: 0.0
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_ranges_that_start_below_absolute_zero() {
+        let result = ConversionTable::new(-300.0, 0.0, 10.0, Scale::Celsius);
+        assert!(matches!(result, Err(TableError::Conversion(_))));
+    }
+
+    #[test]
+    fn writes_plain_text() {
+        let table = ConversionTable::new(0.0, 100.0, 100.0, Scale::Celsius).unwrap();
+        assert_eq!(
+            render(&table, TableFormat::Text),
+            "    °C      °F       K      °R\n  0.00   32.00  273.15  491.67\n100.00  212.00  373.15  671.67\n"
+        );
+    }
+
+    #[test]
+    fn writes_markdown() {
+        let table = ConversionTable::new(0.0, 0.0, 1.0, Scale::Celsius).unwrap();
+        assert_eq!(
+            render(&table, TableFormat::Markdown),
+            "| °C | °F | K | °R |\n| ---: | ---: | ---: | ---: |\n| 0.00 | 32.00 | 273.15 | 491.67 |\n"
+        );
+    }
+
+    #[test]
+    fn writes_csv() {
+        let table = ConversionTable::new(32.0, 212.0, 180.0, Scale::Fahrenheit).unwrap();
+        assert_eq!(
+            render(&table, TableFormat::Csv),
+            "fahrenheit,celsius,kelvin,rankine\n32.00,0.00,273.15,491.67\n212.00,100.00,373.15,671.67\n"
+        );
+    }
+
+    #[test]
+    fn quotes_csv_headers() {
+        let mut registry = UnitRegistry::empty();
+        let odd = Unit::new(
+            "o",
+            "old \"ell\", Flemish",
+            Dimension::Length,
+            Conversion::Linear(0.6858),
+        );
+        registry.register(odd.clone());
+        registry.register(Unit::new(
+            "m",
+            "metre",
+            Dimension::Length,
+            Conversion::Linear(1.0),
+        ));
+        let table = ConversionTable::for_unit(1.0, 1.0, 1.0, &odd, &registry).unwrap();
+        assert_eq!(
+            render(&table, TableFormat::Csv),
+            "\"old \"\"ell\"\", Flemish\",metre\n1.00,0.69\n"
+        );
+    }
+
+    #[test]
+    fn writes_json() {
+        let table = ConversionTable::new(0.0, 10.0, 10.0, Scale::Celsius).unwrap();
+        assert_eq!(
+            render(&table, TableFormat::Json),
+            "[\n  { \"celsius\": 0.00, \"fahrenheit\": 32.00, \"kelvin\": 273.15, \"rankine\": 491.67 },\n  { \"celsius\": 10.00, \"fahrenheit\": 50.00, \"kelvin\": 283.15, \"rankine\": 509.67 }\n]\n"
+        );
+    }
+
+    #[test]
+    fn parses_format_names() {
+        assert_eq!("md".parse::<TableFormat>(), Ok(TableFormat::Markdown));
+        assert_eq!("CSV".parse::<TableFormat>(), Ok(TableFormat::Csv));
+        assert!("xml".parse::<TableFormat>().is_err());
+    }
+}
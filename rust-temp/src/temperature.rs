@@ -0,0 +1,695 @@
+use std::{
+    fmt,
+    ops::{Add, Neg, Sub},
+    str::FromStr,
+};
+
+use crate::error::ConversionError;
+
+const KELVIN_OFFSET: f64 = 273.15;
+const RANKINE_OFFSET: f64 = 491.67;
+/// Absolute zero on the historical scales, worked out from 0 °C = -273.15 °C.
+const REAUMUR_ZERO: f64 = -218.52;
+const DELISLE_ZERO: f64 = 559.725;
+const NEWTON_ZERO: f64 = -90.1395;
+const ROMER_ZERO: f64 = -135.90375;
+
+/// A temperature scale supported by the converter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scale {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+    Rankine,
+    /// Réaumur: water freezes at 0 °Ré and boils at 80 °Ré.
+    Reaumur,
+    /// Delisle: water boils at 0 °De and freezes at 150 °De, so colder
+    /// temperatures have higher values.
+    Delisle,
+    /// Newton: water freezes at 0 °N and boils at 33 °N.
+    Newton,
+    /// Rømer: water freezes at 7.5 °Rø and boils at 60 °Rø.
+    Romer,
+}
+
+impl Scale {
+    /// Every supported scale, in display order.
+    pub const ALL: [Scale; 8] = [
+        Scale::Celsius,
+        Scale::Fahrenheit,
+        Scale::Kelvin,
+        Scale::Rankine,
+        Scale::Reaumur,
+        Scale::Delisle,
+        Scale::Newton,
+        Scale::Romer,
+    ];
+
+    /// Lowercase name of the scale, e.g. "celsius".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scale::Celsius => "celsius",
+            Scale::Fahrenheit => "fahrenheit",
+            Scale::Kelvin => "kelvin",
+            Scale::Rankine => "rankine",
+            Scale::Reaumur => "réaumur",
+            Scale::Delisle => "delisle",
+            Scale::Newton => "newton",
+            Scale::Romer => "rømer",
+        }
+    }
+
+    /// The name spelled without accents, e.g. "reaumur".
+    fn ascii_name(&self) -> &'static str {
+        match self {
+            Scale::Reaumur => "reaumur",
+            Scale::Romer => "romer",
+            scale => scale.name(),
+        }
+    }
+
+    /// Unit symbol of the scale, e.g. "°C".
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Scale::Celsius => "°C",
+            Scale::Fahrenheit => "°F",
+            Scale::Kelvin => "K",
+            Scale::Rankine => "°R",
+            Scale::Reaumur => "°Ré",
+            Scale::Delisle => "°De",
+            Scale::Newton => "°N",
+            Scale::Romer => "°Rø",
+        }
+    }
+
+    /// The scale a bare value on this scale is converted to by default:
+    /// celsius and fahrenheit swap, the absolute scales go to the relative
+    /// scale with the same degree size and the historical scales go to
+    /// celsius.
+    pub fn counterpart(&self) -> Scale {
+        match self {
+            Scale::Celsius => Scale::Fahrenheit,
+            Scale::Fahrenheit | Scale::Kelvin => Scale::Celsius,
+            Scale::Rankine => Scale::Fahrenheit,
+            _ => Scale::Celsius,
+        }
+    }
+
+    /// Whether this is one of the historical scales, which are only found in
+    /// old records.
+    pub fn is_historical(&self) -> bool {
+        matches!(
+            self,
+            Scale::Reaumur | Scale::Delisle | Scale::Newton | Scale::Romer
+        )
+    }
+
+    /// The value of absolute zero on this scale. It is the lowest physically
+    /// meaningful value on every scale except Delisle, where it is the
+    /// highest.
+    pub fn absolute_zero(&self) -> f64 {
+        match self {
+            Scale::Celsius => -KELVIN_OFFSET,
+            Scale::Fahrenheit => 32.0 - RANKINE_OFFSET,
+            Scale::Kelvin | Scale::Rankine => 0.0,
+            Scale::Reaumur => REAUMUR_ZERO,
+            Scale::Delisle => DELISLE_ZERO,
+            Scale::Newton => NEWTON_ZERO,
+            Scale::Romer => ROMER_ZERO,
+        }
+    }
+
+    /// Whether `value` on this scale is colder than absolute zero.
+    pub fn is_below_absolute_zero(&self, value: f64) -> bool {
+        match self {
+            Scale::Delisle => value > DELISLE_ZERO,
+            _ => value < self.absolute_zero(),
+        }
+    }
+
+    /// The factor and offset that take a value on this scale to kelvin, as
+    /// `(value + offset) * factor`.
+    pub(crate) fn kelvin_affine(&self) -> (f64, f64) {
+        match self {
+            Scale::Celsius => (1.0, KELVIN_OFFSET),
+            Scale::Fahrenheit => (5.0 / 9.0, RANKINE_OFFSET - 32.0),
+            Scale::Kelvin => (1.0, 0.0),
+            Scale::Rankine => (5.0 / 9.0, 0.0),
+            Scale::Reaumur => (5.0 / 4.0, -REAUMUR_ZERO),
+            Scale::Delisle => (-2.0 / 3.0, -DELISLE_ZERO),
+            Scale::Newton => (100.0 / 33.0, -NEWTON_ZERO),
+            Scale::Romer => (40.0 / 21.0, -ROMER_ZERO),
+        }
+    }
+
+    /// Converts a raw value on this scale to another scale without checking
+    /// it against absolute zero.
+    pub(crate) fn convert_value(self, value: f64, to: Scale) -> f64 {
+        if to == self {
+            return value;
+        }
+
+        to.value_from_celsius(self.to_celsius(value))
+    }
+
+    /// Converts a difference between two values on this scale to another
+    /// scale. Only the size of a degree matters, so there is no offset, but
+    /// a rise is a fall on the Delisle scale.
+    pub(crate) fn convert_difference(self, value: f64, to: Scale) -> f64 {
+        if to == self {
+            return value;
+        }
+
+        let (from_numerator, from_denominator) = self.degree_in_celsius();
+        let (to_numerator, to_denominator) = to.degree_in_celsius();
+        value * (from_numerator * to_denominator) / (from_denominator * to_numerator)
+    }
+
+    /// The size of a degree on this scale in celsius degrees, as a fraction
+    /// so differences between the common scales convert exactly.
+    fn degree_in_celsius(self) -> (f64, f64) {
+        match self {
+            Scale::Celsius | Scale::Kelvin => (1.0, 1.0),
+            Scale::Fahrenheit | Scale::Rankine => (5.0, 9.0),
+            Scale::Reaumur => (5.0, 4.0),
+            Scale::Delisle => (-2.0, 3.0),
+            Scale::Newton => (100.0, 33.0),
+            Scale::Romer => (40.0, 21.0),
+        }
+    }
+
+    /// Converts a value on this scale to celsius.
+    ///
+    /// Celsius is used as the pivot so that the common celsius/fahrenheit
+    /// conversions do not pick up extra floating point error.
+    fn to_celsius(self, value: f64) -> f64 {
+        match self {
+            Scale::Celsius => value,
+            Scale::Fahrenheit => (value - 32.0) * (5.0 / 9.0),
+            Scale::Kelvin => value - KELVIN_OFFSET,
+            Scale::Rankine => (value - RANKINE_OFFSET) * (5.0 / 9.0),
+            Scale::Reaumur => value * (5.0 / 4.0),
+            Scale::Delisle => 100.0 - value * (2.0 / 3.0),
+            Scale::Newton => value * (100.0 / 33.0),
+            Scale::Romer => (value - 7.5) * (40.0 / 21.0),
+        }
+    }
+
+    /// Converts a value in celsius to this scale.
+    fn value_from_celsius(self, celsius: f64) -> f64 {
+        match self {
+            Scale::Celsius => celsius,
+            Scale::Fahrenheit => celsius * (9.0 / 5.0) + 32.0,
+            Scale::Kelvin => celsius + KELVIN_OFFSET,
+            Scale::Rankine => celsius * (9.0 / 5.0) + RANKINE_OFFSET,
+            Scale::Reaumur => celsius * (4.0 / 5.0),
+            Scale::Delisle => (100.0 - celsius) * (3.0 / 2.0),
+            Scale::Newton => celsius * (33.0 / 100.0),
+            Scale::Romer => celsius * (21.0 / 40.0) + 7.5,
+        }
+    }
+}
+
+impl fmt::Display for Scale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Scale {
+    type Err = ConversionError;
+
+    /// Accepts a scale's name, symbol or initial, ignoring case. Réaumur
+    /// and Rømer may also be spelled "reaumur" and "romer".
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let wanted = input.trim().to_lowercase();
+        Scale::ALL
+            .into_iter()
+            .find(|scale| {
+                wanted == scale.name()
+                    || wanted == scale.ascii_name()
+                    || wanted == scale.symbol().to_lowercase()
+                    || wanted == scale.name()[..1]
+            })
+            .ok_or_else(|| ConversionError::UnknownScale(input.to_string()))
+    }
+}
+
+/// A temperature value on a particular scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Temperature {
+    value: f64,
+    scale: Scale,
+}
+
+impl Temperature {
+    /// Creates a temperature, rejecting values that are not finite or that
+    /// fall below absolute zero.
+    pub fn new(value: f64, scale: Scale) -> Result<Self, ConversionError> {
+        if !value.is_finite() {
+            return Err(ConversionError::NotFinite);
+        }
+        if scale.is_below_absolute_zero(value) {
+            return Err(ConversionError::BelowAbsoluteZero { value, scale });
+        }
+
+        Ok(Temperature { value, scale })
+    }
+
+    /// Parses a numeric string into a temperature on the given scale.
+    pub fn parse(input: &str, scale: Scale) -> Result<Self, ConversionError> {
+        let value = input
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| ConversionError::InvalidNumber(input.to_string()))?;
+
+        Temperature::new(value, scale)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn scale(&self) -> Scale {
+        self.scale
+    }
+
+    /// Returns the same temperature expressed on another scale.
+    pub fn to(&self, scale: Scale) -> Temperature {
+        // Already validated, so skip the checks to avoid rejecting values that
+        // land a rounding error below absolute zero.
+        Temperature {
+            value: self.scale.convert_value(self.value, scale),
+            scale,
+        }
+    }
+}
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*} {}", precision, self.value, self.scale.symbol()),
+            None => write!(f, "{} {}", self.value, self.scale.symbol()),
+        }
+    }
+}
+
+impl Add<TemperatureDelta> for Temperature {
+    type Output = Result<Temperature, ConversionError>;
+
+    /// Warms the temperature by `delta`, keeping this temperature's scale.
+    /// Fails if the result would be below absolute zero.
+    fn add(self, delta: TemperatureDelta) -> Self::Output {
+        Temperature::new(self.value + delta.to(self.scale).value, self.scale)
+    }
+}
+
+impl Sub<TemperatureDelta> for Temperature {
+    type Output = Result<Temperature, ConversionError>;
+
+    /// Cools the temperature by `delta`, keeping this temperature's scale.
+    /// Fails if the result would be below absolute zero.
+    fn sub(self, delta: TemperatureDelta) -> Self::Output {
+        self + -delta
+    }
+}
+
+impl Sub for Temperature {
+    type Output = TemperatureDelta;
+
+    /// The difference between two temperatures, on the left one's scale.
+    fn sub(self, other: Temperature) -> TemperatureDelta {
+        TemperatureDelta {
+            value: self.value - other.to(self.scale).value,
+            scale: self.scale,
+        }
+    }
+}
+
+/// A difference between two temperatures, such as a rise of 10 °C.
+///
+/// Unlike [`Temperature`], converting a delta only rescales it, so 10 Δ°C is
+/// 18 Δ°F, and deltas may be negative. A delta can be added to a temperature,
+/// but two temperatures cannot be added to each other:
+///
+/// ```compile_fail
+/// use rust_convert::{Scale, Temperature};
+///
+/// let a = Temperature::new(20.0, Scale::Celsius).unwrap();
+/// let b = Temperature::new(30.0, Scale::Celsius).unwrap();
+/// let _ = a + b;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemperatureDelta {
+    value: f64,
+    scale: Scale,
+}
+
+impl TemperatureDelta {
+    /// Creates a temperature difference, rejecting values that are not finite.
+    pub fn new(value: f64, scale: Scale) -> Result<Self, ConversionError> {
+        if !value.is_finite() {
+            return Err(ConversionError::NotFinite);
+        }
+
+        Ok(TemperatureDelta { value, scale })
+    }
+
+    /// Parses a numeric string into a temperature difference on the given
+    /// scale.
+    pub fn parse(input: &str, scale: Scale) -> Result<Self, ConversionError> {
+        let value = input
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| ConversionError::InvalidNumber(input.to_string()))?;
+
+        TemperatureDelta::new(value, scale)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn scale(&self) -> Scale {
+        self.scale
+    }
+
+    /// Returns the same difference expressed on another scale.
+    pub fn to(&self, scale: Scale) -> TemperatureDelta {
+        TemperatureDelta {
+            value: self.scale.convert_difference(self.value, scale),
+            scale,
+        }
+    }
+}
+
+impl Add for TemperatureDelta {
+    type Output = TemperatureDelta;
+
+    /// Sums two differences, on the left one's scale.
+    fn add(self, other: TemperatureDelta) -> TemperatureDelta {
+        TemperatureDelta {
+            value: self.value + other.to(self.scale).value,
+            scale: self.scale,
+        }
+    }
+}
+
+impl Neg for TemperatureDelta {
+    type Output = TemperatureDelta;
+
+    fn neg(self) -> TemperatureDelta {
+        TemperatureDelta {
+            value: -self.value,
+            scale: self.scale,
+        }
+    }
+}
+
+impl fmt::Display for TemperatureDelta {
+    /// Writes the difference with a Δ before the symbol, e.g. "18 Δ°F".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*} Δ{}", precision, self.value, self.scale.symbol()),
+            None => write!(f, "{} Δ{}", self.value, self.scale.symbol()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(expected: f64, actual: f64) {
+        let diff = (expected - actual).abs();
+        assert!(
+            diff <= 1e-9,
+            "expected={} actual={} diff={}",
+            expected,
+            actual,
+            diff
+        );
+    }
+
+    fn temp(value: f64, scale: Scale) -> Temperature {
+        Temperature::new(value, scale).expect("temperature should be valid")
+    }
+
+    #[test]
+    fn converts_freezing_point_to_every_scale() {
+        let freezing = temp(0.0, Scale::Celsius);
+        let expected = [
+            (Scale::Celsius, 0.0),
+            (Scale::Fahrenheit, 32.0),
+            (Scale::Kelvin, 273.15),
+            (Scale::Rankine, 491.67),
+        ];
+
+        for (scale, value) in expected {
+            assert_close(value, freezing.to(scale).value());
+        }
+    }
+
+    #[test]
+    fn converts_between_every_pair_of_scales() {
+        // Boiling point of water on each scale.
+        let boiling = [
+            temp(100.0, Scale::Celsius),
+            temp(212.0, Scale::Fahrenheit),
+            temp(373.15, Scale::Kelvin),
+            temp(671.67, Scale::Rankine),
+        ];
+
+        for from in boiling {
+            for to in boiling {
+                let result = from.to(to.scale());
+                assert_eq!(result.scale(), to.scale());
+                assert_close(to.value(), result.value());
+            }
+        }
+    }
+
+    #[test]
+    fn converts_reference_points_on_historical_scales() {
+        // Water freezing, body temperature and water boiling, as published
+        // for each scale.
+        let reference = [
+            (Scale::Celsius, [0.0, 37.0, 100.0]),
+            (Scale::Reaumur, [0.0, 29.6, 80.0]),
+            (Scale::Delisle, [150.0, 94.5, 0.0]),
+            (Scale::Newton, [0.0, 12.21, 33.0]),
+            (Scale::Romer, [7.5, 26.925, 60.0]),
+        ];
+
+        for (from, from_values) in reference {
+            for (to, to_values) in reference {
+                for (value, expected) in from_values.into_iter().zip(to_values) {
+                    let result = temp(value, from).to(to);
+                    assert_close(expected, result.value());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn delisle_runs_backwards() {
+        assert!(
+            temp(0.0, Scale::Delisle).to(Scale::Celsius).value()
+                > temp(150.0, Scale::Delisle).to(Scale::Celsius).value()
+        );
+        assert_close(559.725, temp(0.0, Scale::Kelvin).to(Scale::Delisle).value());
+        assert_eq!(
+            Temperature::new(560.0, Scale::Delisle),
+            Err(ConversionError::BelowAbsoluteZero {
+                value: 560.0,
+                scale: Scale::Delisle
+            })
+        );
+        assert!(Temperature::new(-1000.0, Scale::Delisle).is_ok());
+    }
+
+    #[test]
+    fn historical_deltas_keep_their_direction() {
+        let rise = delta(10.0, Scale::Celsius);
+        assert_close(8.0, rise.to(Scale::Reaumur).value());
+        assert_close(-15.0, rise.to(Scale::Delisle).value());
+        assert_close(3.3, rise.to(Scale::Newton).value());
+        assert_close(5.25, rise.to(Scale::Romer).value());
+        assert_close(
+            18.0,
+            delta(-15.0, Scale::Delisle).to(Scale::Fahrenheit).value(),
+        );
+    }
+
+    #[test]
+    fn absolute_zero_is_zero_on_absolute_scales() {
+        let zero = temp(-273.15, Scale::Celsius);
+        assert_close(0.0, zero.to(Scale::Kelvin).value());
+        assert_close(0.0, zero.to(Scale::Rankine).value());
+        assert_close(-459.67, zero.to(Scale::Fahrenheit).value());
+    }
+
+    #[test]
+    fn converting_to_the_same_scale_is_a_no_op() {
+        let body = temp(36.6, Scale::Celsius);
+        assert_eq!(body.to(Scale::Celsius), body);
+    }
+
+    #[test]
+    fn display_uses_scale_symbol_and_precision() {
+        let room = temp(300.0, Scale::Kelvin);
+        assert_eq!(room.to_string(), "300 K");
+        assert_eq!(format!("{:.2}", room.to(Scale::Fahrenheit)), "80.33 °F");
+    }
+
+    #[test]
+    fn rejects_values_below_absolute_zero() {
+        let cases = [
+            (-273.16, Scale::Celsius),
+            (-500.0, Scale::Fahrenheit),
+            (-0.01, Scale::Kelvin),
+            (-1.0, Scale::Rankine),
+        ];
+
+        for (value, scale) in cases {
+            let result = Temperature::new(value, scale);
+            assert_eq!(
+                result,
+                Err(ConversionError::BelowAbsoluteZero { value, scale })
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_absolute_zero_itself() {
+        for scale in Scale::ALL {
+            assert!(Temperature::new(scale.absolute_zero(), scale).is_ok());
+        }
+    }
+
+    #[test]
+    fn rejects_non_finite_values() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let result = Temperature::new(value, Scale::Celsius);
+            assert_eq!(result, Err(ConversionError::NotFinite));
+        }
+    }
+
+    #[test]
+    fn parse_reports_invalid_numbers() {
+        let result = Temperature::parse("zero", Scale::Celsius);
+        assert_eq!(result, Err(ConversionError::InvalidNumber("zero".into())));
+    }
+
+    #[test]
+    fn parse_rejects_nan_and_infinity_literals() {
+        for input in ["NaN", "inf", "-infinity"] {
+            let result = Temperature::parse(input, Scale::Kelvin);
+            assert_eq!(result, Err(ConversionError::NotFinite));
+        }
+    }
+
+    #[test]
+    fn parses_scale_names_symbols_and_initials() {
+        let cases = [
+            ("c", Scale::Celsius),
+            ("Fahrenheit", Scale::Fahrenheit),
+            ("°F", Scale::Fahrenheit),
+            ("K", Scale::Kelvin),
+            ("rankine", Scale::Rankine),
+            ("°r", Scale::Rankine),
+            ("Réaumur", Scale::Reaumur),
+            ("reaumur", Scale::Reaumur),
+            ("°De", Scale::Delisle),
+            ("n", Scale::Newton),
+            ("romer", Scale::Romer),
+            ("°Rø", Scale::Romer),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(input.parse::<Scale>(), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_scale_names() {
+        let result = "x".parse::<Scale>();
+        assert_eq!(result, Err(ConversionError::UnknownScale("x".into())));
+    }
+
+    fn delta(value: f64, scale: Scale) -> TemperatureDelta {
+        TemperatureDelta::new(value, scale).expect("delta should be valid")
+    }
+
+    #[test]
+    fn deltas_convert_without_an_offset() {
+        let rise = delta(10.0, Scale::Celsius);
+        assert_eq!(rise.to(Scale::Fahrenheit), delta(18.0, Scale::Fahrenheit));
+        assert_eq!(rise.to(Scale::Kelvin), delta(10.0, Scale::Kelvin));
+        assert_eq!(rise.to(Scale::Rankine).value(), 18.0);
+        assert_eq!(
+            delta(18.0, Scale::Fahrenheit).to(Scale::Celsius).value(),
+            10.0
+        );
+    }
+
+    #[test]
+    fn deltas_may_be_negative_but_must_be_finite() {
+        assert_eq!(
+            delta(-500.0, Scale::Kelvin).to(Scale::Celsius).value(),
+            -500.0
+        );
+        assert_eq!(
+            TemperatureDelta::new(f64::NAN, Scale::Celsius),
+            Err(ConversionError::NotFinite)
+        );
+    }
+
+    #[test]
+    fn adds_deltas_to_temperatures() {
+        let room = temp(20.0, Scale::Celsius);
+        assert_eq!(
+            room + delta(18.0, Scale::Fahrenheit),
+            Ok(temp(30.0, Scale::Celsius))
+        );
+        assert_eq!(
+            room - delta(10.0, Scale::Kelvin),
+            Ok(temp(10.0, Scale::Celsius))
+        );
+        assert!(matches!(
+            room - delta(500.0, Scale::Celsius),
+            Err(ConversionError::BelowAbsoluteZero { .. })
+        ));
+    }
+
+    #[test]
+    fn subtracting_temperatures_gives_a_delta() {
+        let difference = temp(212.0, Scale::Fahrenheit) - temp(0.0, Scale::Celsius);
+        assert_eq!(difference, delta(180.0, Scale::Fahrenheit));
+        assert_eq!(
+            delta(1.0, Scale::Celsius) + delta(1.8, Scale::Fahrenheit),
+            delta(2.0, Scale::Celsius)
+        );
+    }
+
+    #[test]
+    fn delta_display_marks_the_difference() {
+        assert_eq!(delta(18.0, Scale::Fahrenheit).to_string(), "18 Δ°F");
+        assert_eq!(format!("{:.2}", delta(-5.0, Scale::Kelvin)), "-5.00 ΔK");
+    }
+
+    #[test]
+    fn below_absolute_zero_message_names_the_limit() {
+        let error = Temperature::new(-500.0, Scale::Celsius).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "-500 degrees celsius is below absolute zero (-273.15 degrees celsius)."
+        );
+
+        let error = Temperature::new(-5.0, Scale::Kelvin).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "-5 kelvin is below absolute zero (0 kelvin)."
+        );
+    }
+}
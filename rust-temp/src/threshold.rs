@@ -0,0 +1,237 @@
+use crate::temperature::{Scale, Temperature, TemperatureDelta};
+
+/// Which side of its limit sets a threshold off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// Hotter than the limit.
+    Above,
+    /// Colder than the limit.
+    Below,
+}
+
+impl Side {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Side::Above => "above",
+            Side::Below => "below",
+        }
+    }
+}
+
+/// A temperature that readings should stay on one side of.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Threshold {
+    pub side: Side,
+    pub limit: Temperature,
+}
+
+impl Threshold {
+    pub fn above(limit: Temperature) -> Self {
+        Threshold {
+            side: Side::Above,
+            limit,
+        }
+    }
+
+    pub fn below(limit: Temperature) -> Self {
+        Threshold {
+            side: Side::Below,
+            limit,
+        }
+    }
+}
+
+/// Whether a threshold has just been crossed or has just recovered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertState {
+    Triggered,
+    Cleared,
+}
+
+/// A change in a threshold's state, and the reading that caused it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Alert {
+    pub threshold: Threshold,
+    pub state: AlertState,
+    pub reading: Temperature,
+}
+
+/// Watches readings against a set of thresholds.
+///
+/// A threshold triggers once when a reading goes past its limit, and
+/// clears only once a reading is back on the right side by more than the
+/// hysteresis, so a reading hovering around the limit doesn't raise an
+/// alert each time it wobbles across. Hotter and colder are judged on the
+/// kelvin scale, so thresholds behave the same on inverted scales like
+/// Delisle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdMonitor {
+    thresholds: Vec<(Threshold, bool)>,
+    /// The hysteresis in kelvins.
+    hysteresis: f64,
+}
+
+impl ThresholdMonitor {
+    /// A monitor with every threshold clear. The hysteresis's sign is
+    /// ignored.
+    pub fn new(thresholds: Vec<Threshold>, hysteresis: TemperatureDelta) -> Self {
+        ThresholdMonitor {
+            thresholds: thresholds
+                .into_iter()
+                .map(|threshold| (threshold, false))
+                .collect(),
+            hysteresis: hysteresis.to(Scale::Kelvin).value().abs(),
+        }
+    }
+
+    /// Checks a reading, returning the thresholds it triggered or cleared
+    /// in the order they were given.
+    pub fn check(&mut self, reading: Temperature) -> Vec<Alert> {
+        let kelvin = reading.to(Scale::Kelvin).value();
+        let mut alerts = Vec::new();
+
+        for (threshold, triggered) in &mut self.thresholds {
+            let limit = threshold.limit.to(Scale::Kelvin).value();
+            let (past, recovered) = match threshold.side {
+                Side::Above => (kelvin > limit, kelvin < limit - self.hysteresis),
+                Side::Below => (kelvin < limit, kelvin > limit + self.hysteresis),
+            };
+            let state = match (*triggered, past, recovered) {
+                (false, true, _) => AlertState::Triggered,
+                (true, _, true) => AlertState::Cleared,
+                _ => continue,
+            };
+            *triggered = state == AlertState::Triggered;
+            alerts.push(Alert {
+                threshold: *threshold,
+                state,
+                reading,
+            });
+        }
+
+        alerts
+    }
+
+    /// Whether any threshold is currently triggered.
+    pub fn is_alerting(&self) -> bool {
+        self.thresholds.iter().any(|(_, triggered)| *triggered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn celsius(value: f64) -> Temperature {
+        Temperature::new(value, Scale::Celsius).expect("temperature should be valid")
+    }
+
+    fn states(monitor: &mut ThresholdMonitor, readings: &[f64]) -> Vec<Vec<AlertState>> {
+        readings
+            .iter()
+            .map(|reading| {
+                monitor
+                    .check(celsius(*reading))
+                    .iter()
+                    .map(|alert| alert.state)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn triggers_once_and_clears_past_the_hysteresis() {
+        let hysteresis = TemperatureDelta::new(2.0, Scale::Celsius).unwrap();
+        let mut monitor = ThresholdMonitor::new(vec![Threshold::above(celsius(80.0))], hysteresis);
+
+        assert_eq!(
+            states(
+                &mut monitor,
+                &[70.0, 80.0, 80.5, 79.5, 81.0, 78.5, 77.9, 79.0, 90.0]
+            ),
+            vec![
+                vec![],
+                vec![],
+                vec![AlertState::Triggered],
+                vec![],
+                vec![],
+                vec![],
+                vec![AlertState::Cleared],
+                vec![],
+                vec![AlertState::Triggered],
+            ]
+        );
+        assert!(monitor.is_alerting());
+    }
+
+    #[test]
+    fn below_thresholds_clear_above_the_limit() {
+        let hysteresis = TemperatureDelta::new(1.0, Scale::Celsius).unwrap();
+        let mut monitor = ThresholdMonitor::new(vec![Threshold::below(celsius(5.0))], hysteresis);
+
+        assert_eq!(
+            states(&mut monitor, &[6.0, 4.0, 5.5, 6.5]),
+            vec![
+                vec![],
+                vec![AlertState::Triggered],
+                vec![],
+                vec![AlertState::Cleared]
+            ]
+        );
+        assert!(!monitor.is_alerting());
+    }
+
+    #[test]
+    fn compares_readings_and_limits_on_any_scale() {
+        let limit = Temperature::new(176.0, Scale::Fahrenheit).unwrap();
+        let hysteresis = TemperatureDelta::new(3.6, Scale::Fahrenheit).unwrap();
+        let mut monitor = ThresholdMonitor::new(vec![Threshold::above(limit)], hysteresis);
+
+        assert_eq!(
+            states(&mut monitor, &[80.5, 78.5, 77.9]),
+            vec![
+                vec![AlertState::Triggered],
+                vec![],
+                vec![AlertState::Cleared]
+            ]
+        );
+    }
+
+    #[test]
+    fn above_means_hotter_on_inverted_scales() {
+        // 0 °De is 100 °C, so 20 °De is colder.
+        let limit = Temperature::new(20.0, Scale::Delisle).unwrap();
+        let mut monitor = ThresholdMonitor::new(
+            vec![Threshold::above(limit)],
+            TemperatureDelta::new(0.0, Scale::Delisle).unwrap(),
+        );
+
+        let alerts = monitor.check(Temperature::new(10.0, Scale::Delisle).unwrap());
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].state, AlertState::Triggered);
+    }
+
+    #[test]
+    fn reports_each_threshold_it_crosses() {
+        let mut monitor = ThresholdMonitor::new(
+            vec![
+                Threshold::above(celsius(60.0)),
+                Threshold::above(celsius(80.0)),
+                Threshold::below(celsius(0.0)),
+            ],
+            TemperatureDelta::new(0.0, Scale::Celsius).unwrap(),
+        );
+
+        let alerts = monitor.check(celsius(85.0));
+
+        assert_eq!(
+            alerts
+                .iter()
+                .map(|alert| alert.threshold.limit.value())
+                .collect::<Vec<_>>(),
+            vec![60.0, 80.0]
+        );
+        assert!(alerts.iter().all(|alert| alert.reading == celsius(85.0)));
+    }
+}
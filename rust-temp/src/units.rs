@@ -0,0 +1,960 @@
+use std::{fmt, str::FromStr, sync::LazyLock};
+
+use crate::{
+    compound,
+    dimension::{BaseDimension, DimensionVector},
+    error::ConversionError,
+    temperature::{Scale, Temperature, TemperatureDelta},
+};
+
+/// The physical quantity a unit measures. Units can only be converted to
+/// other units of the same dimension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Area,
+    Speed,
+    Pressure,
+    Energy,
+    Force,
+    Power,
+    Time,
+    DataSize,
+    Temperature,
+    /// A combination of base dimensions without a name of its own, such as
+    /// thermal conductivity (`L·M·T⁻³·Θ⁻¹`).
+    Derived(DimensionVector),
+}
+
+impl Dimension {
+    /// Every dimension with a name, in display order.
+    pub const NAMED: [Dimension; 12] = [
+        Dimension::Length,
+        Dimension::Mass,
+        Dimension::Volume,
+        Dimension::Area,
+        Dimension::Speed,
+        Dimension::Pressure,
+        Dimension::Energy,
+        Dimension::Force,
+        Dimension::Power,
+        Dimension::Time,
+        Dimension::DataSize,
+        Dimension::Temperature,
+    ];
+
+    /// The dimension's name, or `None` for derived dimensions.
+    pub fn name(&self) -> Option<&'static str> {
+        let name = match self {
+            Dimension::Length => "length",
+            Dimension::Mass => "mass",
+            Dimension::Volume => "volume",
+            Dimension::Area => "area",
+            Dimension::Speed => "speed",
+            Dimension::Pressure => "pressure",
+            Dimension::Energy => "energy",
+            Dimension::Force => "force",
+            Dimension::Power => "power",
+            Dimension::Time => "time",
+            Dimension::DataSize => "data size",
+            Dimension::Temperature => "temperature",
+            Dimension::Derived(_) => return None,
+        };
+        Some(name)
+    }
+
+    /// The exponents of the base dimensions this dimension is made of.
+    pub fn vector(&self) -> DimensionVector {
+        use BaseDimension::*;
+
+        let exponents: &[(BaseDimension, i8)] = match self {
+            Dimension::Length => &[(Length, 1)],
+            Dimension::Mass => &[(Mass, 1)],
+            Dimension::Volume => &[(Length, 3)],
+            Dimension::Area => &[(Length, 2)],
+            Dimension::Speed => &[(Length, 1), (Time, -1)],
+            Dimension::Pressure => &[(Length, -1), (Mass, 1), (Time, -2)],
+            Dimension::Energy => &[(Length, 2), (Mass, 1), (Time, -2)],
+            Dimension::Force => &[(Length, 1), (Mass, 1), (Time, -2)],
+            Dimension::Power => &[(Length, 2), (Mass, 1), (Time, -3)],
+            Dimension::Time => &[(Time, 1)],
+            Dimension::DataSize => &[(Data, 1)],
+            Dimension::Temperature => &[(Temperature, 1)],
+            Dimension::Derived(vector) => return *vector,
+        };
+        DimensionVector::from_exponents(exponents)
+    }
+
+    /// The named dimension with this vector, or a derived one if none has it.
+    /// Going through here keeps equal vectors comparing equal.
+    pub fn from_vector(vector: DimensionVector) -> Dimension {
+        Dimension::NAMED
+            .into_iter()
+            .find(|dimension| dimension.vector() == vector)
+            .unwrap_or(Dimension::Derived(vector))
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => f.write_str(name),
+            None => write!(f, "{}", self.vector()),
+        }
+    }
+}
+
+impl FromStr for Dimension {
+    type Err = String;
+
+    /// Accepts a dimension's name, with spaces, underscores or dashes.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let wanted = input.trim().to_lowercase().replace(['_', '-'], " ");
+        let dimension = match wanted.as_str() {
+            "length" => Dimension::Length,
+            "mass" => Dimension::Mass,
+            "volume" => Dimension::Volume,
+            "area" => Dimension::Area,
+            "speed" => Dimension::Speed,
+            "pressure" => Dimension::Pressure,
+            "energy" => Dimension::Energy,
+            "force" => Dimension::Force,
+            "power" => Dimension::Power,
+            "time" => Dimension::Time,
+            "data size" | "data" => Dimension::DataSize,
+            "temperature" => Dimension::Temperature,
+            _ => return Err(input.to_string()),
+        };
+        Ok(dimension)
+    }
+}
+
+/// How a unit maps onto the base unit of its dimension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Conversion {
+    /// `base = value * factor`
+    Linear(f64),
+    /// `base = (value + offset) * factor`
+    Affine { factor: f64, offset: f64 },
+    /// One of the built-in temperature scales, converted through
+    /// [`Temperature`] so results match the scale-specific code exactly.
+    Temperature(Scale),
+}
+
+impl Conversion {
+    /// The conversion for a unit where `value` in the new unit equals
+    /// `(value + offset) * factor` in a unit using this conversion.
+    pub fn scaled(self, factor: f64, offset: f64) -> Conversion {
+        let (base_factor, base_offset) = match self {
+            Conversion::Linear(base_factor) if offset == 0.0 => {
+                return Conversion::Linear(factor * base_factor);
+            }
+            Conversion::Linear(base_factor) => (base_factor, 0.0),
+            Conversion::Affine { factor, offset } => (factor, offset),
+            Conversion::Temperature(scale) => scale.kelvin_affine(),
+        };
+
+        Conversion::Affine {
+            factor: factor * base_factor,
+            offset: offset + base_offset / factor,
+        }
+    }
+
+    /// How many base units one of this unit spans, ignoring any offset. This
+    /// is what a unit contributes inside a compound unit such as
+    /// `W/(m*°F)`, where only differences of temperature make sense.
+    pub fn factor(self) -> f64 {
+        match self {
+            Conversion::Linear(factor) | Conversion::Affine { factor, .. } => factor,
+            Conversion::Temperature(scale) => scale.kelvin_affine().0,
+        }
+    }
+}
+
+/// A named unit of measurement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    pub symbol: String,
+    pub name: String,
+    /// Other spellings that should resolve to this unit.
+    pub aliases: Vec<String>,
+    pub dimension: Dimension,
+    pub conversion: Conversion,
+}
+
+impl Unit {
+    pub fn new(symbol: &str, name: &str, dimension: Dimension, conversion: Conversion) -> Self {
+        Unit {
+            symbol: symbol.to_string(),
+            name: name.to_string(),
+            aliases: Vec::new(),
+            dimension,
+            conversion,
+        }
+    }
+
+    /// Adds alternative spellings, e.g. plurals or ASCII forms of a symbol.
+    pub fn with_aliases(mut self, aliases: &[&str]) -> Self {
+        self.aliases
+            .extend(aliases.iter().map(|alias| alias.to_string()));
+        self
+    }
+
+    /// The temperature scale behind this unit, if it is a built-in one.
+    pub fn scale(&self) -> Option<Scale> {
+        match self.conversion {
+            Conversion::Temperature(scale) => Some(scale),
+            _ => None,
+        }
+    }
+
+    /// Converts a value in this unit to the base unit of its dimension.
+    pub fn to_base(&self, value: f64) -> Result<f64, ConversionError> {
+        if !value.is_finite() {
+            return Err(ConversionError::NotFinite);
+        }
+
+        let base = match self.conversion {
+            Conversion::Linear(factor) => value * factor,
+            Conversion::Affine { factor, offset } => (value + offset) * factor,
+            Conversion::Temperature(scale) => {
+                return Ok(Temperature::new(value, scale)?.to(Scale::Kelvin).value());
+            }
+        };
+
+        // Custom temperature units are checked in kelvin, with a little slack
+        // so absolute zero itself survives rounding.
+        if self.dimension == Dimension::Temperature && base < -1e-9 {
+            return Err(ConversionError::UnitBelowAbsoluteZero {
+                value,
+                unit: self.symbol.clone(),
+            });
+        }
+
+        Ok(base)
+    }
+
+    /// Converts a value in the base unit of this unit's dimension to this unit.
+    pub fn from_base(&self, base: f64) -> f64 {
+        match self.conversion {
+            Conversion::Linear(factor) => base / factor,
+            Conversion::Affine { factor, offset } => base / factor - offset,
+            Conversion::Temperature(scale) => Scale::Kelvin.convert_value(base, scale),
+        }
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.symbol == name || self.aliases.iter().any(|alias| alias == name)
+    }
+
+    fn matches_ignoring_case(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        [&self.symbol, &self.name]
+            .into_iter()
+            .chain(&self.aliases)
+            .any(|candidate| candidate.to_lowercase() == name)
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.symbol)
+    }
+}
+
+/// A value in a particular unit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub unit: Unit,
+}
+
+impl Quantity {
+    pub fn new(value: f64, unit: Unit) -> Self {
+        Quantity { value, unit }
+    }
+
+    /// Parses a numeric string into a quantity, rejecting values the unit
+    /// cannot hold, such as temperatures below absolute zero.
+    pub fn parse(input: &str, unit: &Unit) -> Result<Self, ConversionError> {
+        let value = input
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| ConversionError::InvalidNumber(input.to_string()))?;
+        unit.to_base(value)?;
+
+        Ok(Quantity::new(value, unit.clone()))
+    }
+
+    /// Returns the same quantity expressed in another unit.
+    pub fn to(&self, unit: &Unit) -> Result<Quantity, ConversionError> {
+        let value = convert_between(self.value, &self.unit, unit)?;
+        Ok(Quantity::new(value, unit.clone()))
+    }
+
+    /// Like [`Quantity::to`], but treats the quantity as a difference, so
+    /// temperature offsets are ignored.
+    pub fn delta_to(&self, unit: &Unit) -> Result<Quantity, ConversionError> {
+        let value = convert_delta_between(self.value, &self.unit, unit)?;
+        Ok(Quantity::new(value, unit.clone()))
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*} {}", precision, self.value, self.unit),
+            None => write!(f, "{} {}", self.value, self.unit),
+        }
+    }
+}
+
+/// A set of units that can be looked up by symbol, name or alias.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitRegistry {
+    units: Vec<Unit>,
+}
+
+static BUILTIN: LazyLock<UnitRegistry> = LazyLock::new(|| UnitRegistry {
+    units: builtin_units(),
+});
+
+impl UnitRegistry {
+    /// A registry with no units in it.
+    pub fn empty() -> Self {
+        UnitRegistry { units: Vec::new() }
+    }
+
+    /// The shared registry of built-in units.
+    pub fn builtin() -> &'static UnitRegistry {
+        &BUILTIN
+    }
+
+    pub fn units(&self) -> &[Unit] {
+        &self.units
+    }
+
+    /// Adds a unit, replacing any existing unit with the same symbol.
+    pub fn register(&mut self, unit: Unit) {
+        self.units.retain(|existing| existing.symbol != unit.symbol);
+        self.units.push(unit);
+    }
+
+    /// Looks a unit up by exact symbol or alias first, then by symbol, name
+    /// or alias ignoring case.
+    pub fn find(&self, name: &str) -> Option<&Unit> {
+        let name = name.trim();
+        self.units
+            .iter()
+            .find(|unit| unit.matches(name))
+            .or_else(|| {
+                self.units
+                    .iter()
+                    .find(|unit| unit.matches_ignoring_case(name))
+            })
+    }
+
+    /// Like [`UnitRegistry::find`], but only finds units of `dimension`.
+    /// This tells "degrees newton" apart from the newton of force.
+    pub fn find_in(&self, name: &str, dimension: Dimension) -> Option<&Unit> {
+        let name = name.trim();
+        let mut units = self.units.iter().filter(|unit| unit.dimension == dimension);
+        units
+            .clone()
+            .find(|unit| unit.matches(name))
+            .or_else(|| units.find(|unit| unit.matches_ignoring_case(name)))
+    }
+
+    /// The registry's unit for a built-in temperature scale, if it has one.
+    pub fn for_scale(&self, scale: Scale) -> Option<&Unit> {
+        self.units.iter().find(|unit| unit.scale() == Some(scale))
+    }
+
+    /// Like [`UnitRegistry::find`], but reports unknown names as an error.
+    pub fn get(&self, name: &str) -> Result<&Unit, ConversionError> {
+        self.find(name)
+            .ok_or_else(|| ConversionError::UnknownUnit(name.to_string()))
+    }
+
+    /// Resolves a unit name or a compound unit expression built from this
+    /// registry's units with `*`, `/`, `^` and parentheses, such as
+    /// `kg*m/s^2` or `BTU/(h*ft*°F)`.
+    ///
+    /// Names the registry knows are returned as they are; anything else is
+    /// parsed into a new linear unit whose dimension is worked out from its
+    /// parts.
+    pub fn parse_unit(&self, expression: &str) -> Result<Unit, ConversionError> {
+        match self.find(expression) {
+            Some(unit) => Ok(unit.clone()),
+            None => compound::parse(expression, self),
+        }
+    }
+
+    /// Converts `value` between two units or compound units named in this
+    /// registry.
+    pub fn convert(&self, value: f64, from: &str, to: &str) -> Result<f64, ConversionError> {
+        convert_between(value, &self.parse_unit(from)?, &self.parse_unit(to)?)
+    }
+
+    /// Converts a difference of `value` between two units or compound units
+    /// named in this registry.
+    pub fn convert_delta(&self, value: f64, from: &str, to: &str) -> Result<f64, ConversionError> {
+        convert_delta_between(value, &self.parse_unit(from)?, &self.parse_unit(to)?)
+    }
+}
+
+impl Default for UnitRegistry {
+    /// A registry holding a copy of the built-in units.
+    fn default() -> Self {
+        UnitRegistry::builtin().clone()
+    }
+}
+
+/// Converts `value` from one unit to another, refusing to convert across
+/// dimensions.
+pub fn convert_between(value: f64, from: &Unit, to: &Unit) -> Result<f64, ConversionError> {
+    check_dimensions(from, to)?;
+
+    // Built-in scales skip the trip through kelvin to keep their exact results.
+    if let (Some(from_scale), Some(to_scale)) = (from.scale(), to.scale()) {
+        return finite(Temperature::new(value, from_scale)?.to(to_scale).value());
+    }
+
+    finite(to.from_base(from.to_base(value)?))
+}
+
+/// Converts a difference of `value`, such as a temperature rise, from one
+/// unit to another. Offsets are ignored, so a rise of 10 °C is a rise of
+/// 18 °F, and the value may be negative.
+pub fn convert_delta_between(value: f64, from: &Unit, to: &Unit) -> Result<f64, ConversionError> {
+    check_dimensions(from, to)?;
+
+    if let (Some(from_scale), Some(to_scale)) = (from.scale(), to.scale()) {
+        return finite(
+            TemperatureDelta::new(value, from_scale)?
+                .to(to_scale)
+                .value(),
+        );
+    }
+    if !value.is_finite() {
+        return Err(ConversionError::NotFinite);
+    }
+
+    finite(value * from.conversion.factor() / to.conversion.factor())
+}
+
+/// Rejects results that overflowed, such as 1e308 °C in fahrenheit.
+fn finite(value: f64) -> Result<f64, ConversionError> {
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(ConversionError::NotFinite)
+    }
+}
+
+fn check_dimensions(from: &Unit, to: &Unit) -> Result<(), ConversionError> {
+    if from.dimension != to.dimension {
+        return Err(ConversionError::IncompatibleUnits {
+            from: from.symbol.clone(),
+            from_dimension: from.dimension,
+            to: to.symbol.clone(),
+            to_dimension: to.dimension,
+        });
+    }
+    Ok(())
+}
+
+/// Converts `value` between two built-in units, e.g.
+/// `convert(5.0, "km", "mi")`.
+pub fn convert(value: f64, from: &str, to: &str) -> Result<f64, ConversionError> {
+    UnitRegistry::builtin().convert(value, from, to)
+}
+
+/// Converts a difference of `value` between two built-in units, e.g.
+/// `convert_delta(10.0, "°C", "°F")` is 18.
+pub fn convert_delta(value: f64, from: &str, to: &str) -> Result<f64, ConversionError> {
+    UnitRegistry::builtin().convert_delta(value, from, to)
+}
+
+fn builtin_units() -> Vec<Unit> {
+    use Conversion::Linear;
+    use Dimension::*;
+
+    let linear =
+        |symbol, name, dimension, factor| Unit::new(symbol, name, dimension, Linear(factor));
+
+    vec![
+        // Length, in metres.
+        linear("m", "metre", Length, 1.0).with_aliases(&["meter", "metres", "meters"]),
+        linear("km", "kilometre", Length, 1e3).with_aliases(&[
+            "kilometer",
+            "kilometres",
+            "kilometers",
+        ]),
+        linear("cm", "centimetre", Length, 1e-2).with_aliases(&[
+            "centimeter",
+            "centimetres",
+            "centimeters",
+        ]),
+        linear("mm", "millimetre", Length, 1e-3).with_aliases(&[
+            "millimeter",
+            "millimetres",
+            "millimeters",
+        ]),
+        linear("µm", "micrometre", Length, 1e-6).with_aliases(&[
+            "um",
+            "micrometer",
+            "micron",
+            "microns",
+        ]),
+        linear("nm", "nanometre", Length, 1e-9).with_aliases(&[
+            "nanometer",
+            "nanometres",
+            "nanometers",
+        ]),
+        linear("in", "inch", Length, 0.0254).with_aliases(&["inches"]),
+        linear("ft", "foot", Length, 0.3048).with_aliases(&["feet"]),
+        linear("yd", "yard", Length, 0.9144).with_aliases(&["yards"]),
+        linear("mi", "mile", Length, 1609.344).with_aliases(&["miles"]),
+        linear("nmi", "nautical mile", Length, 1852.0).with_aliases(&["NM", "nautical miles"]),
+        // Mass, in kilograms.
+        linear("kg", "kilogram", Mass, 1.0).with_aliases(&["kilograms", "kilo", "kilos"]),
+        linear("g", "gram", Mass, 1e-3).with_aliases(&["grams"]),
+        linear("mg", "milligram", Mass, 1e-6).with_aliases(&["milligrams"]),
+        linear("t", "tonne", Mass, 1e3).with_aliases(&["tonnes", "metric ton"]),
+        linear("lb", "pound", Mass, 0.45359237).with_aliases(&["lbs", "pounds"]),
+        linear("oz", "ounce", Mass, 0.028349523125).with_aliases(&["ounces"]),
+        linear("st", "stone", Mass, 6.35029318).with_aliases(&["stones"]),
+        // Volume, in cubic metres.
+        linear("m³", "cubic metre", Volume, 1.0).with_aliases(&[
+            "m3",
+            "cubic meter",
+            "cubic metres",
+            "cubic meters",
+        ]),
+        linear("L", "litre", Volume, 1e-3).with_aliases(&["l", "liter", "litres", "liters"]),
+        linear("mL", "millilitre", Volume, 1e-6).with_aliases(&[
+            "ml",
+            "milliliter",
+            "millilitres",
+            "milliliters",
+        ]),
+        linear("cm³", "cubic centimetre", Volume, 1e-6).with_aliases(&["cm3", "cc"]),
+        linear("ft³", "cubic foot", Volume, 0.028316846592).with_aliases(&["ft3", "cubic feet"]),
+        linear("in³", "cubic inch", Volume, 1.6387064e-5).with_aliases(&["in3", "cubic inches"]),
+        linear("gal", "US gallon", Volume, 3.785411784e-3).with_aliases(&["gallon", "gallons"]),
+        linear("imp gal", "imperial gallon", Volume, 4.54609e-3)
+            .with_aliases(&["impgal", "imperial gallons"]),
+        linear("qt", "US quart", Volume, 9.46352946e-4).with_aliases(&["quart", "quarts"]),
+        linear("pt", "US pint", Volume, 4.73176473e-4).with_aliases(&["pint", "pints"]),
+        linear("cup", "US cup", Volume, 2.365882365e-4).with_aliases(&["cups"]),
+        linear("fl oz", "US fluid ounce", Volume, 2.95735295625e-5).with_aliases(&[
+            "floz",
+            "fluid ounce",
+            "fluid ounces",
+        ]),
+        linear("tbsp", "tablespoon", Volume, 1.478676478125e-5).with_aliases(&["tablespoons"]),
+        linear("tsp", "teaspoon", Volume, 4.92892159375e-6).with_aliases(&["teaspoons"]),
+        // Area, in square metres.
+        linear("m²", "square metre", Area, 1.0).with_aliases(&[
+            "m2",
+            "square meter",
+            "square metres",
+            "square meters",
+        ]),
+        linear("km²", "square kilometre", Area, 1e6).with_aliases(&["km2", "square kilometer"]),
+        linear("cm²", "square centimetre", Area, 1e-4).with_aliases(&["cm2", "square centimeter"]),
+        linear("mm²", "square millimetre", Area, 1e-6).with_aliases(&["mm2", "square millimeter"]),
+        linear("ha", "hectare", Area, 1e4).with_aliases(&["hectares"]),
+        linear("ac", "acre", Area, 4046.8564224).with_aliases(&["acres"]),
+        linear("in²", "square inch", Area, 6.4516e-4).with_aliases(&[
+            "in2",
+            "sq in",
+            "square inches",
+        ]),
+        linear("ft²", "square foot", Area, 0.09290304).with_aliases(&[
+            "ft2",
+            "sq ft",
+            "square feet",
+        ]),
+        linear("yd²", "square yard", Area, 0.83612736).with_aliases(&[
+            "yd2",
+            "sq yd",
+            "square yards",
+        ]),
+        linear("mi²", "square mile", Area, 2589988.110336).with_aliases(&[
+            "mi2",
+            "sq mi",
+            "square miles",
+        ]),
+        // Speed, in metres per second.
+        linear("m/s", "metre per second", Speed, 1.0).with_aliases(&[
+            "mps",
+            "meter per second",
+            "metres per second",
+            "meters per second",
+        ]),
+        linear("km/h", "kilometre per hour", Speed, 1.0 / 3.6).with_aliases(&[
+            "kph",
+            "kmh",
+            "kilometer per hour",
+            "kilometres per hour",
+            "kilometers per hour",
+        ]),
+        linear("mph", "mile per hour", Speed, 0.44704).with_aliases(&["mi/h", "miles per hour"]),
+        linear("ft/s", "foot per second", Speed, 0.3048).with_aliases(&["fps", "feet per second"]),
+        linear("kn", "knot", Speed, 1852.0 / 3600.0).with_aliases(&["kt", "knots"]),
+        // Pressure, in pascals.
+        linear("Pa", "pascal", Pressure, 1.0).with_aliases(&["pascals"]),
+        linear("hPa", "hectopascal", Pressure, 1e2).with_aliases(&["hectopascals"]),
+        linear("kPa", "kilopascal", Pressure, 1e3).with_aliases(&["kilopascals"]),
+        linear("MPa", "megapascal", Pressure, 1e6).with_aliases(&["megapascals"]),
+        linear("bar", "bar", Pressure, 1e5).with_aliases(&["bars"]),
+        linear("mbar", "millibar", Pressure, 1e2).with_aliases(&["mb", "millibars"]),
+        linear("atm", "standard atmosphere", Pressure, 101325.0)
+            .with_aliases(&["atmosphere", "atmospheres"]),
+        linear("psi", "pound per square inch", Pressure, 6894.757293168361)
+            .with_aliases(&["lbf/in2"]),
+        linear("mmHg", "millimetre of mercury", Pressure, 133.322387415)
+            .with_aliases(&["millimeters of mercury"]),
+        linear("inHg", "inch of mercury", Pressure, 3386.389).with_aliases(&["inches of mercury"]),
+        linear("Torr", "torr", Pressure, 101325.0 / 760.0),
+        // Energy, in joules.
+        linear("J", "joule", Energy, 1.0).with_aliases(&["joules"]),
+        linear("kJ", "kilojoule", Energy, 1e3).with_aliases(&["kilojoules"]),
+        linear("MJ", "megajoule", Energy, 1e6).with_aliases(&["megajoules"]),
+        linear("cal", "calorie", Energy, 4.184).with_aliases(&["calories"]),
+        linear("kcal", "kilocalorie", Energy, 4184.0).with_aliases(&["Cal", "kilocalories"]),
+        linear("Wh", "watt hour", Energy, 3600.0).with_aliases(&["watt hours"]),
+        linear("kWh", "kilowatt hour", Energy, 3.6e6).with_aliases(&["kilowatt hours"]),
+        linear("BTU", "British thermal unit", Energy, 1055.05585262).with_aliases(&["Btu", "btu"]),
+        linear("eV", "electronvolt", Energy, 1.602176634e-19).with_aliases(&["electronvolts"]),
+        linear("erg", "erg", Energy, 1e-7).with_aliases(&["ergs"]),
+        // Force, in newtons.
+        linear("N", "newton", Force, 1.0).with_aliases(&["newtons"]),
+        linear("kN", "kilonewton", Force, 1e3).with_aliases(&["kilonewtons"]),
+        linear("dyn", "dyne", Force, 1e-5).with_aliases(&["dynes"]),
+        linear("kgf", "kilogram-force", Force, 9.80665).with_aliases(&["kilopond"]),
+        linear("lbf", "pound-force", Force, 4.4482216152605).with_aliases(&["pounds-force"]),
+        // Power, in watts.
+        linear("W", "watt", Power, 1.0).with_aliases(&["watts"]),
+        linear("kW", "kilowatt", Power, 1e3).with_aliases(&["kilowatts"]),
+        linear("MW", "megawatt", Power, 1e6).with_aliases(&["megawatts"]),
+        linear("hp", "horsepower", Power, 745.6998715822702),
+        // Time, in seconds.
+        linear("s", "second", Time, 1.0).with_aliases(&["sec", "secs", "seconds"]),
+        linear("ms", "millisecond", Time, 1e-3).with_aliases(&["milliseconds"]),
+        linear("µs", "microsecond", Time, 1e-6).with_aliases(&["us", "microseconds"]),
+        linear("ns", "nanosecond", Time, 1e-9).with_aliases(&["nanoseconds"]),
+        linear("min", "minute", Time, 60.0).with_aliases(&["mins", "minutes"]),
+        linear("h", "hour", Time, 3600.0).with_aliases(&["hr", "hrs", "hours"]),
+        linear("d", "day", Time, 86400.0).with_aliases(&["days"]),
+        linear("wk", "week", Time, 604800.0).with_aliases(&["weeks"]),
+        linear("yr", "Julian year", Time, 31557600.0).with_aliases(&["year", "years"]),
+        // Data size, in bytes.
+        linear("B", "byte", DataSize, 1.0).with_aliases(&["bytes"]),
+        linear("bit", "bit", DataSize, 0.125).with_aliases(&["bits"]),
+        linear("kB", "kilobyte", DataSize, 1e3).with_aliases(&["KB", "kilobytes"]),
+        linear("MB", "megabyte", DataSize, 1e6).with_aliases(&["megabytes"]),
+        linear("GB", "gigabyte", DataSize, 1e9).with_aliases(&["gigabytes"]),
+        linear("TB", "terabyte", DataSize, 1e12).with_aliases(&["terabytes"]),
+        linear("KiB", "kibibyte", DataSize, 1024.0).with_aliases(&["kibibytes"]),
+        linear("MiB", "mebibyte", DataSize, 1048576.0).with_aliases(&["mebibytes"]),
+        linear("GiB", "gibibyte", DataSize, 1073741824.0).with_aliases(&["gibibytes"]),
+        linear("TiB", "tebibyte", DataSize, 1099511627776.0).with_aliases(&["tebibytes"]),
+        linear("kbit", "kilobit", DataSize, 125.0).with_aliases(&["kb", "kilobits"]),
+        linear("Mbit", "megabit", DataSize, 125e3).with_aliases(&["Mb", "megabits"]),
+        linear("Gbit", "gigabit", DataSize, 125e6).with_aliases(&["Gb", "gigabits"]),
+        // Temperature, in kelvin.
+        temperature_unit(Scale::Celsius, &["C", "c", "°c", "degC"]),
+        temperature_unit(Scale::Fahrenheit, &["F", "f", "°f", "degF"]),
+        temperature_unit(Scale::Kelvin, &["k", "°K"]),
+        temperature_unit(Scale::Rankine, &["R", "r", "°r", "degR"]),
+        temperature_unit(Scale::Reaumur, &["Ré", "Re", "°Re", "degRe", "reaumur"]),
+        temperature_unit(Scale::Delisle, &["De", "degDe"]),
+        // Not "N", which is the newton of force.
+        temperature_unit(Scale::Newton, &["degN"]),
+        temperature_unit(
+            Scale::Romer,
+            &["Rø", "Ro", "°Ro", "degRo", "romer", "roemer"],
+        ),
+    ]
+}
+
+fn temperature_unit(scale: Scale, aliases: &[&str]) -> Unit {
+    Unit::new(
+        scale.symbol(),
+        scale.name(),
+        Dimension::Temperature,
+        Conversion::Temperature(scale),
+    )
+    .with_aliases(aliases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(expected: f64, actual: f64) {
+        let diff = (expected - actual).abs();
+        assert!(
+            diff <= 1e-9 * expected.abs().max(1.0),
+            "expected={} actual={} diff={}",
+            expected,
+            actual,
+            diff
+        );
+    }
+
+    #[test]
+    fn converts_within_each_dimension() {
+        let cases = [
+            (5.0, "km", "mi", 3.106855961),
+            (1.0, "ft", "in", 12.0),
+            (1.0, "lb", "g", 453.59237),
+            (1.0, "gal", "L", 3.785411784),
+            (1.0, "acre", "m2", 4046.8564224),
+            (100.0, "km/h", "m/s", 27.777777778),
+            (1.0, "atm", "psi", 14.695948775),
+            (1.0, "kWh", "MJ", 3.6),
+            (2.0, "h", "min", 120.0),
+            (1.0, "GiB", "MB", 1073.741824),
+            (212.0, "°F", "°C", 100.0),
+        ];
+
+        for (value, from, to, expected) in cases {
+            let result = convert(value, from, to).expect("conversion should succeed");
+            assert_close(expected, result);
+        }
+    }
+
+    #[test]
+    fn refuses_to_convert_across_dimensions() {
+        let result = convert(1.0, "km", "kg");
+        assert_eq!(
+            result,
+            Err(ConversionError::IncompatibleUnits {
+                from: "km".into(),
+                from_dimension: Dimension::Length,
+                to: "kg".into(),
+                to_dimension: Dimension::Mass,
+            })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Cannot convert km (length, L) to kg (mass, M)."
+        );
+    }
+
+    #[test]
+    fn converts_compound_units() {
+        let cases = [
+            (10.0, "m/s", "km/h", 36.0),
+            (1.0, "kg*m/s^2", "N", 1.0),
+            (1.0, "kg·m·s⁻²", "N", 1.0),
+            (1.0, "W/(m*K)", "BTU/(h*ft*°F)", 0.577789316),
+            (1.0, "kW*h", "MJ", 3.6),
+            (1.0, "N*m", "J", 1.0),
+            (1.0, "lbf/in^2", "psi", 1.0),
+            (2.0, "m³", "L", 2000.0),
+            (60.0, "1/min", "1/s", 1.0),
+        ];
+
+        for (value, from, to, expected) in cases {
+            let result = convert(value, from, to)
+                .unwrap_or_else(|error| panic!("{} -> {}: {}", from, to, error));
+            assert_close(expected, result);
+        }
+    }
+
+    #[test]
+    fn compound_units_get_named_dimensions_when_one_fits() {
+        let registry = UnitRegistry::builtin();
+        assert_eq!(
+            registry.parse_unit("kg*m/s^2").unwrap().dimension,
+            Dimension::Force
+        );
+        assert_eq!(
+            registry.parse_unit("J/s").unwrap().dimension,
+            Dimension::Power
+        );
+
+        let conductivity = registry.parse_unit("W/(m*K)").unwrap();
+        assert_eq!(
+            conductivity.dimension,
+            Dimension::Derived(DimensionVector::from_exponents(&[
+                (BaseDimension::Length, 1),
+                (BaseDimension::Mass, 1),
+                (BaseDimension::Time, -3),
+                (BaseDimension::Temperature, -1),
+            ]))
+        );
+        assert_eq!(conductivity.symbol, "W/(m*K)");
+    }
+
+    #[test]
+    fn mismatched_compound_units_show_both_dimension_vectors() {
+        let error = convert(1.0, "W/(m*K)", "m/s").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Cannot convert W/(m*K) (L·M·T⁻³·Θ⁻¹) to m/s (speed, L·T⁻¹)."
+        );
+    }
+
+    #[test]
+    fn reports_bad_compound_units() {
+        assert_eq!(
+            convert(1.0, "kg*furlong", "kg"),
+            Err(ConversionError::UnknownUnit("furlong".into()))
+        );
+        assert!(matches!(
+            convert(1.0, "m/(s", "m/s"),
+            Err(ConversionError::MalformedUnit { .. })
+        ));
+    }
+
+    #[test]
+    fn reports_unknown_units() {
+        let result = convert(1.0, "furlong", "m");
+        assert_eq!(result, Err(ConversionError::UnknownUnit("furlong".into())));
+    }
+
+    #[test]
+    fn temperature_scales_keep_their_exact_results() {
+        assert_eq!(convert(0.0, "celsius", "fahrenheit"), Ok(32.0));
+        assert_eq!(convert(32.0, "F", "C"), Ok(0.0));
+    }
+
+    #[test]
+    fn deltas_ignore_temperature_offsets() {
+        assert_eq!(convert_delta(10.0, "°C", "°F"), Ok(18.0));
+        assert_eq!(convert_delta(-300.0, "K", "°C"), Ok(-300.0));
+        assert_close(3.106855961, convert_delta(5.0, "km", "mi").unwrap());
+
+        let mut registry = UnitRegistry::default();
+        registry.register(Unit::new(
+            "°X",
+            "example degree",
+            Dimension::Temperature,
+            Conversion::Affine {
+                factor: 0.5,
+                offset: 100.0,
+            },
+        ));
+        assert_close(5.0, registry.convert_delta(10.0, "°X", "K").unwrap());
+    }
+
+    #[test]
+    fn temperature_scales_still_check_absolute_zero() {
+        let result = convert(-500.0, "C", "K");
+        assert!(matches!(
+            result,
+            Err(ConversionError::BelowAbsoluteZero { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_results_that_overflow() {
+        assert_eq!(convert(1e308, "C", "F"), Err(ConversionError::NotFinite));
+        assert_eq!(convert(1e308, "km", "mm"), Err(ConversionError::NotFinite));
+        assert_eq!(
+            convert_delta(1e308, "C", "F"),
+            Err(ConversionError::NotFinite)
+        );
+    }
+
+    #[test]
+    fn exact_symbols_win_over_case_insensitive_matches() {
+        let registry = UnitRegistry::builtin();
+        assert_eq!(registry.get("MB").unwrap().name, "megabyte");
+        assert_eq!(registry.get("Mb").unwrap().name, "megabit");
+        assert_eq!(registry.get("mb").unwrap().name, "millibar");
+        assert_eq!(registry.get("KILOMETRES").unwrap().symbol, "km");
+    }
+
+    #[test]
+    fn quantities_convert_and_display_with_precision() {
+        let registry = UnitRegistry::builtin();
+        let distance = Quantity::new(5.0, registry.get("km").unwrap().clone());
+        let miles = distance.to(registry.get("mi").unwrap()).unwrap();
+
+        assert_eq!(format!("{:.2}", miles), "3.11 mi");
+        assert_eq!(distance.to_string(), "5 km");
+    }
+
+    #[test]
+    fn affine_units_apply_offset_before_factor() {
+        let mut registry = UnitRegistry::default();
+        registry.register(Unit::new(
+            "°X",
+            "example degree",
+            Dimension::Temperature,
+            Conversion::Affine {
+                factor: 0.5,
+                offset: 100.0,
+            },
+        ));
+
+        // (0 + 100) * 0.5 = 50 K
+        assert_close(50.0, registry.convert(0.0, "°X", "K").unwrap());
+        assert_close(0.0, registry.convert(50.0, "K", "°X").unwrap());
+    }
+
+    #[test]
+    fn scaled_conversions_build_on_their_reference() {
+        let celsius = UnitRegistry::builtin().get("°C").unwrap();
+        let scaled = celsius.conversion.scaled(0.8, -10.0);
+        let unit = Unit::new("brew", "brew", Dimension::Temperature, scaled);
+
+        assert_close(273.15, unit.to_base(10.0).unwrap());
+        assert_eq!(
+            Conversion::Linear(1000.0).scaled(2.0, 0.0),
+            Conversion::Linear(2000.0)
+        );
+    }
+
+    #[test]
+    fn parses_dimension_names() {
+        assert_eq!("data_size".parse::<Dimension>(), Ok(Dimension::DataSize));
+        assert_eq!("force".parse::<Dimension>(), Ok(Dimension::Force));
+        assert_eq!("Length".parse::<Dimension>(), Ok(Dimension::Length));
+        assert!("happiness".parse::<Dimension>().is_err());
+    }
+
+    #[test]
+    fn registering_a_symbol_twice_replaces_the_unit() {
+        let mut registry = UnitRegistry::empty();
+        registry.register(Unit::new(
+            "u",
+            "first",
+            Dimension::Length,
+            Conversion::Linear(1.0),
+        ));
+        registry.register(Unit::new(
+            "u",
+            "second",
+            Dimension::Length,
+            Conversion::Linear(2.0),
+        ));
+
+        assert_eq!(registry.units().len(), 1);
+        assert_eq!(registry.get("u").unwrap().name, "second");
+    }
+
+    #[test]
+    fn builtin_symbols_are_unique() {
+        let units = UnitRegistry::builtin().units();
+        for (i, unit) in units.iter().enumerate() {
+            for other in &units[i + 1..] {
+                assert!(
+                    !other.matches(&unit.symbol),
+                    "{} and {} share a spelling",
+                    unit.name,
+                    other.name
+                );
+            }
+        }
+    }
+}
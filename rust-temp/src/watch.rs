@@ -0,0 +1,206 @@
+//! The `watch` subcommand: converts readings as they arrive and raises
+//! alerts when they cross thresholds.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, Write},
+    path::PathBuf,
+    process::Command,
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use rust_convert::{
+    Alert, AlertState, Calibration, NumberFormat, Scale, SensorError, Temperature,
+    TemperatureDelta, Threshold, ThresholdMonitor, read_sensor,
+};
+
+use crate::{CliError, calibrate_temperature, cli::WatchArgs, messages};
+
+/// Where readings are polled from. Without one, values are read from
+/// stdin one per line as they come.
+enum Source {
+    /// A sysfs temperature file.
+    Sensor(PathBuf),
+    /// A file holding one value.
+    File(PathBuf),
+}
+
+/// Where alerts go. With neither a log nor a command they are printed to
+/// stderr.
+struct AlertSinks {
+    log: Option<File>,
+    command: Option<String>,
+}
+
+pub fn run(
+    args: WatchArgs,
+    number_format: NumberFormat,
+    calibration: Option<&Calibration>,
+) -> Result<(), CliError> {
+    let locale = number_format.locale;
+    let from: Scale = args.from.parse()?;
+    let to: Scale = args.to.parse()?;
+
+    let mut thresholds = Vec::new();
+    for value in &args.above {
+        thresholds.push(Threshold::above(Temperature::parse(
+            &locale.delocalize(value),
+            to,
+        )?));
+    }
+    for value in &args.below {
+        thresholds.push(Threshold::below(Temperature::parse(
+            &locale.delocalize(value),
+            to,
+        )?));
+    }
+    let hysteresis = TemperatureDelta::parse(&locale.delocalize(&args.hysteresis), to)?;
+    let mut monitor = ThresholdMonitor::new(thresholds, hysteresis);
+
+    let mut sinks = AlertSinks {
+        log: match &args.alert_log {
+            Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+            None => None,
+        },
+        command: args.alert_command,
+    };
+    let source = match (args.sensor, args.file) {
+        (Some(path), _) => Some(Source::Sensor(path)),
+        (None, Some(path)) => Some(Source::File(path)),
+        (None, None) => None,
+    };
+
+    let mut fired = 0;
+    let mut on_reading = |temperature: Temperature| -> Result<(), CliError> {
+        let reading = temperature.to(to);
+        println!("{}", number_format.format(reading.value()));
+        for alert in monitor.check(reading) {
+            if alert.state == AlertState::Triggered {
+                fired += 1;
+            }
+            sinks.send(&alert, number_format)?;
+        }
+        Ok(())
+    };
+
+    let count = args.count.unwrap_or(usize::MAX);
+    match source {
+        None => {
+            let lines = io::stdin().lock().lines().enumerate();
+            let values =
+                lines.filter(|(_, line)| !line.as_ref().is_ok_and(|line| line.trim().is_empty()));
+            for (index, line) in values.take(count) {
+                let reading = Temperature::parse(&locale.delocalize(&line?), from)
+                    .and_then(|temperature| calibrate_temperature(temperature, calibration));
+                match reading {
+                    Ok(temperature) => on_reading(temperature)?,
+                    Err(error) => {
+                        eprintln!("{}", messages::skipped_line(index + 1, &error, locale))
+                    }
+                }
+            }
+        }
+        Some(source) => {
+            for taken in 0..count {
+                if taken > 0 {
+                    thread::sleep(args.interval);
+                }
+                match source.read(from, number_format, calibration) {
+                    Ok(temperature) => on_reading(temperature)?,
+                    Err(message) => eprintln!("{}", message),
+                }
+            }
+        }
+    }
+
+    match fired {
+        0 => Ok(()),
+        count => Err(CliError::AlertsFired(count)),
+    }
+}
+
+impl Source {
+    /// Takes a reading from a sensor or file, or the warning to print if
+    /// there isn't a valid one right now.
+    fn read(
+        &self,
+        from: Scale,
+        number_format: NumberFormat,
+        calibration: Option<&Calibration>,
+    ) -> Result<Temperature, String> {
+        let locale = number_format.locale;
+        let (path, temperature) = match self {
+            Source::Sensor(path) => {
+                let temperature =
+                    read_sensor(path).map_err(|error| messages::skipped_reading(&error, locale))?;
+                (path, temperature)
+            }
+            Source::File(path) => {
+                let text = fs::read_to_string(path).map_err(|error| {
+                    let error = SensorError::Io {
+                        path: path.clone(),
+                        error,
+                    };
+                    messages::skipped_reading(&error, locale)
+                })?;
+                let temperature = Temperature::parse(&locale.delocalize(text.trim()), from)
+                    .map_err(|error| messages::skipped_value(path, &error, locale))?;
+                (path, temperature)
+            }
+        };
+        calibrate_temperature(temperature, calibration)
+            .map_err(|error| messages::skipped_value(path, &error, locale))
+    }
+}
+
+impl AlertSinks {
+    fn send(&mut self, alert: &Alert, number_format: NumberFormat) -> Result<(), CliError> {
+        let message = messages::alert(alert, number_format);
+        if self.log.is_none() && self.command.is_none() {
+            eprintln!("{}", message);
+        }
+
+        if let Some(log) = &mut self.log {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs());
+            writeln!(log, "{} {}", now, message)?;
+        }
+
+        // The hook's exit status is its own business; only failing to start
+        // it ends the watch.
+        if let Some(command) = &self.command {
+            let state = match alert.state {
+                AlertState::Triggered => "triggered",
+                AlertState::Cleared => "cleared",
+            };
+            shell(command)
+                .env("RUST_CONVERT_ALERT", state)
+                .env("RUST_CONVERT_SIDE", alert.threshold.side.name())
+                .env("RUST_CONVERT_VALUE", alert.reading.value().to_string())
+                .env(
+                    "RUST_CONVERT_LIMIT",
+                    alert.threshold.limit.value().to_string(),
+                )
+                .env("RUST_CONVERT_SCALE", alert.reading.scale().name())
+                .env("RUST_CONVERT_MESSAGE", &message)
+                .status()?;
+        }
+        Ok(())
+    }
+}
+
+fn shell(command: &str) -> Command {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell.arg(command);
+    shell
+}
@@ -0,0 +1,437 @@
+use std::fmt;
+
+use crate::{
+    error::ConversionError,
+    temperature::{Scale, Temperature},
+    units::{Quantity, UnitRegistry},
+};
+
+/// Constants of the Magnus formula for saturation vapour pressure over
+/// water, as given by Alduchov and Eskridge: `a` is dimensionless and `b` is
+/// in celsius.
+const MAGNUS_A: f64 = 17.62;
+const MAGNUS_B: f64 = 243.12;
+
+/// The hottest air, in °F, the heat index is worked out for: a little above
+/// the highest temperature on record, 134 °F. The regression's powers of
+/// the temperature are meaningless beyond it and overflow for huge values.
+const HEAT_INDEX_MAX: f64 = 135.0;
+
+/// A derived quantity along with any reasons to doubt it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate<T = Temperature> {
+    pub value: T,
+    /// The ways the inputs fall outside the range the formula was fitted
+    /// for. The value is still computed, but may be far off.
+    pub warnings: Vec<RangeWarning>,
+}
+
+/// A formula used outside the conditions it is meant for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeWarning {
+    /// The heat index is only meaningful from 80 °F up.
+    HeatIndexTooCold,
+    /// Wind chill is only defined at 50 °F and below, so the air
+    /// temperature is given instead.
+    WindChillTooWarm,
+    /// Wind chill is only defined for winds of 3 mph and above, so the air
+    /// temperature is given instead.
+    WindChillTooCalm,
+    /// The Magnus formula is fitted from -45 °C to 60 °C.
+    DewPointOutOfRange,
+    /// Humidex is only reported from 20 °C up.
+    HumidexTooCold,
+    /// The barometric formula assumes the troposphere's steady cooling,
+    /// which stops at 11 km.
+    AboveTroposphere,
+}
+
+impl fmt::Display for RangeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RangeWarning::HeatIndexTooCold => {
+                "The heat index is only meaningful from 80 °F (26.7 °C) up."
+            }
+            RangeWarning::WindChillTooWarm => {
+                "Wind chill is only defined at 50 °F (10 °C) and below; showing the air temperature."
+            }
+            RangeWarning::WindChillTooCalm => {
+                "Wind chill is only defined for winds of 3 mph (4.8 km/h) and above; showing the air temperature."
+            }
+            RangeWarning::DewPointOutOfRange => {
+                "The dew point formula is only accurate from -45 °C to 60 °C."
+            }
+            RangeWarning::HumidexTooCold => "Humidex is only meaningful from 20 °C up.",
+            RangeWarning::AboveTroposphere => {
+                "The barometric formula is only accurate up to 11 km (36,089 ft)."
+            }
+        })
+    }
+}
+
+/// Reasons a derived quantity could not be computed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WeatherError {
+    /// Relative humidity must be a percentage, and above zero where the dew
+    /// point is needed.
+    InvalidHumidity(f64),
+    /// Wind speed must not be negative.
+    InvalidWindSpeed(f64),
+    /// Pressure must be above zero.
+    InvalidPressure(f64),
+    /// The altitude, in metres, is so high the standard atmosphere has no
+    /// air left.
+    InvalidAltitude(f64),
+    /// The air is too hot for the heat index. Holds the air temperature.
+    HeatIndexOutOfRange(Temperature),
+    /// Water only boils between its triple and critical points. Holds the
+    /// temperature asked about or worked out, in celsius.
+    NoBoilingPoint(f64),
+    /// The wind speed is not a speed, or the result is not a temperature.
+    Conversion(ConversionError),
+}
+
+impl fmt::Display for WeatherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeatherError::InvalidHumidity(humidity) => write!(
+                f,
+                "Relative humidity must be between 0 and 100%, got {}.",
+                humidity
+            ),
+            WeatherError::InvalidWindSpeed(speed) => {
+                write!(f, "Wind speed must not be negative, got {}.", speed)
+            }
+            WeatherError::InvalidPressure(pressure) => {
+                write!(f, "Pressure must be above zero, got {}.", pressure)
+            }
+            WeatherError::InvalidAltitude(altitude) => write!(
+                f,
+                "The standard atmosphere has no air left at an altitude of {} m.",
+                altitude
+            ),
+            WeatherError::HeatIndexOutOfRange(temperature) => write!(
+                f,
+                "The heat index is only worked out up to 135 °F (57.2 °C), got {}.",
+                temperature
+            ),
+            WeatherError::NoBoilingPoint(celsius) => write!(
+                f,
+                "Water can't boil at {:.2} °C, only between its triple point (0.01 °C) and critical point (373.95 °C).",
+                celsius
+            ),
+            WeatherError::Conversion(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for WeatherError {}
+
+impl From<ConversionError> for WeatherError {
+    fn from(error: ConversionError) -> Self {
+        WeatherError::Conversion(error)
+    }
+}
+
+/// The NWS heat index: how hot it feels at a temperature and relative
+/// humidity (in percent), on the temperature's scale.
+///
+/// Follows the National Weather Service's procedure: Steadman's simple
+/// formula, switching to the Rothfusz regression from 80 °F up, with its
+/// adjustments for very dry and very humid air. Air hotter than 135 °F is
+/// rejected.
+pub fn heat_index(
+    temperature: Temperature,
+    relative_humidity: f64,
+) -> Result<Estimate, WeatherError> {
+    check_humidity(relative_humidity, true)?;
+    let t = temperature.to(Scale::Fahrenheit).value();
+    if t > HEAT_INDEX_MAX {
+        return Err(WeatherError::HeatIndexOutOfRange(temperature));
+    }
+    let rh = relative_humidity;
+
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    let index = if (simple + t) / 2.0 < 80.0 {
+        simple
+    } else {
+        let mut index = -42.379 + 2.04901523 * t + 10.14333127 * rh
+            - 0.22475541 * t * rh
+            - 0.00683783 * t * t
+            - 0.05481717 * rh * rh
+            + 0.00122874 * t * t * rh
+            + 0.00085282 * t * rh * rh
+            - 0.00000199 * t * t * rh * rh;
+        if rh < 13.0 && (80.0..=112.0).contains(&t) {
+            index -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+        } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+            index += (rh - 85.0) / 10.0 * ((87.0 - t) / 5.0);
+        }
+        index
+    };
+
+    let mut warnings = Vec::new();
+    if t < 80.0 {
+        warnings.push(RangeWarning::HeatIndexTooCold);
+    }
+    estimate(index, Scale::Fahrenheit, temperature.scale(), warnings)
+}
+
+/// The NWS wind chill: how cold it feels at a temperature and wind speed,
+/// on the temperature's scale. The wind speed may be in any unit of speed.
+/// Above 50 °F or below 3 mph, where the formula is undefined and can come
+/// out warmer than the air, the air temperature is returned with a warning.
+pub fn wind_chill(
+    temperature: Temperature,
+    wind_speed: &Quantity,
+) -> Result<Estimate, WeatherError> {
+    let mph = UnitRegistry::builtin().get("mph")?;
+    let v = wind_speed.to(mph)?.value;
+    if !v.is_finite() || v < 0.0 {
+        return Err(WeatherError::InvalidWindSpeed(wind_speed.value));
+    }
+    let t = temperature.to(Scale::Fahrenheit).value();
+
+    let wind = v.powf(0.16);
+    let chill = 35.74 + 0.6215 * t - 35.75 * wind + 0.4275 * t * wind;
+
+    let mut warnings = Vec::new();
+    if t > 50.0 {
+        warnings.push(RangeWarning::WindChillTooWarm);
+    }
+    if v < 3.0 {
+        warnings.push(RangeWarning::WindChillTooCalm);
+    }
+    if !warnings.is_empty() {
+        return Ok(Estimate {
+            value: temperature,
+            warnings,
+        });
+    }
+    estimate(chill, Scale::Fahrenheit, temperature.scale(), warnings)
+}
+
+/// The dew point at a temperature and relative humidity (in percent), from
+/// the Magnus formula, on the temperature's scale.
+pub fn dew_point(
+    temperature: Temperature,
+    relative_humidity: f64,
+) -> Result<Estimate, WeatherError> {
+    check_humidity(relative_humidity, false)?;
+    let t = temperature.to(Scale::Celsius).value();
+
+    let gamma = (relative_humidity / 100.0).ln() + MAGNUS_A * t / (MAGNUS_B + t);
+    let dew_point = MAGNUS_B * gamma / (MAGNUS_A - gamma);
+
+    let mut warnings = Vec::new();
+    if !(-45.0..=60.0).contains(&t) {
+        warnings.push(RangeWarning::DewPointOutOfRange);
+    }
+    estimate(dew_point, Scale::Celsius, temperature.scale(), warnings)
+}
+
+/// The Canadian humidex at a temperature and relative humidity (in percent),
+/// on the temperature's scale. Environment Canada reports it as a plain
+/// number read like degrees celsius.
+pub fn humidex(temperature: Temperature, relative_humidity: f64) -> Result<Estimate, WeatherError> {
+    let Estimate {
+        value: dew_point,
+        mut warnings,
+    } = dew_point(temperature, relative_humidity)?;
+    let t = temperature.to(Scale::Celsius).value();
+    let dew_point = dew_point.to(Scale::Kelvin).value();
+
+    // Vapour pressure in hectopascals, as Environment Canada defines it.
+    let vapour_pressure = 6.11 * (5417.7530 * (1.0 / 273.16 - 1.0 / dew_point)).exp();
+    let humidex = t + 0.5555 * (vapour_pressure - 10.0);
+
+    if t < 20.0 {
+        warnings.push(RangeWarning::HumidexTooCold);
+    }
+    estimate(humidex, Scale::Celsius, temperature.scale(), warnings)
+}
+
+fn check_humidity(relative_humidity: f64, allow_zero: bool) -> Result<(), WeatherError> {
+    let in_range = if allow_zero {
+        (0.0..=100.0).contains(&relative_humidity)
+    } else {
+        relative_humidity > 0.0 && relative_humidity <= 100.0
+    };
+    if in_range {
+        Ok(())
+    } else {
+        Err(WeatherError::InvalidHumidity(relative_humidity))
+    }
+}
+
+/// Wraps a value computed on `computed_in` as a temperature on `scale`.
+fn estimate(
+    value: f64,
+    computed_in: Scale,
+    scale: Scale,
+    warnings: Vec<RangeWarning>,
+) -> Result<Estimate, WeatherError> {
+    Ok(Estimate {
+        value: Temperature::new(value, computed_in)?.to(scale),
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(expected: f64, actual: f64, tolerance: f64) {
+        let diff = (expected - actual).abs();
+        assert!(
+            diff <= tolerance,
+            "expected={} actual={} diff={}",
+            expected,
+            actual,
+            diff
+        );
+    }
+
+    fn temp(value: f64, scale: Scale) -> Temperature {
+        Temperature::new(value, scale).expect("temperature should be valid")
+    }
+
+    fn speed(value: f64, unit: &str) -> Quantity {
+        Quantity::new(value, UnitRegistry::builtin().get(unit).unwrap().clone())
+    }
+
+    #[test]
+    fn heat_index_matches_the_nws_table() {
+        // Values from the NWS heat index chart.
+        let cases = [(90.0, 70.0, 106.0), (96.0, 65.0, 121.0), (80.0, 80.0, 84.0)];
+        for (t, rh, expected) in cases {
+            let index = heat_index(temp(t, Scale::Fahrenheit), rh).unwrap();
+            assert_close(expected, index.value.value(), 0.5);
+            assert!(index.warnings.is_empty());
+        }
+    }
+
+    #[test]
+    fn heat_index_applies_the_humidity_adjustments() {
+        // Dry air lowers the regression's value, very humid air raises it.
+        let dry = heat_index(temp(100.0, Scale::Fahrenheit), 10.0).unwrap();
+        assert_close(94.1, dry.value.value(), 0.05);
+        let humid = heat_index(temp(84.0, Scale::Fahrenheit), 95.0).unwrap();
+        assert_close(100.9, humid.value.value(), 0.05);
+    }
+
+    #[test]
+    fn heat_index_works_on_any_scale_and_warns_when_cold() {
+        let index = heat_index(temp(35.0, Scale::Celsius), 50.0).unwrap();
+        assert_eq!(index.value.scale(), Scale::Celsius);
+        assert_close(40.7, index.value.value(), 0.05);
+
+        let cool = heat_index(temp(20.0, Scale::Celsius), 50.0).unwrap();
+        assert_eq!(cool.warnings, vec![RangeWarning::HeatIndexTooCold]);
+    }
+
+    #[test]
+    fn heat_index_rejects_air_too_hot_for_the_formula() {
+        assert!(heat_index(temp(135.0, Scale::Fahrenheit), 10.0).is_ok());
+        for t in [60.0, 1e300] {
+            assert_eq!(
+                heat_index(temp(t, Scale::Celsius), 50.0),
+                Err(WeatherError::HeatIndexOutOfRange(temp(t, Scale::Celsius)))
+            );
+        }
+    }
+
+    #[test]
+    fn wind_chill_matches_the_nws_table() {
+        let cases = [(0.0, 15.0, -19.0), (-20.0, 30.0, -53.0), (40.0, 5.0, 36.0)];
+        for (t, v, expected) in cases {
+            let chill = wind_chill(temp(t, Scale::Fahrenheit), &speed(v, "mph")).unwrap();
+            assert_close(expected, chill.value.value(), 0.5);
+            assert!(chill.warnings.is_empty());
+        }
+    }
+
+    #[test]
+    fn wind_chill_accepts_any_speed_unit() {
+        let chill = wind_chill(temp(-10.0, Scale::Celsius), &speed(20.0, "km/h")).unwrap();
+        assert_close(-17.9, chill.value.value(), 0.1);
+    }
+
+    #[test]
+    fn wind_chill_falls_back_to_the_air_temperature_outside_its_range() {
+        let chill = wind_chill(temp(20.0, Scale::Celsius), &speed(1.0, "mph")).unwrap();
+        assert_eq!(chill.value, temp(20.0, Scale::Celsius));
+        assert_eq!(
+            chill.warnings,
+            vec![
+                RangeWarning::WindChillTooWarm,
+                RangeWarning::WindChillTooCalm
+            ]
+        );
+
+        // The formula would give 21.7 °C here, warmer than the air.
+        let calm = wind_chill(temp(20.0, Scale::Celsius), &speed(2.0, "km/h")).unwrap();
+        assert_eq!(calm.value, temp(20.0, Scale::Celsius));
+    }
+
+    #[test]
+    fn wind_chill_rejects_bad_wind_speeds() {
+        let cold = temp(0.0, Scale::Celsius);
+        assert_eq!(
+            wind_chill(cold, &speed(-5.0, "km/h")),
+            Err(WeatherError::InvalidWindSpeed(-5.0))
+        );
+        assert!(matches!(
+            wind_chill(cold, &speed(5.0, "kg")),
+            Err(WeatherError::Conversion(
+                ConversionError::IncompatibleUnits { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn dew_point_uses_the_magnus_formula() {
+        let cases = [(20.0, 50.0, 9.26), (30.0, 80.0, 26.17), (25.0, 100.0, 25.0)];
+        for (t, rh, expected) in cases {
+            let dew = dew_point(temp(t, Scale::Celsius), rh).unwrap();
+            assert_close(expected, dew.value.value(), 0.01);
+        }
+
+        let fahrenheit = dew_point(temp(68.0, Scale::Fahrenheit), 50.0).unwrap();
+        assert_close(48.66, fahrenheit.value.value(), 0.01);
+    }
+
+    #[test]
+    fn dew_point_rejects_impossible_humidity_and_warns_out_of_range() {
+        let mild = temp(20.0, Scale::Celsius);
+        for rh in [0.0, -5.0, 101.0, f64::NAN] {
+            assert!(matches!(
+                dew_point(mild, rh),
+                Err(WeatherError::InvalidHumidity(_))
+            ));
+        }
+
+        let frigid = dew_point(temp(-50.0, Scale::Celsius), 50.0).unwrap();
+        assert_eq!(frigid.warnings, vec![RangeWarning::DewPointOutOfRange]);
+    }
+
+    #[test]
+    fn humidex_matches_environment_canada() {
+        // 30 °C with a dew point of 15 °C is a humidex of 34.
+        let index = humidex(temp(30.0, Scale::Celsius), 39.8).unwrap();
+        assert_eq!(index.value.scale(), Scale::Celsius);
+        assert_close(34.0, index.value.value(), 0.5);
+        assert!(index.warnings.is_empty());
+
+        let fahrenheit = humidex(temp(86.0, Scale::Fahrenheit), 39.8).unwrap();
+        assert_eq!(fahrenheit.value.scale(), Scale::Fahrenheit);
+        assert_close(
+            index.value.value(),
+            fahrenheit.value.to(Scale::Celsius).value(),
+            1e-9,
+        );
+
+        let cool = humidex(temp(15.0, Scale::Celsius), 50.0).unwrap();
+        assert_eq!(cool.warnings, vec![RangeWarning::HumidexTooCold]);
+    }
+}
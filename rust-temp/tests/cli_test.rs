@@ -1,7 +1,19 @@
-use std::process::{Command, ExitStatus};
+use std::io::Write;
+use std::process::{Command, ExitStatus, Stdio};
+
+/// The converter, with the caller's locale cleared so output doesn't depend
+/// on where the tests run.
+fn converter() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_rust-convert"));
+    command
+        .env_remove("LC_ALL")
+        .env_remove("LC_NUMERIC")
+        .env_remove("LANG");
+    command
+}
 
 fn run_command(args: &[&str]) -> (ExitStatus, String, String) {
-    let output = Command::new(env!("CARGO_BIN_EXE_rust-convert"))
+    let output = converter()
         .args(args)
         .output()
         .expect("command should execute successfully");
@@ -75,3 +87,1419 @@ fn fails_when_given_a_non_f64_value() {
     assert!(!status.success());
     assert!(stderr.contains("Problem parsing"));
 }
+
+#[test]
+fn converts_from_kelvin_to_celsius() {
+    let (status, stdout, _stderr) = run_command(&["-k", "273.15"]);
+
+    assert!(status.success());
+    assert!(stdout.contains("0.00 degrees celsius"));
+}
+
+#[test]
+fn converts_from_rankine_to_fahrenheit_with_long_flag() {
+    let (status, stdout, _stderr) = run_command(&["--rankine", "491.67"]);
+
+    assert!(status.success());
+    assert!(stdout.contains("32.00 degrees fahrenheit"));
+}
+
+#[test]
+fn fails_when_value_is_below_absolute_zero() {
+    let (status, stdout, stderr) = run_command(&["-c", "-500"]);
+
+    assert_eq!(status.code(), Some(5));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("below absolute zero"));
+}
+
+#[test]
+fn fails_when_value_is_not_finite() {
+    let (status, _stdout, stderr) = run_command(&["-f", "NaN"]);
+
+    assert_eq!(status.code(), Some(4));
+    assert!(stderr.contains("finite"));
+}
+
+#[test]
+fn fails_when_the_result_is_not_finite() {
+    let (status, stdout, _stderr) = run_command(&["-c", "1e308", "--format", "json"]);
+    assert_eq!(status.code(), Some(4));
+    assert!(stdout.is_empty());
+
+    let (status, stdout, _stderr) = run_command(&["1e308 km to mm"]);
+    assert_eq!(status.code(), Some(4));
+    assert!(stdout.is_empty());
+
+    let (status, stdout, _stderr) = run_command(&[
+        "table", "--from", "1e308", "--to", "1e308", "--unit", "C", "--format", "json",
+    ]);
+    assert_eq!(status.code(), Some(4));
+    assert!(stdout.is_empty());
+}
+
+#[test]
+fn usage_and_parse_errors_use_distinct_exit_codes() {
+    let (usage_status, _stdout, _stderr) = run_command(&[]);
+    let (parse_status, _stdout, _stderr) = run_command(&["-c", "zero"]);
+
+    assert_eq!(usage_status.code(), Some(2));
+    assert_eq!(parse_status.code(), Some(3));
+}
+
+#[test]
+fn prints_a_markdown_table_across_all_scales() {
+    let (status, stdout, _stderr) = run_command(&[
+        "table", "--from", "-40", "--to", "100", "--step", "70", "--scale", "c", "--format",
+        "markdown",
+    ]);
+
+    assert!(status.success());
+    assert_eq!(
+        stdout,
+        "| °C | °F | K | °R |\n\
+         | ---: | ---: | ---: | ---: |\n\
+         | -40.00 | -40.00 | 233.15 | 419.67 |\n\
+         | 30.00 | 86.00 | 303.15 | 545.67 |\n\
+         | 100.00 | 212.00 | 373.15 | 671.67 |\n"
+    );
+}
+
+#[test]
+fn prints_a_csv_table() {
+    let (status, stdout, _stderr) = run_command(&[
+        "table", "--from", "32", "--to", "32", "--scale", "f", "--format", "csv",
+    ]);
+
+    assert!(status.success());
+    assert_eq!(
+        stdout,
+        "fahrenheit,celsius,kelvin,rankine\n32.00,0.00,273.15,491.67\n"
+    );
+}
+
+#[test]
+fn table_fails_when_range_starts_below_absolute_zero() {
+    let (status, _stdout, stderr) =
+        run_command(&["table", "--from", "-300", "--to", "0", "--scale", "c"]);
+
+    assert_eq!(status.code(), Some(5));
+    assert!(stderr.contains("below absolute zero"));
+}
+
+#[test]
+fn table_fails_with_a_non_positive_step() {
+    let (status, _stdout, stderr) =
+        run_command(&["table", "--from", "0", "--to", "10", "--step", "0"]);
+
+    assert_eq!(status.code(), Some(2));
+    assert!(stderr.contains("Step must be a positive number"));
+}
+
+#[test]
+fn table_fails_with_too_many_rows() {
+    let (status, stdout, stderr) =
+        run_command(&["table", "--from", "0", "--to", "10", "--step", "1e-300"]);
+
+    assert_eq!(status.code(), Some(2));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("at most 10000 are allowed"));
+}
+
+fn run_command_with_stdin(args: &[&str], stdin: &str) -> (ExitStatus, String, String) {
+    let mut child = converter()
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("command should start successfully");
+
+    child
+        .stdin
+        .take()
+        .expect("stdin should be piped")
+        .write_all(stdin.as_bytes())
+        .expect("stdin should accept input");
+    let output = child
+        .wait_with_output()
+        .expect("command should execute successfully");
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+    (output.status, stdout, stderr)
+}
+
+#[test]
+fn batch_converts_values_from_stdin() {
+    let (status, stdout, stderr) =
+        run_command_with_stdin(&["batch", "--from", "c", "--to", "f"], "0\n100\n");
+
+    assert!(status.success());
+    assert_eq!(stdout, "32.00\n212.00\n");
+    assert!(stderr.is_empty());
+}
+
+#[test]
+fn batch_skips_bad_lines_with_line_numbers() {
+    let (status, stdout, stderr) =
+        run_command_with_stdin(&["batch", "--from", "c", "--to", "k"], "0\nhot\n10\n");
+
+    assert!(status.success());
+    assert_eq!(stdout, "273.15\n283.15\n");
+    assert!(stderr.contains("line 2:"));
+}
+
+#[test]
+fn batch_strict_mode_fails_on_bad_lines() {
+    let (status, stdout, stderr) = run_command_with_stdin(
+        &["batch", "--from", "c", "--to", "k", "--strict"],
+        "0\n-300\n10\n",
+    );
+
+    assert_eq!(status.code(), Some(5));
+    assert_eq!(stdout, "273.15\n");
+    assert!(stderr.contains("line 2:"));
+}
+
+#[test]
+fn batch_reads_a_csv_column() {
+    let (status, stdout, _stderr) = run_command_with_stdin(
+        &["batch", "--from", "f", "--to", "c", "--column", "temp_f"],
+        "id,temp_f\n1,32\n2,212\n",
+    );
+
+    assert!(status.success());
+    assert_eq!(stdout, "0.00\n100.00\n");
+}
+
+#[test]
+fn batch_reports_rows_without_the_column() {
+    let (status, stdout, stderr) = run_command_with_stdin(
+        &["batch", "--from", "f", "--to", "c", "--column", "temp_f"],
+        "id,temp_f\n1,32\n2\n3,212\n",
+    );
+
+    assert!(status.success());
+    assert_eq!(stdout, "0.00\n100.00\n");
+    assert_eq!(stderr, "line 3: The row has no temp_f field. Skipping.\n");
+
+    let (status, stdout, _stderr) = run_command_with_stdin(
+        &[
+            "batch", "--from", "f", "--to", "c", "--column", "temp_f", "--strict",
+        ],
+        "id,temp_f\n1,32\n2\n3,212\n",
+    );
+    assert_eq!(status.code(), Some(2));
+    assert_eq!(stdout, "0.00\n");
+}
+
+#[test]
+fn global_options_work_before_and_after_subcommands() {
+    let after = run_command(&["table", "--from", "0", "--to", "10", "--precision", "1"]);
+    let before = run_command(&["--precision", "1", "table", "--from", "0", "--to", "10"]);
+    assert!(before.0.success());
+    assert_eq!(before.1, after.1);
+    assert!(before.1.contains(" 10.0   50.0  283.2"));
+
+    let (status, stdout, _stderr) = run_command(&[
+        "--locale",
+        "de",
+        "heat-index",
+        "90",
+        "--scale",
+        "f",
+        "--humidity",
+        "70",
+    ]);
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "105,92 Grad Fahrenheit.");
+
+    let (status, stdout, _stderr) = run_command(&["-c", "5", "table", "--from", "0", "--to", "1"]);
+    assert_eq!(status.code(), Some(2));
+    assert!(stdout.is_empty());
+}
+
+#[test]
+fn rejects_precisions_beyond_the_maximum() {
+    for args in [
+        ["--precision", "2147483647", "-c", "1"],
+        ["--precision", "101", "-c", "1"],
+        ["--sig-figs", "4294967295", "-c", "1"],
+        ["--sig-figs", "0", "-c", "1"],
+    ] {
+        let (status, stdout, _stderr) = run_command(&args);
+        assert_eq!(status.code(), Some(2), "{:?}", args);
+        assert!(stdout.is_empty(), "{:?}", args);
+    }
+
+    let (status, stdout, _stderr) = run_command(&["--sig-figs", "100", "-c", "1"]);
+    assert!(status.success());
+    assert!(stdout.starts_with("33.8000"));
+}
+
+#[test]
+fn converts_a_quoted_expression() {
+    let (status, stdout, _stderr) = run_command(&["72°F to K"]);
+
+    assert!(status.success());
+    assert_eq!(stdout, "295.37 kelvin.\n");
+}
+
+#[test]
+fn converts_an_expression_split_across_arguments() {
+    let (status, stdout, _stderr) = run_command(&["-40", "C", "in", "F"]);
+
+    assert!(status.success());
+    assert_eq!(stdout, "-40.00 degrees fahrenheit.\n");
+}
+
+#[test]
+fn expression_errors_point_at_the_bad_token() {
+    let (status, _stdout, stderr) = run_command(&["98.6 X in C"]);
+
+    assert_eq!(status.code(), Some(3));
+    assert!(stderr.contains("Unknown unit \"X\""));
+    assert!(stderr.contains("\n  98.6 X in C\n       ^\n"));
+}
+
+#[test]
+fn converts_non_temperature_units() {
+    let (status, stdout, _stderr) = run_command(&["5 km to mi"]);
+
+    assert!(status.success());
+    assert_eq!(stdout, "3.11 mi.\n");
+}
+
+#[test]
+fn fails_when_converting_across_dimensions() {
+    let (status, _stdout, stderr) = run_command(&["5", "km", "to", "kg"]);
+
+    assert_eq!(status.code(), Some(8));
+    assert!(stderr.contains("Cannot convert km (length, L) to kg (mass, M)"));
+}
+
+#[test]
+fn converts_compound_units() {
+    let (status, stdout, _stderr) = run_command(&["1 W/(m*K) to BTU/(h*ft*°F)"]);
+
+    assert!(status.success());
+    assert_eq!(stdout, "0.58 BTU/(h*ft*°F).\n");
+}
+
+#[test]
+fn compound_dimension_mismatches_show_both_vectors() {
+    let (status, _stdout, stderr) = run_command(&["1 kg*m/s^2 to W"]);
+
+    assert_eq!(status.code(), Some(8));
+    assert!(stderr.contains("Cannot convert kg*m/s^2 (force, L·M·T⁻²) to W (power, L²·M·T⁻³)"));
+}
+
+fn write_units_file(name: &str, contents: &str) -> std::path::PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("rust-convert-{}-{}.toml", name, std::process::id()));
+    std::fs::write(&path, contents).expect("units file should be writable");
+    path
+}
+
+#[test]
+fn converts_with_units_from_a_definitions_file() {
+    let path = write_units_file(
+        "furlong",
+        "[units.fur]\nname = \"furlong\"\nunit = \"m\"\nfactor = 201.168\n\n[units.ftn]\nname = \"fortnight\"\nunit = \"d\"\nfactor = 14\n",
+    );
+    let path = path.to_str().unwrap();
+
+    let (status, stdout, _stderr) = run_command(&["--units-file", path, "10 fur to km"]);
+    assert!(status.success());
+    assert_eq!(stdout, "2.01 km.\n");
+
+    let (status, stdout, _stderr) = run_command_with_stdin(
+        &["batch", "--from", "ftn", "--to", "d", "--units-file", path],
+        "1\n2\n",
+    );
+    assert!(status.success());
+    assert_eq!(stdout, "14.00\n28.00\n");
+}
+
+#[test]
+fn reports_cyclic_unit_definitions() {
+    let path = write_units_file(
+        "cycle",
+        "[units.a]\nname = \"a\"\nunit = \"b\"\n\n[units.b]\nname = \"b\"\nunit = \"a\"\n",
+    );
+
+    let (status, _stdout, stderr) =
+        run_command(&["--units-file", path.to_str().unwrap(), "1 a to b"]);
+
+    assert_eq!(status.code(), Some(9));
+    assert!(stderr.contains("cycle"));
+}
+
+#[test]
+fn fails_when_the_units_file_is_missing() {
+    let (status, _stdout, stderr) =
+        run_command(&["--units-file", "/nonexistent/units.toml", "1 km to m"]);
+
+    assert_eq!(status.code(), Some(9));
+    assert!(stderr.contains("Could not read /nonexistent/units.toml"));
+}
+
+#[test]
+fn converts_temperature_differences_with_delta() {
+    let (status, stdout, _stderr) = run_command(&["--delta", "-c", "10"]);
+    assert!(status.success());
+    assert_eq!(stdout, "18.00 Δ°F.\n");
+
+    let (status, stdout, _stderr) = run_command(&["--delta", "-300", "K", "to", "F"]);
+    assert!(status.success());
+    assert_eq!(stdout, "-540.00 Δ°F.\n");
+}
+
+#[test]
+fn converts_differences_marked_in_the_expression() {
+    let (status, stdout, _stderr) = run_command(&["10 Δ°C to °F"]);
+
+    assert!(status.success());
+    assert_eq!(stdout, "18.00 Δ°F.\n");
+}
+
+#[test]
+fn precision_sets_the_number_of_decimals() {
+    let (status, stdout, _stderr) = run_command(&["--precision", "4", "-f", "100"]);
+    assert!(status.success());
+    assert_eq!(stdout, "37.7778 degrees celsius.\n");
+
+    let (_status, stdout, _stderr) =
+        run_command(&["--precision", "1", "--rounding", "truncate", "-f", "100"]);
+    assert_eq!(stdout, "37.7 degrees celsius.\n");
+}
+
+#[test]
+fn sig_figs_auto_matches_the_input() {
+    let (status, stdout, _stderr) = run_command(&["--sig-figs", "auto", "98.6 F to C"]);
+    assert!(status.success());
+    assert_eq!(stdout, "37.0 degrees celsius.\n");
+
+    let (_status, stdout, _stderr) = run_command(&["--sig-figs", "2", "5 km to mi"]);
+    assert_eq!(stdout, "3.1 mi.\n");
+}
+
+#[test]
+fn precision_applies_to_tables_and_batches() {
+    let (_status, stdout, _stderr) = run_command(&[
+        "table",
+        "--from",
+        "0",
+        "--to",
+        "0",
+        "--format",
+        "csv",
+        "--precision",
+        "0",
+    ]);
+    assert_eq!(stdout, "celsius,fahrenheit,kelvin,rankine\n0,32,273,492\n");
+
+    let (_status, stdout, _stderr) = run_command_with_stdin(
+        &["batch", "--from", "c", "--to", "k", "--sig-figs", "auto"],
+        "20.00\n",
+    );
+    assert_eq!(stdout, "293.2\n");
+}
+
+#[test]
+fn precision_and_sig_figs_conflict() {
+    let (status, _stdout, _stderr) =
+        run_command(&["--precision", "2", "--sig-figs", "3", "-c", "10"]);
+
+    assert_eq!(status.code(), Some(2));
+}
+
+#[test]
+fn exact_mode_writes_repeating_decimals() {
+    let (status, stdout, _stderr) = run_command(&["--exact", "-f", "100"]);
+    assert!(status.success());
+    assert_eq!(stdout, "37.(7) degrees celsius.\n");
+
+    let (_status, stdout, _stderr) =
+        run_command(&["--exact", "--notation", "fraction", "100 F to C"]);
+    assert_eq!(stdout, "340/9 degrees celsius.\n");
+
+    let (_status, stdout, _stderr) = run_command(&["--exact", "1 km/h to m/s"]);
+    assert_eq!(stdout, "0.2(7) m/s.\n");
+
+    let (_status, stdout, _stderr) = run_command(&["--exact", "--delta", "-c", "10"]);
+    assert_eq!(stdout, "18 Δ°F.\n");
+}
+
+#[test]
+fn exact_mode_reports_exponents_it_cannot_hold() {
+    let (status, stdout, stderr) = run_command(&["--exact", "-c", "1.5e-2147483648"]);
+
+    assert_eq!(status.code(), Some(4));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("too many digits to convert exactly"));
+}
+
+#[test]
+fn exact_mode_rejects_rounding_options() {
+    let (status, _stdout, _stderr) = run_command(&["--exact", "--precision", "2", "-f", "1"]);
+    assert_eq!(status.code(), Some(2));
+
+    let (status, _stdout, _stderr) = run_command(&["--notation", "fraction", "-f", "1"]);
+    assert_eq!(status.code(), Some(2));
+
+    let (status, _stdout, _stderr) = run_command(&["--exact", "-f", "-500"]);
+    assert_eq!(status.code(), Some(5));
+}
+
+#[test]
+fn locale_option_reads_and_writes_decimal_commas() {
+    let (status, stdout, _stderr) = run_command(&["--locale", "de", "-c", "36,6"]);
+    assert!(status.success());
+    assert_eq!(stdout, "97,88 Grad Fahrenheit.\n");
+
+    let (_status, stdout, _stderr) = run_command(&["--locale", "fr", "36,6 C to F"]);
+    assert_eq!(stdout, "97,88 degrés Fahrenheit.\n");
+
+    let (_status, stdout, _stderr) = run_command(&["--locale", "en", "12345 km to m"]);
+    assert_eq!(stdout, "12,345,000.00 m.\n");
+}
+
+#[test]
+fn locale_defaults_to_the_environment() {
+    let output = converter()
+        .env("LANG", "de_DE.UTF-8")
+        .args(["-f", "100"])
+        .output()
+        .expect("command should execute successfully");
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "37,78 Grad Celsius.\n"
+    );
+}
+
+#[test]
+fn batch_output_is_grouped_only_for_an_explicit_locale() {
+    let mut child = converter()
+        .env("LANG", "en_US.UTF-8")
+        .args(["batch", "--from", "c", "--to", "f"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("command should execute successfully");
+    child
+        .stdin
+        .take()
+        .expect("stdin should be piped")
+        .write_all(b"10000\n")
+        .expect("stdin should accept input");
+    let output = child
+        .wait_with_output()
+        .expect("command should execute successfully");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "18032.00\n");
+
+    let (_status, stdout, _stderr) = run_command_with_stdin(
+        &["--locale", "en", "batch", "--from", "c", "--to", "f"],
+        "10000\n",
+    );
+    assert_eq!(stdout, "18,032.00\n");
+}
+
+#[test]
+fn french_numbers_may_be_grouped_with_plain_spaces() {
+    let (status, stdout, _stderr) = run_command(&["--locale", "fr", "1 000 m to km"]);
+    assert!(status.success());
+    assert_eq!(stdout, "1,00 km.\n");
+}
+
+#[test]
+fn kelvin_takes_no_degree_word() {
+    let (_status, stdout, _stderr) = run_command(&["--locale", "de", "0 C to K"]);
+    assert_eq!(stdout, "273,15 Kelvin.\n");
+
+    let (status, _stdout, stderr) = run_command(&["-k", "-5"]);
+    assert_eq!(status.code(), Some(5));
+    assert_eq!(stderr, "-5 kelvin is below absolute zero (0 kelvin).\n");
+}
+
+#[test]
+fn errors_are_translated() {
+    let (status, _stdout, stderr) = run_command(&["--locale", "de", "-c", "-500"]);
+    assert_eq!(status.code(), Some(5));
+    assert_eq!(
+        stderr,
+        "-500 Grad Celsius liegt unter dem absoluten Nullpunkt (-273,15 Grad Celsius).\n"
+    );
+
+    let (status, _stdout, stderr) = run_command(&["--locale", "fr", "--bogus"]);
+    assert_eq!(status.code(), Some(2));
+    assert!(stderr.starts_with("Argument inattendu : --bogus.\nUtilisation : rust-convert"));
+}
+
+#[test]
+fn comma_decimals_are_rejected_without_a_locale() {
+    let (status, _stdout, stderr) = run_command(&["-c", "36,6"]);
+
+    assert_eq!(status.code(), Some(3));
+    assert!(stderr.contains("Problem parsing value: 36,6."));
+}
+
+#[test]
+fn interactive_mode_reuses_the_last_units_for_bare_numbers() {
+    let (status, stdout, stderr) = run_command_with_stdin(
+        &["--interactive"],
+        "72 F to C\n100\n:precision 1\n212\n:scale k\n0\n:to F\n0\n",
+    );
+
+    assert!(status.success());
+    assert!(stderr.is_empty());
+    assert_eq!(
+        stdout,
+        "22.22 degrees celsius.\n\
+         37.78 degrees celsius.\n\
+         100.0 degrees celsius.\n\
+         -273.2 degrees celsius.\n\
+         -459.7 degrees fahrenheit.\n"
+    );
+}
+
+#[test]
+fn interactive_mode_reports_errors_and_keeps_going() {
+    let (status, stdout, stderr) =
+        run_command_with_stdin(&["-i"], "100\n:bogus\n5 km to kg\n5 km to mi\n:quit\n1\n");
+
+    assert!(status.success());
+    assert_eq!(stdout, "3.11 mi.\n");
+    assert!(stderr.contains("No units to convert between yet."));
+    assert!(stderr.contains("Unknown command: :bogus."));
+    assert!(stderr.contains("Cannot convert km"));
+}
+
+#[test]
+fn json_format_writes_an_object_per_result() {
+    let (status, stdout, _stderr) = run_command(&["-f", "212", "--format", "json"]);
+
+    assert!(status.success());
+    assert_eq!(
+        stdout.trim(),
+        r#"{"input_value": 212, "input_scale": "fahrenheit", "output_value": 100, "output_scale": "celsius", "delta": false, "formatted": "100.00 degrees celsius."}"#
+    );
+}
+
+#[test]
+fn json_format_applies_to_expressions() {
+    let (status, stdout, _stderr) = run_command(&["--format", "json", "1 mi to km"]);
+
+    assert!(status.success());
+    assert_eq!(
+        stdout.trim(),
+        r#"{"input_value": 1, "input_scale": "mile", "output_value": 1.609344, "output_scale": "kilometre", "delta": false, "formatted": "1.61 km."}"#
+    );
+}
+
+#[test]
+fn json_format_writes_errors_to_stderr() {
+    let (status, stdout, stderr) = run_command(&["-f", "-500", "--format", "json"]);
+
+    assert_eq!(status.code(), Some(5));
+    assert!(stdout.is_empty());
+    assert_eq!(
+        stderr.trim(),
+        r#"{"error": "below_absolute_zero", "message": "-500 degrees fahrenheit is below absolute zero (-459.67 degrees fahrenheit).", "exit_code": 5}"#
+    );
+}
+
+#[test]
+fn json_format_reports_usage_errors() {
+    let (status, _stdout, stderr) = run_command(&["--format", "json", "--bogus"]);
+
+    assert_eq!(status.code(), Some(2));
+    assert!(stderr.starts_with(r#"{"error": "unexpected_argument""#));
+    assert!(!stderr.contains("Usage"));
+}
+
+#[test]
+fn historical_scales_convert_to_celsius() {
+    let cases = [
+        (["--reaumur", "80"], "100.00 degrees celsius."),
+        (["--delisle", "0"], "100.00 degrees celsius."),
+        (["--newton", "33"], "100.00 degrees celsius."),
+        (["--romer", "7.5"], "0.00 degrees celsius."),
+    ];
+
+    for (args, expected) in cases {
+        let (status, stdout, _stderr) = run_command(&args);
+        assert!(status.success());
+        assert_eq!(stdout.trim(), expected);
+    }
+}
+
+#[test]
+fn delisle_values_above_absolute_zero_are_rejected() {
+    let (status, _stdout, stderr) = run_command(&["--delisle", "600"]);
+
+    assert_eq!(status.code(), Some(5));
+    assert!(
+        stderr.contains("600 degrees delisle is below absolute zero (559.725 degrees delisle).")
+    );
+}
+
+#[test]
+fn weather_subcommands_answer_on_the_input_scale() {
+    let cases: [(&[&str], &str); 4] = [
+        (
+            &["heat-index", "90", "--scale", "f", "--humidity", "70"],
+            "105.92 degrees fahrenheit.",
+        ),
+        (
+            &["wind-chill", "-10", "--wind", "20"],
+            "-17.84 degrees celsius.",
+        ),
+        (&["dew-point", "20", "--rh", "50"], "9.26 degrees celsius."),
+        (
+            &["humidex", "30", "--humidity", "39.8"],
+            "33.87 degrees celsius.",
+        ),
+    ];
+
+    for (args, expected) in cases {
+        let (status, stdout, stderr) = run_command(args);
+        assert!(status.success(), "{:?}", args);
+        assert!(stderr.is_empty(), "{:?}: {}", args, stderr);
+        assert_eq!(stdout.trim(), expected);
+    }
+}
+
+#[test]
+fn weather_subcommands_warn_outside_the_formula_range() {
+    let (status, stdout, stderr) = run_command(&[
+        "wind-chill",
+        "60",
+        "-s",
+        "f",
+        "--wind",
+        "10",
+        "--wind-unit",
+        "mph",
+    ]);
+
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "60.00 degrees fahrenheit.");
+    assert_eq!(
+        stderr.trim(),
+        "Warning: Wind chill is only defined at 50 °F (10 °C) and below; showing the air temperature."
+    );
+
+    let (status, stdout, _stderr) = run_command(&["wind-chill", "20", "--wind", "2"]);
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "20.00 degrees celsius.");
+
+    let (status, stdout, _stderr) =
+        run_command(&["humidex", "86", "-s", "f", "--humidity", "39.8"]);
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "92.97 degrees fahrenheit.");
+}
+
+#[test]
+fn weather_subcommands_honour_the_json_format() {
+    let (status, stdout, _stderr) = run_command(&[
+        "--format",
+        "json",
+        "heat-index",
+        "90",
+        "-s",
+        "f",
+        "--rh",
+        "70",
+    ]);
+    assert!(status.success());
+    assert!(
+        stdout.starts_with(
+            r#"{"input_value": 90, "input_scale": "fahrenheit", "output_value": 105.92"#
+        )
+    );
+    assert!(stdout.trim_end().ends_with(
+        r#""output_scale": "fahrenheit", "delta": false, "formatted": "105.92 degrees fahrenheit."}"#
+    ));
+
+    let (status, _stdout, stderr) =
+        run_command(&["--format", "json", "dew-point", "20", "--rh", "120"]);
+    assert_eq!(status.code(), Some(3));
+    assert!(stderr.starts_with(r#"{"error": "invalid_humidity""#));
+}
+
+#[test]
+fn heat_index_rejects_air_too_hot_for_the_formula() {
+    let (status, stdout, stderr) = run_command(&["heat-index", "70", "--rh", "50"]);
+
+    assert_eq!(status.code(), Some(3));
+    assert!(stdout.is_empty());
+    assert_eq!(
+        stderr,
+        "The heat index is only worked out up to 135 °F (57.2 °C), got 70 °C.\n"
+    );
+}
+
+#[test]
+fn weather_subcommands_reject_impossible_humidity() {
+    let (status, stdout, stderr) = run_command(&["dew-point", "20", "--humidity", "120"]);
+
+    assert_eq!(status.code(), Some(3));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("Relative humidity must be between 0 and 100%, got 120."));
+}
+
+#[test]
+fn boiling_point_follows_altitude_and_pressure() {
+    let cases: [(&[&str], &str); 3] = [
+        (
+            &["boiling-point", "--altitude", "1609"],
+            "94.66 degrees celsius.",
+        ),
+        (
+            &[
+                "boiling-point",
+                "--altitude",
+                "5280",
+                "--altitude-unit",
+                "ft",
+                "-s",
+                "f",
+            ],
+            "202.39 degrees fahrenheit.",
+        ),
+        (
+            &["boiling-point", "--pressure", "2", "--pressure-unit", "atm"],
+            "120.75 degrees celsius.",
+        ),
+    ];
+
+    for (args, expected) in cases {
+        let (status, stdout, stderr) = run_command(args);
+        assert!(status.success(), "{:?}", args);
+        assert_eq!(stdout.trim(), expected, "{:?}", args);
+        assert!(stderr.is_empty(), "{:?}", args);
+    }
+}
+
+#[test]
+fn boiling_point_honours_the_json_format() {
+    let (status, stdout, _stderr) =
+        run_command(&["--format", "json", "boiling-point", "--altitude", "1609"]);
+    assert!(status.success());
+    assert!(
+        stdout.starts_with(r#"{"input_value": 1609, "input_scale": "metre", "output_value": 94.6"#)
+    );
+    assert!(stdout.trim_end().ends_with(
+        r#""output_scale": "celsius", "delta": false, "formatted": "94.66 degrees celsius."}"#
+    ));
+
+    let (status, stdout, _stderr) = run_command(&[
+        "--format",
+        "json",
+        "boiling-point",
+        "--boils-at",
+        "100",
+        "--pressure-unit",
+        "kPa",
+    ]);
+    assert!(status.success());
+    assert!(
+        stdout
+            .starts_with(r#"{"input_value": 100, "input_scale": "celsius", "output_value": 101."#)
+    );
+    assert!(
+        stdout.contains(r#""output_scale": "kilopascal""#),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout
+            .trim_end()
+            .ends_with(r#""formatted": "101.34 kPa."}"#),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn subcommands_without_reports_reject_the_json_format() {
+    for args in [
+        &["--format", "json", "thermocouple", "--type", "k", "4.096"][..],
+        &["--format", "json", "rtd", "138.51"],
+        &["--format", "json", "table", "--from", "0", "--to", "10"],
+    ] {
+        let (status, stdout, stderr) = run_command(args);
+        assert_eq!(status.code(), Some(2), "{:?}", args);
+        assert!(stdout.is_empty());
+        assert!(stderr.starts_with(r#"{"error": "usage""#), "{}", stderr);
+    }
+}
+
+#[test]
+fn boiling_point_finds_the_pressure_for_a_temperature() {
+    let (status, stdout, _stderr) = run_command(&[
+        "boiling-point",
+        "--boils-at",
+        "212",
+        "-s",
+        "f",
+        "--pressure-unit",
+        "psi",
+    ]);
+
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "14.70 psi.");
+}
+
+#[test]
+fn boiling_point_rejects_water_that_cannot_boil() {
+    let (status, stdout, stderr) = run_command(&["boiling-point", "--boils-at", "400"]);
+
+    assert_eq!(status.code(), Some(3));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("Water can't boil at 400.00 °C"));
+
+    let (status, _stdout, _stderr) =
+        run_command(&["boiling-point", "--altitude", "1", "--pressure", "2"]);
+    assert_eq!(status.code(), Some(2));
+    let (status, _stdout, stderr) = run_command(&[
+        "boiling-point",
+        "--altitude",
+        "100",
+        "--altitude-unit",
+        "km",
+    ]);
+    assert_eq!(status.code(), Some(3));
+    assert_eq!(
+        stderr,
+        "The standard atmosphere has no air left at an altitude of 100000 m.\n"
+    );
+}
+
+#[test]
+fn thermocouple_converts_millivolts_with_cold_junction_compensation() {
+    let (status, stdout, _stderr) = run_command(&["thermocouple", "--type", "k", "4.096"]);
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "99.96 degrees celsius.");
+
+    let (status, stdout, _stderr) = run_command(&[
+        "thermocouple",
+        "-t",
+        "k",
+        "3.096",
+        "--cold-junction",
+        "77",
+        "--scale",
+        "f",
+    ]);
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "211.94 degrees fahrenheit.");
+}
+
+#[test]
+fn thermocouple_reverse_gives_the_nist_voltage() {
+    let (status, stdout, _stderr) = run_command(&[
+        "thermocouple",
+        "-t",
+        "J",
+        "100",
+        "--reverse",
+        "--precision",
+        "3",
+    ]);
+
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "5.269 mV.");
+}
+
+#[test]
+fn rtd_converts_resistance_both_ways() {
+    let (status, stdout, _stderr) = run_command(&["rtd", "138.51"]);
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "100.01 degrees celsius.");
+
+    let (status, stdout, _stderr) = run_command(&["rtd", "100", "--reverse"]);
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "138.51 Ω.");
+
+    let (status, stdout, _stderr) = run_command(&["rtd", "18.52"]);
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "-200.00 degrees celsius.");
+    for r0 in ["0", "inf", "NaN"] {
+        let (status, stdout, stderr) = run_command(&["rtd", "0", "--r0", r0]);
+        assert_eq!(status.code(), Some(2), "{}", r0);
+        assert!(stdout.is_empty());
+        assert!(
+            stderr.contains("expected a resistance above zero"),
+            "{}",
+            stderr
+        );
+    }
+}
+
+#[test]
+fn sensor_readings_out_of_range_exit_with_their_own_code() {
+    let (status, _stdout, stderr) = run_command(&["thermocouple", "-t", "t", "30"]);
+    assert_eq!(status.code(), Some(10));
+    assert!(stderr.contains("30 mV is outside the type T thermocouple's range"));
+
+    let (status, _stdout, stderr) = run_command(&["rtd", "5"]);
+    assert_eq!(status.code(), Some(10));
+    assert!(stderr.contains("5 Ω is outside the RTD range of 18.52 Ω to 390.48 Ω."));
+}
+
+/// Builds a sysfs tree with one thermal zone and one hwmon sensor.
+fn write_fake_sysfs(name: &str) -> std::path::PathBuf {
+    let root = std::env::temp_dir().join(format!("rust-convert-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    for (path, contents) in [
+        ("class/thermal/thermal_zone0/type", "acpitz\n"),
+        ("class/thermal/thermal_zone0/temp", "25000\n"),
+        ("class/hwmon/hwmon0/name", "nvme\n"),
+        ("class/hwmon/hwmon0/temp1_label", "Composite\n"),
+        ("class/hwmon/hwmon0/temp1_input", "37850\n"),
+        ("class/hwmon/hwmon0/temp2_input", "unavailable\n"),
+    ] {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).expect("sysfs dir should be writable");
+        std::fs::write(path, contents).expect("sysfs file should be writable");
+    }
+    root
+}
+
+#[test]
+fn sensors_lists_readings_from_the_given_root() {
+    let root = write_fake_sysfs("sensors-text");
+    let root = root.to_str().unwrap();
+
+    let (status, stdout, stderr) = run_command(&["sensors", "--root", root, "--scale", "f"]);
+
+    assert!(status.success());
+    assert_eq!(
+        stdout,
+        "sensor              °F\nacpitz           77.00\nnvme Composite  100.13\n"
+    );
+    assert!(stderr.contains("temp2_input: \"unavailable\" is not a temperature"));
+    assert!(stderr.trim_end().ends_with("Skipping."));
+}
+
+#[test]
+fn sensors_writes_json() {
+    let root = write_fake_sysfs("sensors-json");
+
+    let (status, stdout, _stderr) = run_command(&[
+        "sensors",
+        "--root",
+        root.to_str().unwrap(),
+        "--format",
+        "json",
+    ]);
+
+    assert!(status.success());
+    let zone = root.join("class/thermal/thermal_zone0/temp");
+    assert!(stdout.starts_with(&format!(
+        "[\n  {{ \"sensor\": \"acpitz\", \"path\": \"{}\", \"celsius\": 25.00 }},\n",
+        zone.display()
+    )));
+    assert!(stdout.contains("\"sensor\": \"nvme Composite\""));
+}
+
+#[test]
+fn sensors_fails_when_there_are_none() {
+    let root = std::env::temp_dir().join(format!("rust-convert-no-sysfs-{}", std::process::id()));
+
+    let (status, _stdout, stderr) = run_command(&["sensors", "--root", root.to_str().unwrap()]);
+
+    assert_eq!(status.code(), Some(1));
+    assert!(stderr.contains("No temperature sensors found under"));
+}
+
+#[test]
+fn watch_alerts_once_per_crossing() {
+    let (status, stdout, stderr) = run_command_with_stdin(
+        &["watch", "--above", "80", "--hysteresis", "2"],
+        "70\n85\n79\n77\n\n86\n",
+    );
+
+    assert_eq!(status.code(), Some(11));
+    assert_eq!(stdout, "70.00\n85.00\n79.00\n77.00\n86.00\n");
+    assert_eq!(
+        stderr,
+        "Alert: 85.00 °C is above 80.00 °C.\n\
+         Cleared: 77.00 °C is no longer above 80.00 °C.\n\
+         Alert: 86.00 °C is above 80.00 °C.\n\
+         2 alerts fired.\n"
+    );
+}
+
+#[test]
+fn watch_succeeds_when_no_alert_fires() {
+    let (status, stdout, stderr) = run_command_with_stdin(
+        &["watch", "--from", "f", "--below", "0", "--count", "2"],
+        "50\noops\n32\n14\n",
+    );
+
+    assert!(status.success());
+    assert_eq!(stdout, "10.00\n");
+    assert_eq!(stderr, "line 2: Problem parsing value: oops. Skipping.\n");
+}
+
+#[test]
+fn watch_logs_sensor_alerts_to_a_file() {
+    let root = write_fake_sysfs("watch-sensor");
+    let sensor = root.join("class/hwmon/hwmon0/temp1_input");
+    let log = root.join("alerts.log");
+
+    let (status, stdout, stderr) = run_command(&[
+        "watch",
+        "--sensor",
+        sensor.to_str().unwrap(),
+        "--to",
+        "f",
+        "--above",
+        "95",
+        "--interval",
+        "0",
+        "--count",
+        "2",
+        "--alert-log",
+        log.to_str().unwrap(),
+    ]);
+
+    assert_eq!(status.code(), Some(11));
+    assert_eq!(stdout, "100.13\n100.13\n");
+    assert_eq!(stderr, "1 alert fired.\n");
+    let logged = std::fs::read_to_string(log).unwrap();
+    assert_eq!(logged.lines().count(), 1);
+    assert!(logged.ends_with(" Alert: 100.13 °F is above 95.00 °F.\n"));
+}
+
+#[cfg(unix)]
+#[test]
+fn watch_runs_the_alert_command() {
+    let root = write_fake_sysfs("watch-hook");
+    let file = root.join("reading.txt");
+    std::fs::write(&file, "-10\n").unwrap();
+    let hook = root.join("hook.txt");
+    let command = format!(
+        "echo \"$RUST_CONVERT_ALERT $RUST_CONVERT_SIDE $RUST_CONVERT_LIMIT $RUST_CONVERT_SCALE\" >> {}",
+        hook.display()
+    );
+
+    let (status, _stdout, stderr) = run_command(&[
+        "watch",
+        "--file",
+        file.to_str().unwrap(),
+        "--below",
+        "-5",
+        "--count",
+        "1",
+        "--alert-command",
+        &command,
+    ]);
+
+    assert_eq!(status.code(), Some(11));
+    assert_eq!(stderr, "1 alert fired.\n");
+    assert_eq!(
+        std::fs::read_to_string(hook).unwrap(),
+        "triggered below -5 celsius\n"
+    );
+}
+
+fn write_calibration_file(name: &str, contents: &str) -> std::path::PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("rust-convert-{}-{}.csv", name, std::process::id()));
+    std::fs::write(&path, contents).expect("calibration file should be writable");
+    path
+}
+
+#[test]
+fn calibration_corrects_readings_before_converting() {
+    let path = write_calibration_file("probe", "raw,reference\n0.8,0\n100.8,100\n");
+    let path = path.to_str().unwrap();
+
+    let (status, stdout, _stderr) = run_command(&["--calibration", path, "-c", "0.8"]);
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "32.00 degrees fahrenheit.");
+
+    let (status, stdout, _stderr) = run_command(&["--calibration", path, "37.8 C to F"]);
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "98.60 degrees fahrenheit.");
+
+    let (status, stdout, _stderr) = run_command_with_stdin(
+        &["batch", "--from", "c", "--to", "k", "--calibration", path],
+        "0.8\n",
+    );
+    assert!(status.success());
+    assert_eq!(stdout, "273.15\n");
+}
+
+#[test]
+fn calibration_corrects_thermocouple_readings_in_celsius() {
+    let path = write_calibration_file("thermocouple", "0,1\n100,101\n");
+    let path = path.to_str().unwrap();
+
+    let (status, stdout, _stderr) = run_command(&["thermocouple", "-t", "k", "4.096"]);
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "99.96 degrees celsius.");
+
+    let (status, stdout, _stderr) =
+        run_command(&["--calibration", path, "thermocouple", "-t", "k", "4.096"]);
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "100.96 degrees celsius.");
+}
+
+#[test]
+fn calibration_rejects_values_that_are_not_temperatures() {
+    let path = write_calibration_file("length", "0,1\n100,101\n");
+    let path = path.to_str().unwrap();
+
+    let (status, stdout, stderr) = run_command(&["--calibration", path, "5 km to mi"]);
+    assert_eq!(status.code(), Some(12));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("can't be used for values in km"));
+
+    let (status, _stdout, _stderr) = run_command_with_stdin(
+        &["batch", "--from", "km", "--to", "mi", "--calibration", path],
+        "5\n",
+    );
+    assert_eq!(status.code(), Some(12));
+}
+
+#[test]
+fn calibration_supports_piecewise_fits() {
+    let path = write_calibration_file("piecewise", "0,0.5\n50,49\n100,98\n");
+
+    let (status, stdout, _stderr) = run_command(&[
+        "dew-point",
+        "25",
+        "--humidity",
+        "100",
+        "--calibration",
+        path.to_str().unwrap(),
+        "--fit",
+        "piecewise",
+    ]);
+
+    assert!(status.success());
+    assert_eq!(stdout.trim(), "24.75 degrees celsius.");
+}
+
+#[test]
+fn bad_calibration_files_exit_with_their_own_code() {
+    let path = write_calibration_file("bad-calibration", "0.8,0\nhot,100\n");
+
+    let (status, _stdout, stderr) =
+        run_command(&["--calibration", path.to_str().unwrap(), "-c", "20"]);
+
+    assert_eq!(status.code(), Some(12));
+    assert!(stderr.contains("Calibration line 2: expected a raw and a reference value"));
+}
+
+#[test]
+fn calibration_conflicts_with_exact() {
+    let path = write_calibration_file("exact-calibration", "0,0\n1,1\n");
+
+    let (status, _stdout, _stderr) = run_command(&[
+        "--exact",
+        "--calibration",
+        path.to_str().unwrap(),
+        "-c",
+        "20",
+    ]);
+
+    assert_eq!(status.code(), Some(2));
+}
+
+#[test]
+fn metar_reads_reports_from_stdin() {
+    let (status, stdout, stderr) = run_command_with_stdin(
+        &["metar", "--scale", "f"],
+        "METAR KJFK 121651Z 31015G25KT 10SM M05/M12 A3012 RMK AO2 T10511122\n\
+         \n\
+         EGLL 121650Z 24010KT 9999 SCT030 12/08 Q1015=\n\
+         EGLL 121720Z NIL=\n\
+         AAXX 01004 88889 12782 61506 10094 20047 30111\n",
+    );
+
+    assert!(status.success());
+    assert_eq!(
+        stdout,
+        "station   T °F  Td °F\n\
+         KJFK     22.82  10.04\n\
+         EGLL     53.60  46.40\n\
+         88889    48.92  40.46\n"
+    );
+    assert_eq!(
+        stderr,
+        "line 4: No temperature group in the report. Skipping.\n"
+    );
+}
+
+#[test]
+fn metar_writes_json() {
+    let (status, stdout, _stderr) =
+        run_command_with_stdin(&["metar", "--format", "json"], "ENSB 121650Z M02/ Q1002\n");
+
+    assert!(status.success());
+    assert_eq!(
+        stdout,
+        "[\n  { \"station\": \"ENSB\", \"scale\": \"celsius\", \"temperature\": -2.00, \"dew_point\": null, \"precise\": false }\n]\n"
+    );
+}
+
+#[test]
+fn color_prints_hex_rgb_and_chromaticity() {
+    let (status, stdout, stderr) = run_command(&["color", "1000", "2700", "6500"]);
+
+    assert!(status.success());
+    // Not a terminal, so no swatches.
+    assert_eq!(
+        stdout,
+        "      K  hex      red  green  blue       x       y\n\
+         1000.00  #ff1700  255     23     0  0.6531  0.3447\n\
+         2700.00  #ffad58  255    173    88  0.4600  0.4110\n\
+         6500.00  #fff9fe  255    249   254  0.3135  0.3237\n"
+    );
+    assert!(stderr.is_empty());
+}
+
+#[test]
+fn color_reads_other_scales_and_writes_csv() {
+    let (status, stdout, _stderr) =
+        run_command(&["color", "2426.85", "--scale", "c", "--format", "csv"]);
+
+    assert!(status.success());
+    assert_eq!(
+        stdout,
+        "scale,temperature,hex,red,green,blue,x,y\n\
+         celsius,2426.85,#ffad58,255,173,88,0.4600,0.4110\n"
+    );
+}
+
+#[test]
+fn color_rejects_temperatures_outside_the_range() {
+    let (status, stdout, stderr) = run_command(&["color", "6500", "500"]);
+
+    assert_eq!(status.code(), Some(10));
+    assert!(stdout.is_empty());
+    assert_eq!(
+        stderr.trim(),
+        "500 K is outside the color temperature range of 1000 K to 40000 K."
+    );
+}
+
+#[test]
+fn doc_converts_json_fields_and_keeps_the_rest() {
+    let (status, stdout, stderr) = run_command_with_stdin(
+        &[
+            "doc",
+            "--path",
+            "$.readings[*].temp_f",
+            "--from",
+            "f",
+            "--to",
+            "c",
+            "--rename",
+            "temp_c",
+        ],
+        "{\"site\": \"north\", \"readings\": [{\"temp_f\": 212}, {\"temp_f\": \"hot\"}]}\n",
+    );
+
+    assert!(status.success());
+    assert_eq!(
+        stdout,
+        "{\"site\": \"north\", \"readings\": [{\"temp_c\": 100.00}, {\"temp_f\": \"hot\"}]}\n"
+    );
+    assert_eq!(
+        stderr,
+        "$.readings[1].temp_f: Problem parsing value: hot. Skipping.\n"
+    );
+}
+
+#[test]
+fn doc_adds_a_csv_column() {
+    let (status, stdout, _stderr) = run_command_with_stdin(
+        &[
+            "doc", "--column", "temp_f", "--from", "f", "--to", "c", "--add", "temp_c",
+        ],
+        "id,temp_f,note\n1,32,\"cold, dry\"\n",
+    );
+
+    assert!(status.success());
+    assert_eq!(stdout, "id,temp_f,temp_c,note\n1,32,0.00,\"cold, dry\"\n");
+}
+
+#[test]
+fn doc_reports_invalid_json() {
+    let (status, _stdout, stderr) = run_command_with_stdin(
+        &["doc", "--path", "$.t", "--from", "c", "--to", "k"],
+        "{\"t\": 1,}",
+    );
+
+    assert_eq!(status.code(), Some(3));
+    assert!(stderr.contains("Invalid JSON at line 1, column 9."));
+}
+
+#[test]
+fn doc_needs_a_path_or_a_column() {
+    let (status, _stdout, _stderr) =
+        run_command_with_stdin(&["doc", "--from", "c", "--to", "k"], "{}");
+    assert_eq!(status.code(), Some(2));
+
+    let (status, _stdout, _stderr) = run_command_with_stdin(
+        &[
+            "doc", "--path", "$[*]", "--from", "c", "--to", "k", "--add", "kelvin",
+        ],
+        "[]",
+    );
+    assert_eq!(status.code(), Some(2));
+}
+
+#[test]
+fn annotate_writes_conversions_next_to_temperatures() {
+    let (status, stdout, _stderr) = run_command_with_stdin(
+        &["annotate"],
+        "Bake at 350°F.\nLows of -5 C, highs of 20-25 °C (68-77°F).\n",
+    );
+
+    assert!(status.success());
+    assert_eq!(
+        stdout,
+        "Bake at 350°F (177°C).\nLows of -5 C (23°F), highs of 20-25 °C (68-77°F).\n"
+    );
+}
+
+#[test]
+fn annotate_dry_run_lists_byte_offsets() {
+    let (status, stdout, _stderr) = run_command_with_stdin(
+        &[
+            "annotate",
+            "--dry-run",
+            "--to",
+            "k",
+            "--template",
+            "{value} {scale}",
+        ],
+        "Bake at 350°F.\nLows of -5 C.\n",
+    );
+
+    assert!(status.success());
+    assert_eq!(
+        stdout,
+        "8-14: 350°F -> 450 kelvin\n24-28: -5 C -> 268 kelvin\n"
+    );
+}
+
+#[test]
+fn annotate_rejects_unknown_placeholders() {
+    let (status, _stdout, stderr) =
+        run_command_with_stdin(&["annotate", "--template", "{kelvin}"], "");
+
+    assert_eq!(status.code(), Some(2));
+    assert!(stderr.contains("Unknown placeholder {kelvin}."));
+}